@@ -1,6 +1,6 @@
 use crate::bits::Bits;
 use crate::signed::Signed;
-use num_bigint::{BigInt, Sign};
+use num_bigint::{BigInt, BigUint, Sign};
 use std::fmt::{Display, Formatter, LowerHex};
 
 /// The BlackBox struct provides a way to wrap a blackbox,
@@ -583,6 +583,17 @@ impl Default for Verilog {
     }
 }
 
+/// The place in the Rust source where the HDL kernel of a block is defined.
+/// This is recorded by `#[hdl_gen]`, and used to point at the block when
+/// reporting problems found by the checks.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SourceLocation {
+    /// The source file (as given by `file!()`)
+    pub file: &'static str,
+    /// The line of the HDL kernel function
+    pub line: u32,
+}
+
 #[doc(hidden)]
 pub type VerilogBlock = Vec<VerilogStatement>;
 
@@ -685,6 +696,12 @@ impl VerilogLiteral {
             _ => panic!("Loop index is too large!"),
         }
     }
+    /// The bit pattern of the literal (negative values are stored in two's complement).
+    pub fn as_biguint(&self) -> BigUint {
+        let modulus = BigInt::from(1) << self.bits;
+        let val = ((&self.val % &modulus) + &modulus) % &modulus;
+        val.to_biguint().unwrap()
+    }
 }
 
 impl From<bool> for VerilogLiteral {
@@ -780,6 +797,11 @@ pub enum VerilogExpression {
         Box<VerilogExpression>,
         Box<VerilogExpression>,
     ),
+    Ternary(
+        Box<VerilogExpression>,
+        Box<VerilogExpression>,
+        Box<VerilogExpression>,
+    ),
 }
 
 #[doc(hidden)]
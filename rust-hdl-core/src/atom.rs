@@ -2,6 +2,7 @@ use crate::ast::VerilogLiteral;
 use crate::constraint::PinConstraint;
 use crate::synth::VCDValue;
 use crate::type_descriptor::{TypeDescriptor, TypeKind};
+use num_bigint::BigUint;
 
 #[doc(hidden)]
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -44,6 +45,28 @@ pub trait Atom {
     fn constraints(&self) -> Vec<PinConstraint>;
 }
 
+/// An [Atom] that can be driven from outside of the circuit.  Values are
+/// exchanged as bit patterns, laid out as in the generated Verilog.
+#[doc(hidden)]
+pub trait AtomMut: Atom {
+    /// The value that will be latched on the next update
+    fn next_bits(&self) -> BigUint;
+    /// Drive the atom with a new value, and latch it immediately.  Returns `true`
+    /// if the value of the atom changed.  Invalid bit patterns are ignored.
+    fn set_bits(&mut self, bits: &BigUint) -> bool;
+    /// Set the value that will be latched on the next update (like assigning to `next`
+    /// in a testbench).  Returns `false` if the bit pattern is not a valid value.
+    fn set_next_bits(&mut self, bits: &BigUint) -> bool;
+    /// Force the atom to hold the given value, regardless of what drives it, until it
+    /// is released.  Returns `false` if the bit pattern is not a valid value.
+    fn force_bits(&mut self, bits: &BigUint) -> bool;
+    /// Release a forced atom, so that it follows its driver again
+    fn release(&mut self);
+    /// Make every bit of the atom unknown, if its type supports that.  Returns `false`
+    /// for two-state types.
+    fn set_unknown(&mut self) -> bool;
+}
+
 pub fn is_atom_an_enum(atom: &dyn Atom) -> bool {
     matches!(atom.descriptor().kind, TypeKind::Enum(_))
 }
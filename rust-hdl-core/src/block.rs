@@ -1,5 +1,5 @@
 use crate::logic::Logic;
-use crate::probe::Probe;
+use crate::probe::{Probe, ProbeMut};
 
 /// The [Block] trait is required for all circuitry that
 /// can be simulated by RustHDL.  If you want to be able
@@ -14,8 +14,35 @@ pub trait Block: Logic {
     fn update_all(&mut self);
     /// Returns `true` if anything in the circuit has changed (outputs or internal state)
     fn has_changed(&self) -> bool;
+    /// Returns `true` if one of the ports of the circuit changed on the last update.  The
+    /// ports are the signals that the owner of the circuit can read: a signal itself, the
+    /// signals of an interface, and the signals and interfaces of a block (but not those of
+    /// the blocks inside it).  The default is conservative and always returns `true`.
+    fn has_changed_ports(&self) -> bool {
+        true
+    }
+    /// Returns `true` if one of the signals of the circuit changed on the last update, when
+    /// it is part of the interface of a block (i.e., a signal, or an interface, but not a
+    /// block).  The default is conservative and always returns `true`.
+    fn has_changed_interface(&self) -> bool {
+        true
+    }
+    /// Propogate changes from inputs to outputs, but only re-evaluate the blocks for which
+    /// one of the signals they read (their own ports, and the ports of the blocks inside them)
+    /// changed on the last update.  The signals are still latched as in [Block::update_all].
+    /// Once the circuit has been updated with [Block::update_all], the result is identical.
+    fn update_pending(&mut self) {
+        self.update_all()
+    }
     /// The visitor pattern - allows a circuit to be probed by a [Probe] struct.
     fn accept(&self, name: &str, probe: &mut dyn Probe);
+    /// The visitor pattern, with mutable access to the atoms of the circuit.  The
+    /// default does not visit anything.
+    fn accept_mut(&mut self, _name: &str, _probe: &mut dyn ProbeMut) {}
+    /// The Rust type of the circuit, used to describe it in reports.
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 impl<B: Block> Block for Vec<B> {
@@ -40,12 +67,33 @@ impl<B: Block> Block for Vec<B> {
         false
     }
 
+    fn has_changed_ports(&self) -> bool {
+        self.iter().any(|x| x.has_changed_ports())
+    }
+
+    fn has_changed_interface(&self) -> bool {
+        self.iter().any(|x| x.has_changed_interface())
+    }
+
+    fn update_pending(&mut self) {
+        for x in self {
+            x.update_pending();
+        }
+    }
+
     fn accept(&self, name: &str, probe: &mut dyn Probe) {
         for x in self.iter().enumerate() {
             let name = format!("{}${}", name, x.0);
             x.1.accept(&name, probe);
         }
     }
+
+    fn accept_mut(&mut self, name: &str, probe: &mut dyn ProbeMut) {
+        for x in self.iter_mut().enumerate() {
+            let name = format!("{}${}", name, x.0);
+            x.1.accept_mut(&name, probe);
+        }
+    }
 }
 
 impl<B: Block, const P: usize> Block for [B; P] {
@@ -70,10 +118,31 @@ impl<B: Block, const P: usize> Block for [B; P] {
         false
     }
 
+    fn has_changed_ports(&self) -> bool {
+        self.iter().any(|x| x.has_changed_ports())
+    }
+
+    fn has_changed_interface(&self) -> bool {
+        self.iter().any(|x| x.has_changed_interface())
+    }
+
+    fn update_pending(&mut self) {
+        for x in self {
+            x.update_pending();
+        }
+    }
+
     fn accept(&self, name: &str, probe: &mut dyn Probe) {
         for x in self.iter().enumerate() {
             let name = format!("{}${}", name, x.0);
             x.1.accept(&name, probe);
         }
     }
+
+    fn accept_mut(&mut self, name: &str, probe: &mut dyn ProbeMut) {
+        for x in self.iter_mut().enumerate() {
+            let name = format!("{}${}", name, x.0);
+            x.1.accept_mut(&name, probe);
+        }
+    }
 }
@@ -0,0 +1,170 @@
+use crate::block::Block;
+use crate::logic::Logic;
+use crate::probe::Probe;
+use crate::regression::sim_rng;
+use crate::synth::Synth;
+use rand::Rng;
+use std::cell::Cell;
+
+/// The settings for injecting clock domain crossing (CDC) faults into a simulation (see
+/// [Simulation::set_cdc_jitter](crate::simulate::Simulation::set_cdc_jitter)).  Normally,
+/// a signal that crosses into a new clock domain is sampled perfectly - a change on the input
+/// is always seen by the next edge of the destination clock.  With jitter, a change can be
+/// resolved one edge late instead, which is what happens in hardware when the first flop of
+/// a synchronizer goes metastable, or when the bits of a bus arrive at slightly different
+/// times.  A correctly designed crossing works regardless.  The random decisions are drawn
+/// from [sim_rng], so a seeded simulation makes the same decisions each time it is run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CDCJitter {
+    /// The probability that a change on a single bit crossing (like the input of a
+    /// synchronizer) is resolved one clock late
+    pub late: f64,
+    /// The probability that each bit of a change on a multi-bit crossing is sampled
+    /// one clock late
+    pub skew: f64,
+}
+
+impl Default for CDCJitter {
+    fn default() -> Self {
+        Self {
+            late: 0.5,
+            skew: 0.5,
+        }
+    }
+}
+
+thread_local! {
+    static CDC_JITTER: Cell<Option<CDCJitter>> = const { Cell::new(None) };
+}
+
+// The settings are per thread, since the circuit is updated on the thread that runs the simulation
+pub(crate) fn install_cdc_jitter(jitter: Option<CDCJitter>) {
+    CDC_JITTER.with(|x| x.set(jitter));
+}
+
+/// The CDC jitter settings of the simulation running on the calling thread (if any)
+pub fn cdc_jitter() -> Option<CDCJitter> {
+    CDC_JITTER.with(|x| x.get())
+}
+
+/// Resolve a change on a clock domain crossing from `old` to `new`.  Without jitter, this is
+/// just `new`.  With jitter, the bits that are resolved late keep their `old` value.  A single
+/// bit crossing uses the `late` probability, and each bit of a wider crossing uses the `skew`
+/// probability.  Types that cannot be rebuilt from their bits are resolved late as a whole.
+pub fn cdc_resolve<T: Synth>(old: T, new: T) -> T {
+    let jitter = match cdc_jitter() {
+        Some(jitter) if old != new => jitter,
+        _ => return new,
+    };
+    let mut rng = sim_rng();
+    if T::BITS == 1 {
+        return if rng.gen_bool(jitter.late.clamp(0.0, 1.0)) {
+            old
+        } else {
+            new
+        };
+    }
+    let skew = jitter.skew.clamp(0.0, 1.0);
+    let old_bits = old.verilog().as_biguint();
+    let mut bits = new.verilog().as_biguint();
+    for ndx in 0..T::BITS as u64 {
+        let was = old_bits.bit(ndx);
+        if was != bits.bit(ndx) && rng.gen_bool(skew) {
+            bits.set_bit(ndx, was);
+        }
+    }
+    match T::from_bits(&bits) {
+        Some(x) => x,
+        None if rng.gen_bool(skew) => old,
+        None => new,
+    }
+}
+
+/// A [ClockCrossing] holds the simulation state of the point at which a signal enters the
+/// domain of a clock.  It is used with the [cdc_cross!] macro, which presents the signal to
+/// the flops of the new domain.  In hardware, the crossing is just a wire, so it adds nothing
+/// to the generated Verilog.  In simulation, when CDC jitter is enabled, the bits of a change
+/// may be seen one edge of the clock late (see [cdc_resolve]).  This models the first flop
+/// of a synchronizer going metastable, and the skew between the bits of a bus.
+#[derive(Clone, Debug, Default)]
+pub struct ClockCrossing<T: Synth> {
+    // The value of the input at the last sample, and the value presented to the flops
+    current: T,
+    shown: T,
+    // The value of the input at the last edge, until it is released to the flops
+    settled: Option<T>,
+}
+
+impl<T: Synth> ClockCrossing<T> {
+    /// Sample the `input` to the crossing, where `edge` is true at an active edge of the
+    /// destination clock.  Returns the value seen by the destination domain.
+    pub fn sample(&mut self, edge: bool, input: T) -> T {
+        // A change that was resolved late is seen once the edge that missed it has passed
+        if edge {
+            self.settled.get_or_insert(self.current);
+        } else if let Some(settled) = self.settled.take() {
+            self.shown = cdc_resolve(settled, self.current);
+        }
+        if input != self.current {
+            self.shown = cdc_resolve(self.shown, input);
+            self.current = input;
+        }
+        self.shown
+    }
+}
+
+impl<T: Synth> Logic for ClockCrossing<T> {
+    fn update(&mut self) {}
+}
+
+// The crossing has no signals, so it is invisible to probes (and to the Verilog)
+impl<T: Synth> Block for ClockCrossing<T> {
+    fn connect_all(&mut self) {}
+    fn update_all(&mut self) {}
+    fn has_changed(&self) -> bool {
+        false
+    }
+    fn has_changed_ports(&self) -> bool {
+        false
+    }
+    fn has_changed_interface(&self) -> bool {
+        false
+    }
+    fn update_pending(&mut self) {}
+    fn accept(&self, _name: &str, _probe: &mut dyn Probe) {}
+}
+
+/// The [cdc_cross!] macro reads a signal that crosses into the domain of `clock` through a
+/// [ClockCrossing].  In Verilog, it is just the signal.
+///
+/// ```
+/// use rust_hdl_core::prelude::*;
+///
+/// #[derive(LogicBlock, Default)]
+/// pub struct Capture {
+///    pub clock: Signal<In, Clock>,
+///    pub sig_in: Signal<In, Bit>,
+///    pub sig_out: Signal<Out, Bit>,
+///    cross: ClockCrossing<Bit>,
+/// }
+///
+/// impl Logic for Capture {
+///    #[hdl_gen]
+///    fn update(&mut self) {
+///        // In Verilog, this is sig_out = sig_in
+///        self.sig_out.next = cdc_cross!(self, clock, cross, sig_in);
+///    }
+/// }
+/// ```
+///
+/// The crossing has to see every change of the signal, so if the signal is only read
+/// under some condition, also invoke the macro as a statement at the top level of the
+/// kernel.  As a statement, it produces no Verilog at all.
+#[macro_export]
+macro_rules! cdc_cross {
+    ($self: ident, $clock: ident, $cross: ident, $sig: ident) => {
+        $self
+            .$cross
+            .sample($self.$clock.pos_edge(), $self.$sig.val())
+    };
+}
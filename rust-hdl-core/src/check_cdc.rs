@@ -0,0 +1,63 @@
+use crate::block::Block;
+use crate::check_error::CheckError;
+use crate::check_timing::check_timing;
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+
+// The synchronizer widgets, and the flop in each one that captures the signal from the
+// other clock domain
+const SYNCHRONIZERS: [(&str, &str); 2] = [("BitSynchronizer", "dff0"), ("SyncReceiver", "hold")];
+
+#[derive(Default)]
+struct SynchronizerScan {
+    path: NamedPath,
+    found: Vec<String>,
+}
+
+impl Probe for SynchronizerScan {
+    fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
+        self.path.push(name);
+        let type_name = node.type_name();
+        let type_name = type_name.split('<').next().unwrap_or(type_name);
+        let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+        if let Some((_, flop)) = SYNCHRONIZERS.iter().find(|x| x.0 == type_name) {
+            self.found.push(format!(
+                "{}.{}.",
+                self.path.to_string().replace('$', "."),
+                flop
+            ));
+        }
+    }
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+}
+
+/// Check a circuit for unsynchronized clock domain crossings.  The clock domain of each
+/// register (i.e., each [TimingInfo](crate::timing::TimingInfo) returned by
+/// [Logic::timing](crate::logic::Logic::timing)) is the signal its clock is wired from, as
+/// found by [check_timing].  A combinational path from a register in one domain to a register
+/// in another domain is a crossing, and is reported in a [CheckError::ClockDomainCrossings].
+///
+/// A crossing that is captured by the first flop of a `BitSynchronizer`, or by the holding
+/// register of a `SyncReceiver` (which only samples the bus once the handshake says it is
+/// stable), is synchronized, and is not reported.  The blocks are recognized by their type
+/// name.  Everything else in a crossing widget is checked as usual, so the `SyncSender`/
+/// `SyncReceiver` pair and the asynchronous FIFOs (which are built from them) pass on their
+/// own, but a signal from the wrong domain feeding one of them does not.  A RAM with separate
+/// read and write clocks is not a crossing either, since the two sides are separate registers.
+pub fn check_cdc(uut: &dyn Block) -> Result<(), CheckError> {
+    let mut scan = SynchronizerScan::default();
+    uut.accept("uut", &mut scan);
+    let crossings = check_timing(uut)
+        .register_paths
+        .into_iter()
+        .filter(|x| x.launch_clock != x.capture_clock)
+        .filter(|x| !scan.found.iter().any(|flop| x.to.starts_with(flop)))
+        .collect::<Vec<_>>();
+    if crossings.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError::ClockDomainCrossings(crossings))
+    }
+}
@@ -1,6 +1,8 @@
 use crate::block::Block;
 use crate::check_connected::check_connected;
 use crate::check_logic_loops::check_logic_loops;
+use crate::check_timing::RegisterPath;
+use crate::check_widths::WidthMismatch;
 use crate::check_write_inputs::check_inputs_not_written;
 
 use std::collections::HashMap;
@@ -30,6 +32,22 @@ pub enum CheckError {
     LogicLoops(PathedNameList),
     /// The circuit attempts to write to the inputs, which is not allowed in RustHDL.
     WritesToInputs(PathedNameList),
+    /// The circuit samples signals from one clock domain in another, without going
+    /// through a synchronizer (see [check_cdc](crate::check_cdc::check_cdc)).
+    ClockDomainCrossings(Vec<RegisterPath>),
+    /// Signals that are assigned on some, but not all of the paths through the HDL kernel
+    /// of a block, which makes the synthesis tool infer latches (see
+    /// [check_latches](crate::check_latches::check_latches)).
+    InferredLatches(PathedNameList),
+    /// Values of different widths are assigned or compared, or bits are accessed past the
+    /// end of a signal (see [check_widths](crate::check_widths::check_widths)).
+    WidthMismatches(Vec<WidthMismatch>),
+    /// Signals that are driven from more than one place (see
+    /// [check_multiple_drivers](crate::check_nets::check_multiple_drivers)).
+    MultipleDrivers(PathedNameList),
+    /// Signals that are computed, but never read (see
+    /// [check_unused_signals](crate::check_nets::check_unused_signals)).
+    UnusedSignals(PathedNameList),
 }
 
 /// This is a helper function used to check a [Block] for connection, loops, and
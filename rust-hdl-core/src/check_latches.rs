@@ -0,0 +1,161 @@
+use crate::ast::{
+    Verilog, VerilogBlock, VerilogBlockOrConditional, VerilogExpression, VerilogStatement,
+};
+use crate::block::Block;
+use crate::check_error::{CheckError, PathedName, PathedNameList};
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+use std::collections::HashSet;
+
+// The signals assigned on every path through a piece of code, and those assigned on any path
+#[derive(Default)]
+struct Assigned {
+    always: HashSet<String>,
+    sometimes: HashSet<String>,
+}
+
+impl Assigned {
+    fn then(mut self, other: Assigned) -> Self {
+        self.always.extend(other.always);
+        self.sometimes.extend(other.sometimes);
+        self
+    }
+}
+
+// Only one of the branches is taken
+fn either(branches: Vec<Assigned>) -> Assigned {
+    let mut ret = Assigned::default();
+    let mut always: Option<HashSet<String>> = None;
+    for branch in branches {
+        always = Some(match always {
+            None => branch.always,
+            Some(x) => x.intersection(&branch.always).cloned().collect(),
+        });
+        ret.sometimes.extend(branch.sometimes);
+    }
+    ret.always = always.unwrap_or_default();
+    ret
+}
+
+// The signal written by an assignment (a write to some of the bits counts as a write to the signal)
+fn target_name(e: &VerilogExpression) -> Option<String> {
+    match e {
+        VerilogExpression::Signal(x) => Some(x.replace("$next", "")),
+        VerilogExpression::Index(x, _)
+        | VerilogExpression::Slice(x, _, _)
+        | VerilogExpression::Paren(x)
+        | VerilogExpression::Cast(x, _) => target_name(x),
+        _ => None,
+    }
+}
+
+fn assigned_in_block(block: &VerilogBlock) -> Assigned {
+    block.iter().fold(Assigned::default(), |acc, x| {
+        acc.then(assigned_in_statement(x))
+    })
+}
+
+fn assigned_in_otherwise(otherwise: &VerilogBlockOrConditional) -> Assigned {
+    match otherwise {
+        VerilogBlockOrConditional::Block(block) => assigned_in_block(block),
+        VerilogBlockOrConditional::Conditional(statement) => assigned_in_statement(statement),
+        VerilogBlockOrConditional::None => Assigned::default(),
+    }
+}
+
+fn assigned_in_statement(statement: &VerilogStatement) -> Assigned {
+    match statement {
+        VerilogStatement::Assignment(target, _)
+        | VerilogStatement::SliceAssignment { base: target, .. } => {
+            let mut ret = Assigned::default();
+            if let Some(name) = target_name(target) {
+                ret.always.insert(name.clone());
+                ret.sometimes.insert(name);
+            }
+            ret
+        }
+        VerilogStatement::If(c) => either(vec![
+            assigned_in_block(&c.then),
+            assigned_in_otherwise(&c.otherwise),
+        ]),
+        // Rust only accepts exhaustive matches, so one of the cases is always taken
+        VerilogStatement::Match(m) => either(
+            m.cases
+                .iter()
+                .map(|x| assigned_in_block(&x.block))
+                .collect(),
+        ),
+        VerilogStatement::Loop(l) => assigned_in_block(&l.block),
+        VerilogStatement::Macro(block) => assigned_in_block(block),
+        VerilogStatement::Comment(_) | VerilogStatement::Link(_) => Assigned::default(),
+    }
+}
+
+#[derive(Default)]
+struct CheckLatches {
+    path: NamedPath,
+    failures: PathedNameList,
+}
+
+impl Probe for CheckLatches {
+    fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
+        self.path.push(name);
+        if let Verilog::Combinatorial(code) = &node.hdl() {
+            let assigned = assigned_in_block(code);
+            let mut latches = assigned
+                .sometimes
+                .difference(&assigned.always)
+                .cloned()
+                .collect::<Vec<_>>();
+            latches.sort();
+            for name in latches {
+                self.failures.push(PathedName {
+                    path: self.path.to_string(),
+                    name,
+                });
+            }
+        }
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+}
+
+/// Check a circuit for latches.  The `update` function of a block is translated into
+/// an `always @(*)` block of Verilog.  If a signal is assigned on some, but not all of the
+/// paths through the block (i.e., in the `then` branch of an `if`, but not in the `else`
+/// branch), then the synthesis tool must infer a latch to hold the value of the signal on
+/// the other paths.  That is almost never what you want, and is prevented by assigning a
+/// default value to the signal at the top of the `update` function.
+/// ```rust
+/// use rust_hdl_core::prelude::*;
+/// use rust_hdl_core::check_latches::check_latches;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Leaky {
+///    pub enable: Signal<In, Bit>,
+///    pub active: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Leaky {
+///    #[hdl_gen]
+///    fn update(&mut self) {
+///       if self.enable.val() {
+///          self.active.next = true; // <-- active holds its value when enable is false
+///       }
+///    }
+/// }
+///
+/// let mut uut = Leaky::default(); uut.connect_all();
+/// assert!(check_latches(&uut).is_err());
+/// ```
+pub fn check_latches(uut: &dyn Block) -> Result<(), CheckError> {
+    let mut visitor = CheckLatches::default();
+    uut.accept("uut", &mut visitor);
+    if visitor.failures.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError::InferredLatches(visitor.failures))
+    }
+}
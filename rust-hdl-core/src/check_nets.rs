@@ -0,0 +1,341 @@
+use crate::ast::{Verilog, VerilogExpression, VerilogLink};
+use crate::atom::{Atom, AtomKind};
+use crate::block::Block;
+use crate::check_error::{CheckError, PathedName, PathedNameList};
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+use crate::verilog_gen::verilog_link_extraction;
+use crate::verilog_visitor::{walk_index, walk_slice, VerilogVisitor};
+use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Role {
+    // A local signal of the module
+    Local,
+    // An output of the module
+    Output,
+    // An input of a child of the module
+    ChildInput,
+    // An output of a child of the module
+    ChildOutput,
+    // Inputs and constants are not checked here
+    Other,
+}
+
+type Signals = BTreeMap<String, (Role, PathedName)>;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Mode {
+    Read,
+    Write,
+}
+
+// Collects the names of the signals read and written by an HDL kernel (but not by its links)
+struct VerilogAccessCollector {
+    reads: BTreeSet<String>,
+    writes: BTreeSet<String>,
+    mode: Mode,
+}
+
+impl VerilogAccessCollector {
+    fn with_mode(&mut self, mode: Mode, e: &VerilogExpression) {
+        let current_mode = self.mode;
+        self.mode = mode;
+        self.visit_expression(e);
+        self.mode = current_mode;
+    }
+}
+
+impl VerilogVisitor for VerilogAccessCollector {
+    fn visit_signal(&mut self, c: &str) {
+        match self.mode {
+            Mode::Read => self.reads.insert(c.to_owned()),
+            Mode::Write => self.writes.insert(c.to_owned()),
+        };
+    }
+
+    fn visit_assignment(&mut self, l: &VerilogExpression, r: &VerilogExpression) {
+        self.with_mode(Mode::Read, r);
+        self.with_mode(Mode::Write, l);
+    }
+
+    fn visit_slice_assignment(
+        &mut self,
+        base: &VerilogExpression,
+        _width: &usize,
+        offset: &VerilogExpression,
+        replacement: &VerilogExpression,
+    ) {
+        self.with_mode(Mode::Read, offset);
+        self.with_mode(Mode::Read, replacement);
+        self.with_mode(Mode::Write, base);
+    }
+
+    // The index of a bit (or a slice) that is written is still read
+    fn visit_index(&mut self, a: &VerilogExpression, b: &VerilogExpression) {
+        if self.mode == Mode::Write {
+            self.visit_expression(a);
+            self.with_mode(Mode::Read, b);
+        } else {
+            walk_index(self, a, b);
+        }
+    }
+
+    fn visit_slice(&mut self, a: &VerilogExpression, b: &usize, c: &VerilogExpression) {
+        if self.mode == Mode::Write {
+            self.visit_expression(a);
+            self.with_mode(Mode::Read, c);
+        } else {
+            walk_slice(self, a, b, c);
+        }
+    }
+}
+
+// Find the signals of the module that a name in the HDL refers to.  Array indices
+// like `nodes[i]` refer to every element of the array, and a field of a struct
+// refers to the whole signal.
+fn resolve(signals: &Signals, name: &str) -> Vec<String> {
+    let name = name
+        .trim_start_matches('.')
+        .replace("::", "$")
+        .replace('.', "$")
+        .trim_end_matches("$next")
+        .to_owned();
+    if name.contains('[') {
+        let index = Regex::new(r"\[([^\]]*)\]").unwrap();
+        let mut pattern = String::from("^");
+        let mut last = 0;
+        for capture in index.captures_iter(&name) {
+            let whole = capture.get(0).unwrap();
+            pattern += &regex::escape(&name[last..whole.start()]);
+            match capture[1].parse::<usize>() {
+                Ok(n) => pattern += &format!("\\${}", n),
+                Err(_) => pattern += r"\$\d+",
+            }
+            last = whole.end();
+        }
+        pattern += &regex::escape(&name[last..]);
+        pattern += "$";
+        let pattern = Regex::new(&pattern).unwrap();
+        return signals
+            .keys()
+            .filter(|x| pattern.is_match(x))
+            .cloned()
+            .collect();
+    }
+    let mut name = name.as_str();
+    loop {
+        if signals.contains_key(name) {
+            return vec![name.to_owned()];
+        }
+        match name.rsplit_once('$') {
+            Some((prefix, _)) => name = prefix,
+            None => return vec![],
+        }
+    }
+}
+
+// The signal at one end of a link
+fn link_name(owner: &str, my_name: &str) -> String {
+    if my_name.is_empty() {
+        owner.to_owned()
+    } else {
+        format!("{}${}", owner, my_name)
+    }
+}
+
+#[derive(Default)]
+struct CheckNets {
+    path: NamedPath,
+    namespace: NamedPath,
+    scopes: Vec<Signals>,
+    multiple_drivers: PathedNameList,
+    unused: PathedNameList,
+}
+
+impl CheckNets {
+    fn check_module(&mut self, signals: &Signals, code: &Verilog) {
+        let code = match code {
+            Verilog::Combinatorial(code) => code,
+            _ => return,
+        };
+        let mut collector = VerilogAccessCollector {
+            reads: Default::default(),
+            writes: Default::default(),
+            mode: Mode::Read,
+        };
+        collector.visit_block(code);
+        let mut drivers: BTreeMap<String, usize> = BTreeMap::new();
+        let mut reads: BTreeSet<String> = BTreeSet::new();
+        for name in &collector.writes {
+            for signal in resolve(signals, name) {
+                *drivers.entry(signal).or_default() += 1;
+            }
+        }
+        for name in &collector.reads {
+            reads.extend(resolve(signals, name));
+        }
+        for link in verilog_link_extraction(code) {
+            let (driven, source) = match &link {
+                VerilogLink::Forward(x) => (Some(&x.other_name), &x.owner_name),
+                VerilogLink::Backward(x) => (Some(&x.owner_name), &x.other_name),
+                VerilogLink::Bidirectional(x) => {
+                    reads.extend(resolve(signals, &link_name(&x.owner_name, &x.my_name)));
+                    (None, &x.other_name)
+                }
+            };
+            let my_name = match &link {
+                VerilogLink::Forward(x)
+                | VerilogLink::Backward(x)
+                | VerilogLink::Bidirectional(x) => &x.my_name,
+            };
+            if let Some(driven) = driven {
+                for signal in resolve(signals, &link_name(driven, my_name)) {
+                    *drivers.entry(signal).or_default() += 1;
+                }
+            }
+            reads.extend(resolve(signals, &link_name(source, my_name)));
+        }
+        for (name, (role, signal)) in signals {
+            let driven = drivers.get(name).copied().unwrap_or_default()
+                + usize::from(*role == Role::ChildOutput);
+            let checked = [
+                Role::Local,
+                Role::Output,
+                Role::ChildInput,
+                Role::ChildOutput,
+            ];
+            if driven > 1 && checked.contains(role) && !self.multiple_drivers.contains(signal) {
+                self.multiple_drivers.push(signal.clone());
+            }
+            if [Role::Local, Role::ChildOutput].contains(role) && !reads.contains(name) {
+                self.unused.push(signal.clone());
+            }
+        }
+    }
+}
+
+impl Probe for CheckNets {
+    fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+        self.namespace.reset();
+        self.scopes.push(Default::default());
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.namespace.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        let namespace = self.namespace.flat("$");
+        let name = if namespace.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{}${}", namespace, name)
+        };
+        let pathed = PathedName {
+            path: self.path.to_string(),
+            name: name.clone(),
+        };
+        let (role, parent_role) = match signal.kind() {
+            AtomKind::LocalSignal => (Role::Local, None),
+            AtomKind::OutputParameter => (Role::Output, Some(Role::ChildOutput)),
+            AtomKind::InputParameter => (Role::Other, Some(Role::ChildInput)),
+            _ => (Role::Other, None),
+        };
+        let depth = self.scopes.len();
+        if let (Some(parent_role), true) = (parent_role, depth > 1) {
+            self.scopes[depth - 2].insert(
+                format!("{}${}", self.path.last(), name),
+                (parent_role, pathed.clone()),
+            );
+        }
+        self.scopes[depth - 1].insert(name, (role, pathed));
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.namespace.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, node: &dyn Block) {
+        let signals = self.scopes.pop().unwrap_or_default();
+        self.check_module(&signals, &node.hdl());
+        self.path.pop();
+    }
+}
+
+fn check_nets(uut: &dyn Block) -> CheckNets {
+    let mut visitor = CheckNets::default();
+    uut.accept("uut", &mut visitor);
+    // Blocks are checked as they are finished, so put the report back in order
+    let order = |x: &PathedName| (x.path.clone(), x.name.clone());
+    visitor.multiple_drivers.sort_by_key(order);
+    visitor.unused.sort_by_key(order);
+    visitor
+}
+
+/// Check a circuit for signals that are driven from more than one place.  A signal
+/// can be driven by the HDL kernel of a block (any number of assignments in the kernel
+/// count as one driver), by a `link!` or `join!` of an interface, or (for the outputs of
+/// a child block) by the child itself.  Each multiply-driven signal is reported once, by
+/// the [PathedName] of the block that owns it.  Writes to inputs are reported by
+/// [check_inputs_not_written](crate::check_write_inputs::check_inputs_not_written) instead.
+/// ```rust
+/// use rust_hdl_core::prelude::*;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Inverter {
+///     pub sig_in: Signal<In, Bit>,
+///     pub sig_out: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Inverter {
+///     #[hdl_gen]
+///     fn update(&mut self) {
+///         self.sig_out.next = !self.sig_in.val();
+///     }
+/// }
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Fight {
+///     pub sig_in: Signal<In, Bit>,
+///     pub sig_out: Signal<Out, Bit>,
+///     inv: Inverter,
+/// }
+///
+/// impl Logic for Fight {
+///     #[hdl_gen]
+///     fn update(&mut self) {
+///         self.inv.sig_in.next = self.sig_in.val();
+///         self.inv.sig_out.next = false; // <-- The inverter already drives this
+///         self.sig_out.next = self.inv.sig_out.val();
+///     }
+/// }
+///
+/// let mut uut = Fight::default(); uut.connect_all();
+/// assert!(check_multiple_drivers(&uut).is_err());
+/// ```
+pub fn check_multiple_drivers(uut: &dyn Block) -> Result<(), CheckError> {
+    let visitor = check_nets(uut);
+    if visitor.multiple_drivers.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError::MultipleDrivers(visitor.multiple_drivers))
+    }
+}
+
+/// Check a circuit for signals that are computed, but never used.  These are the
+/// local signals that the HDL kernel of a block does not read, and the outputs of child
+/// blocks that their parent does not read (or pass on with a `link!` or `join!`).  The
+/// outputs of the top level block are not reported.  Blocks with custom Verilog are not
+/// checked.  An unused signal is not an error as far as the synthesis tools are concerned
+/// (they will simply remove the logic), but it often means that something was left out.
+pub fn check_unused_signals(uut: &dyn Block) -> Result<(), CheckError> {
+    let visitor = check_nets(uut);
+    if visitor.unused.is_empty() {
+        Ok(())
+    } else {
+        Err(CheckError::UnusedSignals(visitor.unused))
+    }
+}
@@ -0,0 +1,291 @@
+use crate::ast::SourceLocation;
+use crate::block::Block;
+use crate::check_cdc::check_cdc;
+use crate::check_connected::check_connected;
+use crate::check_error::{CheckError, PathedName};
+use crate::check_latches::check_latches;
+use crate::check_logic_loops::check_logic_loops;
+use crate::check_nets::{check_multiple_drivers, check_unused_signals};
+use crate::check_widths::{check_widths, WidthMismatchKind};
+use crate::check_write_inputs::check_inputs_not_written;
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// How serious a [CheckViolation] is
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CheckSeverity {
+    /// The circuit will not simulate or synthesize correctly
+    Error,
+    /// The circuit is probably not what was intended
+    Warning,
+}
+
+/// A single problem found by [check_all_report]
+#[derive(Clone, Debug, PartialEq)]
+pub struct CheckViolation {
+    pub severity: CheckSeverity,
+    /// The check that found the problem (i.e., `open signal`)
+    pub check: &'static str,
+    /// The hierarchical name of the signal, like `uut.counter.enable`
+    pub signal: String,
+    /// More details about the problem, if the check provides them
+    pub detail: Option<String>,
+    /// The Rust type of the block that owns the signal
+    pub block_type: String,
+    /// Where the HDL kernel of that block is, if it was written with `#[hdl_gen]`
+    pub location: Option<SourceLocation>,
+}
+
+impl Display for CheckViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            CheckSeverity::Error => "error",
+            CheckSeverity::Warning => "warning",
+        };
+        write!(f, "{}: {} `{}`", severity, self.check, self.signal)?;
+        if let Some(detail) = &self.detail {
+            write!(f, ": {}", detail)?;
+        }
+        match &self.location {
+            Some(location) => write!(
+                f,
+                "\n  --> {}:{} ({})",
+                location.file, location.line, self.block_type
+            ),
+            None => write!(f, "\n  --> ({})", self.block_type),
+        }
+    }
+}
+
+/// The problems found by [check_all_report], in the order of the checks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CheckReport {
+    pub violations: Vec<CheckViolation>,
+}
+
+impl CheckReport {
+    /// The number of errors in the report
+    pub fn errors(&self) -> usize {
+        self.count(CheckSeverity::Error)
+    }
+    /// The number of warnings in the report
+    pub fn warnings(&self) -> usize {
+        self.count(CheckSeverity::Warning)
+    }
+    /// Returns `true` if there are no errors (there may still be warnings)
+    pub fn is_ok(&self) -> bool {
+        self.errors() == 0
+    }
+    fn count(&self, severity: CheckSeverity) -> usize {
+        self.violations
+            .iter()
+            .filter(|x| x.severity == severity)
+            .count()
+    }
+}
+
+fn plural(count: usize, what: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, what)
+    } else {
+        format!("{} {}s", count, what)
+    }
+}
+
+impl Display for CheckReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.violations.is_empty() {
+            return writeln!(f, "No problems found");
+        }
+        writeln!(
+            f,
+            "Found {} and {}",
+            plural(self.errors(), "error"),
+            plural(self.warnings(), "warning")
+        )?;
+        for violation in &self.violations {
+            writeln!(f)?;
+            writeln!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct BlockInfo {
+    path: NamedPath,
+    blocks: HashMap<String, (&'static str, Option<SourceLocation>)>,
+}
+
+impl Probe for BlockInfo {
+    fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
+        self.path.push(name);
+        self.blocks.insert(
+            self.path.to_string(),
+            (node.type_name(), node.hdl_location()),
+        );
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+}
+
+struct Collector {
+    blocks: HashMap<String, (&'static str, Option<SourceLocation>)>,
+    violations: Vec<CheckViolation>,
+}
+
+impl Collector {
+    // `path` is the `$` separated path of the block that owns the signal
+    fn push(
+        &mut self,
+        severity: CheckSeverity,
+        check: &'static str,
+        path: &str,
+        name: &str,
+        detail: Option<String>,
+    ) {
+        let (block_type, location) = self.blocks.get(path).copied().unwrap_or_default();
+        let signal = if name.is_empty() {
+            path.to_owned()
+        } else {
+            format!("{}${}", path, name)
+        };
+        self.violations.push(CheckViolation {
+            severity,
+            check,
+            signal: signal.replace('$', "."),
+            detail,
+            block_type: block_type.into(),
+            location,
+        })
+    }
+    fn push_names(&mut self, severity: CheckSeverity, check: &'static str, names: &[PathedName]) {
+        for name in names {
+            self.push(severity, check, &name.path, &name.name, None);
+        }
+    }
+    fn add(&mut self, result: Result<(), CheckError>) {
+        let error = match result {
+            Ok(()) => return,
+            Err(error) => error,
+        };
+        match error {
+            CheckError::OpenSignal(map) => {
+                let mut names = map.into_values().collect::<Vec<_>>();
+                names.sort_by_key(|x| (x.path.clone(), x.name.clone()));
+                self.push_names(CheckSeverity::Error, "open signal", &names)
+            }
+            CheckError::LogicLoops(names) => {
+                self.push_names(CheckSeverity::Error, "logic loop", &names)
+            }
+            CheckError::WritesToInputs(names) => {
+                self.push_names(CheckSeverity::Error, "write to input", &names)
+            }
+            CheckError::ClockDomainCrossings(paths) => {
+                for path in paths {
+                    // The register is the last part of the name (i.e., `uut.capture.dff`)
+                    let (owner, register) = path.to.rsplit_once('.').unwrap_or((&path.to, ""));
+                    let detail = format!(
+                        "sampled from `{}` ({}) in {}",
+                        path.from, path.launch_clock, path.capture_clock
+                    );
+                    self.push(
+                        CheckSeverity::Error,
+                        "clock domain crossing",
+                        &owner.replace('.', "$"),
+                        register,
+                        Some(detail),
+                    );
+                }
+            }
+            CheckError::InferredLatches(names) => {
+                self.push_names(CheckSeverity::Error, "inferred latch", &names)
+            }
+            CheckError::WidthMismatches(mismatches) => {
+                for mismatch in mismatches {
+                    let detail = match mismatch.kind {
+                        WidthMismatchKind::Truncation | WidthMismatchKind::Extension => format!(
+                            "assigned {} bits, but holds {}",
+                            mismatch.actual, mismatch.expected
+                        ),
+                        WidthMismatchKind::Comparison => format!(
+                            "{} bits compared with {} bits",
+                            mismatch.expected, mismatch.actual
+                        ),
+                        WidthMismatchKind::OutOfRange => format!(
+                            "bit {} accessed, but it only has {}",
+                            mismatch.actual.saturating_sub(1),
+                            mismatch.expected
+                        ),
+                    };
+                    self.push(
+                        CheckSeverity::Error,
+                        "width mismatch",
+                        &mismatch.signal.path,
+                        &mismatch.signal.name,
+                        Some(detail),
+                    );
+                }
+            }
+            CheckError::MultipleDrivers(names) => {
+                self.push_names(CheckSeverity::Error, "multiple drivers", &names)
+            }
+            CheckError::UnusedSignals(names) => {
+                self.push_names(CheckSeverity::Warning, "unused signal", &names)
+            }
+        }
+    }
+}
+
+/// Run all of the checks on a circuit, and collect everything they find into a single
+/// [CheckReport].  Unlike [check_all](crate::check_error::check_all), this does not
+/// stop at the first check that fails, so that all of the problems in a large design
+/// can be fixed at once.  Each problem is reported with the Rust type of the block it
+/// belongs to, and the location of that block's HDL kernel (for blocks that use `#[hdl_gen]`).
+/// Unused signals are reported as warnings, and everything else as errors.  The report
+/// can be printed for a human readable summary.
+/// ```rust
+/// use rust_hdl_core::prelude::*;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Broken {
+///     pub I: Signal<In, Bit>,
+///     pub O: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Broken {
+///    #[hdl_gen]
+///    fn update(&mut self) {
+///       // Purposely left blank... circuit is broken!
+///    }
+/// }
+///
+/// let mut uut = TopWrap::new(Broken::default());
+/// uut.connect_all();
+/// let report = check_all_report(&uut);
+/// assert!(!report.is_ok());
+/// println!("{}", report);
+/// ```
+pub fn check_all_report(uut: &dyn Block) -> CheckReport {
+    let mut info = BlockInfo::default();
+    uut.accept("uut", &mut info);
+    let mut collector = Collector {
+        blocks: info.blocks,
+        violations: vec![],
+    };
+    collector.add(check_connected(uut));
+    collector.add(check_logic_loops(uut));
+    collector.add(check_inputs_not_written(uut));
+    collector.add(check_cdc(uut));
+    collector.add(check_latches(uut));
+    collector.add(check_widths(uut));
+    collector.add(check_multiple_drivers(uut));
+    collector.add(check_unused_signals(uut));
+    CheckReport {
+        violations: collector.violations,
+    }
+}
@@ -8,13 +8,11 @@ use crate::probe::Probe;
 use crate::type_descriptor::TypeKind;
 use crate::verilog_visitor::VerilogVisitor;
 use petgraph::algo::{connected_components, is_cyclic_directed};
-use petgraph::dot::Dot;
 use petgraph::prelude::*;
-use petgraph::unionfind::UnionFind;
-use petgraph::visit::NodeIndexable;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 
-#[derive(Clone, Debug, Copy, PartialEq)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Hash)]
 enum SignalNodeKind {
     Normal,
     Bidirectional,
@@ -42,18 +40,16 @@ struct SignalNode {
 #[derive(Clone, Debug, Default)]
 struct SignalGraph {
     pub graph: Graph<SignalNode, SignalEdgeKind, Directed>,
+    index: HashMap<(String, SignalNodeKind), NodeIndex>,
 }
 
 impl SignalGraph {
-    fn dot(&self) -> String {
-        format!("{:?}", Dot::new(&self.graph))
-    }
     fn add_signal_node(&mut self, node: &SignalNode) -> NodeIndex {
-        let index = self.graph.node_indices().find(|i| self.graph[*i].eq(node));
-        match index {
-            Some(n) => n,
-            None => self.graph.add_node(node.clone()),
-        }
+        let graph = &mut self.graph;
+        *self
+            .index
+            .entry((node.name.clone(), node.kind))
+            .or_insert_with(|| graph.add_node(node.clone()))
     }
     fn add_signal_edge(&mut self, from: &SignalNode, to: NodeIndex, kind: SignalEdgeKind) {
         let from_index = self.add_signal_node(from);
@@ -78,6 +74,12 @@ struct TimingChecker {
     write_name: String,
     read_names: Vec<ReadScope>,
     pub graph: SignalGraph,
+    // The registers (and the clock signal that drives each one)
+    registers: Vec<(String, String)>,
+    // The top level outputs
+    outputs: Vec<String>,
+    // The inputs and outputs of each open scope with custom Verilog and no registers
+    custom: Vec<Option<(Vec<String>, Vec<String>)>>,
 }
 
 impl Default for TimingChecker {
@@ -89,6 +91,9 @@ impl Default for TimingChecker {
             write_name: "".to_string(),
             read_names: vec![],
             graph: Default::default(),
+            registers: vec![],
+            outputs: vec![],
+            custom: vec![],
         }
     }
 }
@@ -133,7 +138,6 @@ impl TimingChecker {
         let write_id = self.graph.add_signal_node(&write_node);
         for scope in &self.read_names {
             for read in scope {
-                println!("Adding edge from {:?} -> {:?}", read, write_node);
                 self.graph.add_signal_edge(read, write_id, edge);
             }
         }
@@ -142,19 +146,26 @@ impl TimingChecker {
         let v1 = format!(
             "{}${}${}",
             self.path.to_string(),
-            x.other_name.replace('[', "$").replace(']', ""),
-            x.my_name
+            link_ident(&x.other_name),
+            link_ident(&x.my_name)
         );
         let v2 = format!(
             "{}${}${}",
             self.path.to_string(),
-            x.owner_name.replace('[', "$").replace(']', ""),
-            x.my_name
+            link_ident(&x.owner_name),
+            link_ident(&x.my_name)
         );
         (v1, v2)
     }
 }
 
+// Links name the fields of an interface as `.field` (or `.sub.field`), and arrays as `name[n]`
+fn link_ident(x: &str) -> String {
+    x.trim_start_matches('.')
+        .replace(['.', '['], "$")
+        .replace(']', "")
+}
+
 impl VerilogVisitor for TimingChecker {
     fn visit_conditional(&mut self, c: &VerilogConditional) {
         self.push_read_scope();
@@ -238,11 +249,14 @@ impl VerilogVisitor for TimingChecker {
 
 impl Probe for TimingChecker {
     fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
-        println!("Start scope {}", name);
         self.path.push(name);
         self.namespace.reset();
-        self.add_code(&self.path.to_string(), node.hdl());
-        for info in &node.timing() {
+        let code = node.hdl();
+        let timing = node.timing();
+        self.custom
+            .push((matches!(code, Verilog::Custom(_)) && timing.is_empty()).then(Default::default));
+        self.add_code(&self.path.to_string(), code);
+        for info in &timing {
             // The timing info represents a register.  A register
             // adds a write dependency based on the clock
             // The use of the clock decouples the outputs from the inputs.
@@ -262,6 +276,10 @@ impl Probe for TimingChecker {
             }
             self.pop_read_scope();
             let write_name = format!("{}${}", self.path.to_string(), info.name);
+            self.registers.push((
+                write_name.clone(),
+                format!("{}${}", self.path.to_string(), info.clock),
+            ));
             for input in &info.inputs {
                 self.push_read_scope();
                 self.add_read(
@@ -282,7 +300,6 @@ impl Probe for TimingChecker {
         self.clear_scope();
     }
     fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
-        println!("Start Namespace {}", name);
         self.namespace.push(name);
     }
     fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
@@ -294,8 +311,17 @@ impl Probe for TimingChecker {
             format!("{}${}", namespace, name)
         };
         // Add an async source for all input parameters at the top scope
-        let is_top_scope = self.path.to_string().eq("top");
+        let is_top_scope = self.path.to_string().eq("uut");
         let global_signal_name = format!("{}${}", self.path.to_string(), name);
+        if let Some(Some((inputs, outputs))) = self.custom.last_mut() {
+            if signal.descriptor().name != "clock" {
+                match signal.kind() {
+                    AtomKind::InputParameter => inputs.push(global_signal_name.clone()),
+                    AtomKind::OutputParameter => outputs.push(global_signal_name.clone()),
+                    _ => {}
+                }
+            }
+        }
         match signal.kind() {
             AtomKind::InputParameter | AtomKind::InOutParameter => {
                 if is_top_scope {
@@ -313,6 +339,9 @@ impl Probe for TimingChecker {
                     );
                 }
             }
+            AtomKind::OutputParameter if is_top_scope => {
+                self.outputs.push(global_signal_name.clone());
+            }
             AtomKind::Constant => {
                 let my_id = self.graph.add_signal_node(&SignalNode {
                     name: global_signal_name.clone(),
@@ -364,57 +393,401 @@ impl Probe for TimingChecker {
         self.namespace.pop();
     }
     fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        // The custom Verilog is not traced, so assume every input can reach every output
+        if let Some(Some((inputs, outputs))) = self.custom.pop() {
+            for output in &outputs {
+                self.push_read_scope();
+                for input in &inputs {
+                    self.add_read(input, SignalNodeKind::Normal);
+                }
+                self.add_write(output, SignalNodeKind::Normal, SignalEdgeKind::Assign);
+                self.pop_read_scope();
+            }
+        }
         self.path.pop();
     }
 }
 
-pub fn check_timing<U: Block>(uut: &U) {
+/// A register found by [check_timing], and the clock that drives it
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimedRegister {
+    /// The path to the register (i.e., `uut.counter.dff`)
+    pub name: String,
+    /// The signal the clock of the register comes from (i.e., `uut.clock`)
+    pub clock: String,
+}
+
+/// A combinational path from the output of one register to the input of another
+#[derive(Clone, Debug, PartialEq)]
+pub struct RegisterPath {
+    /// The register that launches the path
+    pub from: String,
+    /// The register that captures the path
+    pub to: String,
+    /// The clock of the launching register
+    pub launch_clock: String,
+    /// The clock of the capturing register
+    pub capture_clock: String,
+    /// The largest number of assignments (including the connections between
+    /// modules) along the path
+    pub depth: usize,
+}
+
+/// A combinational path from a top level input to a top level output, that does not
+/// pass through a register
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnclockedPath {
+    pub input: String,
+    pub output: String,
+    /// The largest number of assignments along the path
+    pub depth: usize,
+}
+
+/// The result of [check_timing].  The paths are grouped by the clock of the
+/// register that captures them, and are sorted by name, so that reports can be
+/// compared from one run to the next.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TimingReport {
+    /// The signal graph contains a loop (the depths of paths through the loop are not counted)
+    pub cyclic: bool,
+    /// The number of disconnected pieces in the signal graph
+    pub connected_components: usize,
+    pub registers: Vec<TimedRegister>,
+    pub register_paths: Vec<RegisterPath>,
+    pub unclocked_paths: Vec<UnclockedPath>,
+}
+
+impl TimingReport {
+    /// The clocks that drive the registers of the circuit
+    pub fn clocks(&self) -> Vec<String> {
+        let mut clocks = self
+            .registers
+            .iter()
+            .map(|x| x.clock.clone())
+            .collect::<Vec<_>>();
+        clocks.sort();
+        clocks.dedup();
+        clocks
+    }
+    /// The register to register paths that are captured by registers on `clock`
+    pub fn paths_for_clock(&self, clock: &str) -> Vec<&RegisterPath> {
+        self.register_paths
+            .iter()
+            .filter(|x| x.capture_clock == clock)
+            .collect()
+    }
+    /// The depth of the deepest register to register path
+    pub fn max_depth(&self) -> usize {
+        self.register_paths
+            .iter()
+            .map(|x| x.depth)
+            .max()
+            .unwrap_or(0)
+    }
+    /// A machine-readable (JSON) version of the report
+    pub fn to_json(&self) -> String {
+        let registers = self
+            .registers
+            .iter()
+            .map(|x| format!("{{\"name\":\"{}\",\"clock\":\"{}\"}}", x.name, x.clock))
+            .collect::<Vec<_>>()
+            .join(",");
+        let register_paths = self
+            .register_paths
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"from\":\"{}\",\"to\":\"{}\",\"launch_clock\":\"{}\",\"capture_clock\":\"{}\",\"depth\":{}}}",
+                    x.from, x.to, x.launch_clock, x.capture_clock, x.depth
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let unclocked_paths = self
+            .unclocked_paths
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"input\":\"{}\",\"output\":\"{}\",\"depth\":{}}}",
+                    x.input, x.output, x.depth
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"cyclic\":{},\"connected_components\":{},\"max_depth\":{},\"registers\":[{}],\"register_paths\":[{}],\"unclocked_paths\":[{}]}}",
+            self.cyclic,
+            self.connected_components,
+            self.max_depth(),
+            registers,
+            register_paths,
+            unclocked_paths
+        )
+    }
+}
+
+impl Display for TimingReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Timing report:")?;
+        writeln!(f, "  Signal graph is cyclic: {}", self.cyclic)?;
+        writeln!(f, "  Connected components: {}", self.connected_components)?;
+        for clock in self.clocks() {
+            let registers = self.registers.iter().filter(|x| x.clock == clock).count();
+            let paths = self.paths_for_clock(&clock);
+            let depth = paths.iter().map(|x| x.depth).max().unwrap_or(0);
+            writeln!(
+                f,
+                "  Clock {}: {} registers, {} paths, max depth {}",
+                clock,
+                registers,
+                paths.len(),
+                depth
+            )?;
+            for path in paths {
+                write!(f, "    {} -> {} (depth {})", path.from, path.to, path.depth)?;
+                if path.launch_clock != path.capture_clock {
+                    write!(f, " launched by {}", path.launch_clock)?;
+                }
+                writeln!(f)?;
+            }
+        }
+        if !self.unclocked_paths.is_empty() {
+            writeln!(f, "  Unclocked paths:")?;
+            for path in &self.unclocked_paths {
+                writeln!(
+                    f,
+                    "    {} -> {} (depth {})",
+                    path.input, path.output, path.depth
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// The endpoints (register inputs and top level outputs) that can be reached from each node
+// of the graph through combinational logic, with the largest number of assignments needed
+// to reach them.
+struct Endpoints<'a> {
+    graph: &'a Graph<SignalNode, SignalEdgeKind>,
+    outputs: &'a HashSet<NodeIndex>,
+    memo: HashMap<NodeIndex, HashMap<NodeIndex, usize>>,
+    // The nodes being visited, and their position on the stack
+    active: HashMap<NodeIndex, usize>,
+}
+
+impl<'a> Endpoints<'a> {
+    fn reach(&mut self, node: NodeIndex) -> HashMap<NodeIndex, usize> {
+        self.reach_from(node).0
+    }
+    // Also returns the lowest position on the stack of the nodes that the search looped
+    // back to.  The endpoints of a node inside a loop are only complete once the search
+    // returns to the node where it entered the loop, so only those are remembered.
+    fn reach_from(&mut self, node: NodeIndex) -> (HashMap<NodeIndex, usize>, usize) {
+        if let Some(x) = self.memo.get(&node) {
+            return (x.clone(), usize::MAX);
+        }
+        let position = self.active.len();
+        self.active.insert(node, position);
+        let mut lowest = usize::MAX;
+        let mut ret: HashMap<NodeIndex, usize> = Default::default();
+        if self.outputs.contains(&node) {
+            ret.insert(node, 0);
+        }
+        let edges = self
+            .graph
+            .edges(node)
+            .map(|e| (e.target(), *e.weight()))
+            .collect::<Vec<_>>();
+        for (target, kind) in edges {
+            match kind {
+                SignalEdgeKind::Input => {
+                    ret.entry(target).or_insert(0);
+                }
+                SignalEdgeKind::Assign => {
+                    // Don't follow a loop around more than once
+                    if let Some(x) = self.active.get(&target) {
+                        lowest = lowest.min(*x);
+                        continue;
+                    }
+                    let (reached, low) = self.reach_from(target);
+                    lowest = lowest.min(low);
+                    for (endpoint, depth) in reached {
+                        let entry = ret.entry(endpoint).or_insert(0);
+                        *entry = (*entry).max(depth + 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.active.remove(&node);
+        if lowest >= position {
+            self.memo.insert(node, ret.clone());
+        }
+        (ret, lowest)
+    }
+}
+
+// Follow a clock back through the wiring to the signals it comes from (usually a single
+// top level input).  A signal can appear both as a normal and a bidirectional node, and
+// the links between joined interfaces do not say which way the clock flows, so they are
+// followed both ways.
+fn clock_source(
+    g: &Graph<SignalNode, SignalEdgeKind>,
+    names: &HashMap<&str, Vec<NodeIndex>>,
+    node: NodeIndex,
+) -> String {
+    let mut seen = HashSet::new();
+    let mut pending = vec![g[node].name.as_str()];
+    let mut roots = vec![];
+    while let Some(name) = pending.pop() {
+        if !seen.insert(name) {
+            continue;
+        }
+        let nodes = &names[name];
+        let mut driven = false;
+        let mut external = false;
+        for node in nodes {
+            for edge in g.edges_directed(*node, Incoming) {
+                match edge.weight() {
+                    SignalEdgeKind::Assign => {
+                        driven = true;
+                        pending.push(&g[edge.source()].name);
+                    }
+                    SignalEdgeKind::Extern => external = true,
+                    _ => {}
+                }
+            }
+            if g[*node].kind == SignalNodeKind::Bidirectional {
+                driven = true;
+                for edge in g.edges_directed(*node, Outgoing) {
+                    if g[edge.target()].kind == SignalNodeKind::Bidirectional {
+                        pending.push(&g[edge.target()].name);
+                    }
+                }
+            }
+        }
+        if external || !driven {
+            roots.push(display_name(name));
+        }
+    }
+    if roots.is_empty() {
+        return display_name(&g[node].name);
+    }
+    roots.sort();
+    roots.join(", ")
+}
+
+fn find_node(
+    g: &Graph<SignalNode, SignalEdgeKind>,
+    names: &HashMap<&str, Vec<NodeIndex>>,
+    name: &str,
+    kind: SignalNodeKind,
+) -> Option<NodeIndex> {
+    names
+        .get(name)?
+        .iter()
+        .copied()
+        .find(|i| g[*i].kind == kind)
+}
+
+fn display_name(name: &str) -> String {
+    name.replace('$', ".")
+}
+
+/// Analyze the timing of a circuit.  The registers in the circuit are found from
+/// the [TimingInfo](crate::timing::TimingInfo) returned by [Logic::timing](crate::logic::Logic::timing),
+/// and the clock of each register is traced back through the wiring to the signal it comes
+/// from.  A block with custom Verilog and no registers is assumed to connect each of its
+/// inputs to each of its outputs (other than clocks) combinationally.  The report lists the combinational paths between registers, grouped by the
+/// clock that captures them, along with their depth, and the paths from the top level
+/// inputs to the top level outputs that do not pass through any register.
+pub fn check_timing<U: Block + ?Sized>(uut: &U) -> TimingReport {
     let mut scan = TimingChecker::default();
-    uut.accept("top", &mut scan);
-    //println!("{}",&scan.graph);
-    let dot = scan.graph.dot();
-    println!("Graph is cyclic: {}", is_cyclic_directed(&scan.graph.graph));
-    println!(
-        "Number of connected components: {}",
-        connected_components(&scan.graph.graph)
-    );
+    uut.accept("uut", &mut scan);
     let g = &scan.graph.graph;
-    let mut vertex_sets = UnionFind::new(g.node_count());
-    for edge in g.edge_references() {
-        let (a, b) = (edge.source(), edge.target());
-        vertex_sets.union(g.to_index(a), g.to_index(b));
-    }
-    let mut labels = vertex_sets.into_labeling();
-    let mut unique_labels = labels.clone();
-    unique_labels.sort_unstable();
-    unique_labels.dedup();
-    // For each label, we extract the vertices and build a new graph
-    for subgraph in unique_labels {
-        let mut remap: HashMap<_, _> = Default::default();
-        let mut s: Graph<SignalNode, SignalEdgeKind> = Graph::default();
-        for (ndx, label) in labels.iter().enumerate() {
-            if *label == subgraph {
-                let old_index = g.from_index(ndx);
-                let old_weight = g[g.from_index(ndx)].clone();
-                let new_index = s.add_node(old_weight);
-                remap.insert(old_index, new_index);
+    let mut names: HashMap<&str, Vec<NodeIndex>> = HashMap::new();
+    for node in g.node_indices() {
+        names.entry(g[node].name.as_str()).or_default().push(node);
+    }
+    let outputs = scan
+        .outputs
+        .iter()
+        .filter_map(|x| find_node(g, &names, x, SignalNodeKind::Normal))
+        .collect::<HashSet<_>>();
+    let mut endpoints = Endpoints {
+        graph: g,
+        outputs: &outputs,
+        memo: Default::default(),
+        active: Default::default(),
+    };
+    let mut registers = vec![];
+    let mut sinks = HashMap::new();
+    for (name, clock) in &scan.registers {
+        let clock = names
+            .get(clock.as_str())
+            .map(|x| clock_source(g, &names, x[0]))
+            .unwrap_or_default();
+        if let Some(sink) = find_node(g, &names, name, SignalNodeKind::Sink) {
+            sinks.insert(sink, registers.len());
+        }
+        registers.push(TimedRegister {
+            name: display_name(name),
+            clock,
+        });
+    }
+    let mut register_paths = vec![];
+    for (ndx, (name, _)) in scan.registers.iter().enumerate() {
+        let source = match find_node(g, &names, name, SignalNodeKind::Source) {
+            Some(x) => x,
+            None => continue,
+        };
+        let mut reached: HashMap<usize, usize> = Default::default();
+        for edge in g.edges(source) {
+            for (endpoint, depth) in endpoints.reach(edge.target()) {
+                if let Some(capture) = sinks.get(&endpoint) {
+                    let entry = reached.entry(*capture).or_insert(0);
+                    *entry = (*entry).max(depth);
+                }
             }
         }
-        for edge in g.edge_references() {
-            let (a, b) = (edge.source(), edge.target());
-            assert!(!(remap.contains_key(&a) ^ remap.contains_key(&b)));
-            if remap.contains_key(&a) {
-                let a_new = remap.get(&a).unwrap();
-                let b_new = remap.get(&b).unwrap();
-                s.add_edge(*a_new, *b_new, *edge.weight());
+        for (capture, depth) in reached {
+            register_paths.push(RegisterPath {
+                from: registers[ndx].name.clone(),
+                to: registers[capture].name.clone(),
+                launch_clock: registers[ndx].clock.clone(),
+                capture_clock: registers[capture].clock.clone(),
+                depth,
+            });
+        }
+    }
+    let mut unclocked_paths = vec![];
+    for node in g.node_indices() {
+        if g[node].kind != SignalNodeKind::Source || !g[node].name.starts_with("extern$") {
+            continue;
+        }
+        for edge in g.edges(node) {
+            for (endpoint, depth) in endpoints.reach(edge.target()) {
+                if outputs.contains(&endpoint) {
+                    unclocked_paths.push(UnclockedPath {
+                        input: display_name(&g[edge.target()].name),
+                        output: display_name(&g[endpoint].name),
+                        depth,
+                    });
+                }
             }
         }
-        /*        println!("Number of elements in subgraph {}", remap.len());
-        std::fs::write(
-            format!("sub_graph_{}.dot", subgraph),
-            format!("{:?}", Dot::new(&s)),
-        )
-        .unwrap();*/
     }
-    //std::fs::write("dag.dot", dot).unwrap();
+    registers.sort_by(|a, b| a.name.cmp(&b.name));
+    register_paths.sort_by(|a, b| {
+        (&a.capture_clock, &a.from, &a.to).cmp(&(&b.capture_clock, &b.from, &b.to))
+    });
+    unclocked_paths.sort_by(|a, b| (&a.input, &a.output).cmp(&(&b.input, &b.output)));
+    TimingReport {
+        cyclic: is_cyclic_directed(g),
+        connected_components: connected_components(g),
+        registers,
+        register_paths,
+        unclocked_paths,
+    }
 }
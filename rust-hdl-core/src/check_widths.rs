@@ -0,0 +1,340 @@
+use crate::ast::{
+    Verilog, VerilogBlock, VerilogBlockOrConditional, VerilogExpression, VerilogOp, VerilogOpUnary,
+    VerilogStatement,
+};
+use crate::block::Block;
+use crate::check_error::{CheckError, PathedName};
+use crate::module_defines::ModuleDefines;
+use crate::trace_filter::glob_to_regex;
+use num_traits::ToPrimitive;
+use regex::Regex;
+use std::collections::BTreeMap;
+
+/// The kinds of problems found by [check_widths]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum WidthMismatchKind {
+    /// A value is assigned to a narrower signal, and loses its upper bits
+    Truncation,
+    /// A value is assigned to a wider signal, and is padded with zeros
+    Extension,
+    /// Two values of different widths are compared
+    Comparison,
+    /// A bit (or slice of bits) is past the end of the signal
+    OutOfRange,
+}
+
+/// A width mismatch found by [check_widths]
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidthMismatch {
+    /// The signal that is assigned, compared or sliced
+    pub signal: PathedName,
+    pub kind: WidthMismatchKind,
+    /// The width of the signal (or of the left side of a comparison)
+    pub expected: usize,
+    /// The width of the value (or of the right side of a comparison).  For bits that
+    /// are out of range, this is one past the last bit accessed.
+    pub actual: usize,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Width {
+    // The width of a signal, or of an expression built from them
+    Sized(usize),
+    // A literal takes the width of where it is used, so this is just the number of bits it needs
+    Literal(usize),
+}
+
+// The width of the result of an operator that combines the bits of both sides
+fn merge(a: Option<Width>, b: Option<Width>) -> Option<Width> {
+    match (a?, b?) {
+        (Width::Sized(x), Width::Sized(y)) => Some(Width::Sized(x.max(y))),
+        (Width::Sized(x), Width::Literal(_)) | (Width::Literal(_), Width::Sized(x)) => {
+            Some(Width::Sized(x))
+        }
+        (Width::Literal(x), Width::Literal(y)) => Some(Width::Literal(x.max(y))),
+    }
+}
+
+// The name of a signal as it appears in the module definitions
+fn signal_name(x: &str) -> String {
+    x.trim_start_matches('.')
+        .replace('.', "$")
+        .replace("::", "$")
+        .trim_end_matches("$next")
+        .to_owned()
+}
+
+fn first_signal(e: &VerilogExpression) -> Option<String> {
+    match e {
+        VerilogExpression::Signal(x) => Some(signal_name(x)),
+        VerilogExpression::Literal(_) => None,
+        VerilogExpression::Cast(x, _)
+        | VerilogExpression::Signed(x)
+        | VerilogExpression::Unsigned(x)
+        | VerilogExpression::Paren(x)
+        | VerilogExpression::Unary(_, x)
+        | VerilogExpression::Index(x, _)
+        | VerilogExpression::Slice(x, _, _)
+        | VerilogExpression::IndexReplace(x, _, _) => first_signal(x),
+        VerilogExpression::Binary(x, _, y) | VerilogExpression::Ternary(_, x, y) => {
+            first_signal(x).or_else(|| first_signal(y))
+        }
+    }
+}
+
+struct WidthChecker<'a> {
+    module: &'a str,
+    widths: &'a BTreeMap<String, usize>,
+    failures: Vec<WidthMismatch>,
+}
+
+impl<'a> WidthChecker<'a> {
+    fn report(
+        &mut self,
+        e: &VerilogExpression,
+        kind: WidthMismatchKind,
+        expected: usize,
+        actual: usize,
+    ) {
+        self.failures.push(WidthMismatch {
+            signal: PathedName {
+                path: self.module.into(),
+                name: first_signal(e).unwrap_or_default(),
+            },
+            kind,
+            expected,
+            actual,
+        })
+    }
+    // Check that `width` bits starting at `offset` fit in `target` (if the offset is a constant)
+    fn range(
+        &mut self,
+        target: &VerilogExpression,
+        target_width: Option<Width>,
+        offset: &VerilogExpression,
+        width: usize,
+    ) {
+        if let (Some(Width::Sized(target_width)), VerilogExpression::Literal(offset)) =
+            (target_width, offset)
+        {
+            let end = offset
+                .as_biguint()
+                .to_usize()
+                .map_or(usize::MAX, |x| x.saturating_add(width));
+            if end > target_width {
+                self.report(target, WidthMismatchKind::OutOfRange, target_width, end);
+            }
+        }
+    }
+    fn assign(&mut self, target: &VerilogExpression, to: Option<Width>, from: Option<Width>) {
+        match (to, from) {
+            (Some(Width::Sized(to)), Some(Width::Sized(from))) if from > to => {
+                self.report(target, WidthMismatchKind::Truncation, to, from)
+            }
+            (Some(Width::Sized(to)), Some(Width::Sized(from))) if from < to => {
+                self.report(target, WidthMismatchKind::Extension, to, from)
+            }
+            (Some(Width::Sized(to)), Some(Width::Literal(from))) if from > to => {
+                self.report(target, WidthMismatchKind::Truncation, to, from)
+            }
+            _ => {}
+        }
+    }
+    fn compare(&mut self, e: &VerilogExpression, left: Option<Width>, right: Option<Width>) {
+        let (x, y) = match (left, right) {
+            (Some(Width::Sized(x)), Some(Width::Sized(y))) if x != y => (x, y),
+            (Some(Width::Sized(x)), Some(Width::Literal(y))) if y > x => (x, y),
+            (Some(Width::Literal(x)), Some(Width::Sized(y))) if x > y => (x, y),
+            _ => return,
+        };
+        self.report(e, WidthMismatchKind::Comparison, x, y);
+    }
+    fn width(&mut self, e: &VerilogExpression) -> Option<Width> {
+        match e {
+            VerilogExpression::Signal(x) => {
+                self.widths.get(&signal_name(x)).map(|x| Width::Sized(*x))
+            }
+            VerilogExpression::Literal(x) => Some(Width::Literal(x.as_biguint().bits() as usize)),
+            VerilogExpression::Cast(x, bits) => {
+                self.width(x);
+                Some(Width::Sized(*bits))
+            }
+            VerilogExpression::Signed(x)
+            | VerilogExpression::Unsigned(x)
+            | VerilogExpression::Paren(x) => self.width(x),
+            VerilogExpression::Binary(x, op, y) => {
+                let left = self.width(x);
+                let right = self.width(y);
+                match op {
+                    VerilogOp::Eq
+                    | VerilogOp::Ne
+                    | VerilogOp::Lt
+                    | VerilogOp::Le
+                    | VerilogOp::Gt
+                    | VerilogOp::Ge => {
+                        self.compare(e, left, right);
+                        Some(Width::Sized(1))
+                    }
+                    VerilogOp::LogicalAnd | VerilogOp::LogicalOr => Some(Width::Sized(1)),
+                    VerilogOp::Shl | VerilogOp::Shr => left,
+                    _ => merge(left, right),
+                }
+            }
+            VerilogExpression::Unary(op, x) => {
+                let width = self.width(x);
+                match op {
+                    VerilogOpUnary::Not | VerilogOpUnary::Neg => width,
+                    _ => Some(Width::Sized(1)),
+                }
+            }
+            VerilogExpression::Index(x, ndx) => {
+                let width = self.width(x);
+                self.width(ndx);
+                self.range(x, width, ndx, 1);
+                Some(Width::Sized(1))
+            }
+            VerilogExpression::Slice(x, bits, offset) => {
+                let width = self.width(x);
+                self.width(offset);
+                self.range(x, width, offset, *bits);
+                Some(Width::Sized(*bits))
+            }
+            VerilogExpression::IndexReplace(x, ndx, value) => {
+                let width = self.width(x);
+                self.width(ndx);
+                self.width(value);
+                self.range(x, width, ndx, 1);
+                width
+            }
+            VerilogExpression::Ternary(sel, on_true, on_false) => {
+                self.width(sel);
+                let on_true = self.width(on_true);
+                let on_false = self.width(on_false);
+                merge(on_true, on_false)
+            }
+        }
+    }
+    fn block(&mut self, block: &VerilogBlock) {
+        for statement in block {
+            self.statement(statement);
+        }
+    }
+    fn statement(&mut self, statement: &VerilogStatement) {
+        match statement {
+            VerilogStatement::Assignment(target, value) => {
+                let to = self.width(target);
+                let from = self.width(value);
+                self.assign(target, to, from);
+            }
+            VerilogStatement::SliceAssignment {
+                base,
+                width,
+                offset,
+                replacement,
+            } => {
+                let base_width = self.width(base);
+                self.width(offset);
+                self.range(base, base_width, offset, *width);
+                let from = self.width(replacement);
+                self.assign(base, Some(Width::Sized(*width)), from);
+            }
+            VerilogStatement::If(c) => {
+                self.width(&c.test);
+                self.block(&c.then);
+                match &c.otherwise {
+                    VerilogBlockOrConditional::Block(block) => self.block(block),
+                    VerilogBlockOrConditional::Conditional(statement) => self.statement(statement),
+                    VerilogBlockOrConditional::None => {}
+                }
+            }
+            VerilogStatement::Match(m) => {
+                self.width(&m.test);
+                for case in &m.cases {
+                    self.block(&case.block);
+                }
+            }
+            VerilogStatement::Loop(l) => self.block(&l.block),
+            VerilogStatement::Macro(block) => self.block(block),
+            VerilogStatement::Comment(_) | VerilogStatement::Link(_) => {}
+        }
+    }
+}
+
+/// A [WidthCheck] runs [check_widths] with an allow-list, for the cases where a
+/// mismatch is intentional (although an explicit `bit_cast` is usually clearer).
+/// The allow-list is a set of globs over the hierarchical name of the signal in the
+/// report, written with `.` as the separator (i.e., `uut.counter.count` or `uut.sdram.*`).
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// #[derive(LogicBlock, Default)]
+/// struct Narrow {
+///    pub wide: Signal<In, Bits<16>>,
+///    pub narrow: Signal<Out, Bits<8>>,
+/// }
+///
+/// impl Logic for Narrow {
+///    #[hdl_gen]
+///    fn update(&mut self) {
+///       self.narrow.next = self.wide.val().get_bits::<8>(12);
+///    }
+/// }
+///
+/// let mut uut = Narrow::default(); uut.connect_all();
+/// assert!(check_widths(&uut).is_err());
+/// assert!(WidthCheck::default().allow("uut.wide").check(&uut).is_ok());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct WidthCheck {
+    allowed: Vec<String>,
+}
+
+impl WidthCheck {
+    /// Allow mismatches on the signals whose name matches `glob`
+    pub fn allow(mut self, glob: &str) -> Self {
+        self.allowed.push(glob.into());
+        self
+    }
+    /// Check the circuit, skipping the allowed signals
+    pub fn check(&self, uut: &dyn Block) -> Result<(), CheckError> {
+        let allowed = self
+            .allowed
+            .iter()
+            .map(|x| glob_to_regex(x))
+            .collect::<Vec<Regex>>();
+        let mut defines = ModuleDefines::default();
+        uut.accept("uut", &mut defines);
+        let mut failures = vec![];
+        for (module, widths, code) in defines.module_code() {
+            if let Verilog::Combinatorial(code) = &code {
+                let mut checker = WidthChecker {
+                    module: &module,
+                    widths: &widths,
+                    failures: vec![],
+                };
+                checker.block(code);
+                failures.extend(checker.failures);
+            }
+        }
+        failures.retain(|x| {
+            let name = format!("{}${}", x.signal.path, x.signal.name).replace('$', ".");
+            !allowed.iter().any(|glob| glob.is_match(&name))
+        });
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(CheckError::WidthMismatches(failures))
+        }
+    }
+}
+
+/// Check the HDL kernels of a circuit for mismatched widths, using the widths of
+/// the signals in each module.  This reports assignments that truncate or extend
+/// the value, comparisons between values of different widths, and bits (or slices,
+/// like those from `get_bits`) that are past the end of a signal.  Literals take their
+/// width from where they are used (as in Verilog), so they are only reported if their
+/// value does not fit.  An explicit `bit_cast` marks a change of width as intentional.
+/// To skip other intentional cases, use a [WidthCheck] with an allow-list.
+pub fn check_widths(uut: &dyn Block) -> Result<(), CheckError> {
+    WidthCheck::default().check(uut)
+}
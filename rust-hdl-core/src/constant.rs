@@ -1,15 +1,16 @@
 use crate::ast::VerilogLiteral;
-use crate::atom::{Atom, AtomKind};
+use crate::atom::{Atom, AtomKind, AtomMut};
 use crate::bits::Bits;
 use crate::block::Block;
 use crate::constraint::PinConstraint;
 use crate::logic::Logic;
-use crate::probe::Probe;
+use crate::probe::{Probe, ProbeMut};
 use crate::signal::{get_signal_id, Signal};
 use crate::sim_assert_eq;
 use crate::simulate::{Sim, Simulation};
 use crate::synth::{Synth, VCDValue};
 use crate::type_descriptor::TypeDescriptor;
+use num_bigint::BigUint;
 
 /// The [Constant] wrapper can hold any [Synth] type
 /// and store it in a circuit for use by the HDL kernel.
@@ -136,7 +137,46 @@ impl<T: Synth> Block for Constant<T> {
         false
     }
 
+    fn has_changed_ports(&self) -> bool {
+        false
+    }
+
+    fn has_changed_interface(&self) -> bool {
+        false
+    }
+
+    fn update_pending(&mut self) {}
+
     fn accept(&self, name: &str, probe: &mut dyn Probe) {
         probe.visit_atom(name, self);
     }
+
+    fn accept_mut(&mut self, name: &str, probe: &mut dyn ProbeMut) {
+        probe.visit_atom(name, self);
+    }
+}
+
+impl<T: Synth> AtomMut for Constant<T> {
+    fn next_bits(&self) -> BigUint {
+        self.val.verilog().as_biguint()
+    }
+
+    // A constant cannot be driven
+    fn set_bits(&mut self, _bits: &BigUint) -> bool {
+        false
+    }
+
+    fn set_next_bits(&mut self, _bits: &BigUint) -> bool {
+        false
+    }
+
+    fn force_bits(&mut self, _bits: &BigUint) -> bool {
+        false
+    }
+
+    fn release(&mut self) {}
+
+    fn set_unknown(&mut self) -> bool {
+        false
+    }
 }
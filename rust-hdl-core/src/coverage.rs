@@ -0,0 +1,746 @@
+use crate::ast::{Verilog, VerilogBlockOrConditional, VerilogExpression, VerilogStatement};
+use crate::atom::{Atom, AtomKind};
+use crate::bits::clog2;
+use crate::block::Block;
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+use crate::synth::VCDValue;
+use crate::tracer::Tracer;
+use crate::type_descriptor::{TypeDescriptor, TypeKind};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+// Enum values are reported by variant name, while the type descriptor
+// holds the fully qualified names (i.e., `State::Idle`).
+pub(crate) fn enum_label_index(labels: &[String], value: &str) -> Option<usize> {
+    let suffix = format!("::{}", value);
+    labels
+        .iter()
+        .position(|x| x.ends_with(&suffix) || x.eq(value))
+}
+
+// Flatten the value of a signal into its bits, LSB first.  `None` is used for
+// bits that are neither 0 nor 1.
+fn flatten(descriptor: &TypeDescriptor, val: &VCDValue, bits: &mut Vec<Option<bool>>) {
+    fn bit(x: &vcd::Value) -> Option<bool> {
+        match x {
+            vcd::Value::V0 => Some(false),
+            vcd::Value::V1 => Some(true),
+            _ => None,
+        }
+    }
+    match (&descriptor.kind, val) {
+        (_, VCDValue::Single(x)) => bits.push(bit(x)),
+        (_, VCDValue::Vector(x)) => bits.extend(x.iter().rev().map(bit)),
+        (TypeKind::Enum(labels), VCDValue::String(t)) => {
+            let width = clog2(labels.len()).max(1);
+            match enum_label_index(labels, t) {
+                Some(index) => bits.extend((0..width).map(|i| Some(index & (1 << i) != 0))),
+                None => bits.extend((0..width).map(|_| None)),
+            }
+        }
+        (TypeKind::Composite(fields), VCDValue::Composite(vals)) => {
+            for (field, val) in fields.iter().zip(vals.iter()) {
+                flatten(&field.kind, val, bits);
+            }
+        }
+        _ => panic!("Mismatch in values versus type information"),
+    }
+}
+
+pub(crate) fn atom_bits(signal: &dyn Atom) -> Vec<Option<bool>> {
+    let mut bits = vec![];
+    flatten(&signal.descriptor(), &signal.vcd(), &mut bits);
+    bits
+}
+
+#[derive(Clone, Debug)]
+struct ToggleRecord {
+    module: String,
+    name: String,
+    last: Vec<Option<bool>>,
+    rose: Vec<bool>,
+    fell: Vec<bool>,
+}
+
+impl ToggleRecord {
+    fn update(&mut self, bits: Vec<Option<bool>>) {
+        for (ndx, (last, now)) in self.last.iter().zip(bits.iter()).enumerate() {
+            match (last, now) {
+                (Some(false), Some(true)) => self.rose[ndx] = true,
+                (Some(true), Some(false)) => self.fell[ndx] = true,
+                _ => {}
+            }
+        }
+        self.last = bits;
+    }
+}
+
+/// A bit of a signal that did not toggle in both directions during a simulation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UntoggledBit {
+    /// The hierarchical name of the signal, i.e., `uut.fifo.read`
+    pub signal: String,
+    /// The index of the bit within the signal (0 is the LSB)
+    pub bit: usize,
+    /// `true` if a 0 -> 1 transition was seen
+    pub rose: bool,
+    /// `true` if a 1 -> 0 transition was seen
+    pub fell: bool,
+}
+
+/// Toggle coverage for the signals of a single module (not including its children).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleToggleSummary {
+    /// The hierarchical name of the module, i.e., `uut.fifo`
+    pub module: String,
+    /// The total number of signal bits in the module
+    pub bits: usize,
+    /// The number of bits that toggled in both directions
+    pub toggled: usize,
+}
+
+/// The [ToggleCoverage] collector records the 0->1 and 1->0 transitions of every bit of
+/// every signal in the circuit.  A bit is covered if it has toggled in both directions.
+/// Attach it to a simulation with [Simulation::add_observer](crate::simulate::Simulation::add_observer),
+/// and inspect it once the simulation is complete.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Foo {
+///    pub clock: Signal<In, Clock>
+/// }
+///
+/// impl Logic for Foo {
+///   #[hdl_gen]
+///   fn update(&mut self) {
+///   }
+/// }
+///
+/// let mut sim : Simulation<Foo> = Default::default();
+/// sim.add_clock(5, |x| x.clock.next = !x.clock.val());
+/// sim.add_testbench(|mut ep: Sim<Foo>| {
+///     let x = ep.init()?;
+///     let x = ep.wait(100, x)?;
+///     ep.done(x)
+/// });
+/// let coverage = sim.add_observer(ToggleCoverage::default());
+/// sim.run(Box::new(Foo::default()), 1000).unwrap();
+/// assert!(coverage.borrow().untoggled().is_empty());
+/// println!("{}", coverage.borrow());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ToggleCoverage {
+    records: Vec<ToggleRecord>,
+    index: HashMap<usize, usize>,
+}
+
+struct ToggleRegister<'a> {
+    coverage: &'a mut ToggleCoverage,
+    module: NamedPath,
+    path: NamedPath,
+}
+
+impl<'a> Probe for ToggleRegister<'a> {
+    fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
+        self.module.push(name);
+        self.path.push(name);
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        if signal.kind() == AtomKind::Constant {
+            return;
+        }
+        self.path.push(name);
+        let last = atom_bits(signal);
+        self.coverage
+            .index
+            .insert(signal.id(), self.coverage.records.len());
+        self.coverage.records.push(ToggleRecord {
+            module: self.module.flat("."),
+            name: self.path.flat("."),
+            rose: vec![false; last.len()],
+            fell: vec![false; last.len()],
+            last,
+        });
+        self.path.pop();
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.module.pop();
+        self.path.pop();
+    }
+}
+
+struct ToggleUpdate<'a>(&'a mut ToggleCoverage);
+
+impl<'a> Probe for ToggleUpdate<'a> {
+    fn visit_atom(&mut self, _name: &str, signal: &dyn Atom) {
+        if let Some(ndx) = self.0.index.get(&signal.id()) {
+            self.0.records[*ndx].update(atom_bits(signal));
+        }
+    }
+}
+
+impl Tracer for ToggleCoverage {
+    fn start(&mut self, uut: &dyn Block) {
+        self.records.clear();
+        self.index.clear();
+        uut.accept(
+            "uut",
+            &mut ToggleRegister {
+                coverage: self,
+                module: Default::default(),
+                path: Default::default(),
+            },
+        );
+    }
+
+    fn step(&mut self, _time: u64, uut: &dyn Block) {
+        uut.accept("uut", &mut ToggleUpdate(self));
+    }
+}
+
+impl ToggleCoverage {
+    /// The bits that have not toggled in both directions, by hierarchical name.
+    pub fn untoggled(&self) -> Vec<UntoggledBit> {
+        self.records
+            .iter()
+            .flat_map(|record| {
+                (0..record.last.len())
+                    .filter(|ndx| !(record.rose[*ndx] && record.fell[*ndx]))
+                    .map(|ndx| UntoggledBit {
+                        signal: record.name.clone(),
+                        bit: ndx,
+                        rose: record.rose[ndx],
+                        fell: record.fell[ndx],
+                    })
+            })
+            .collect()
+    }
+    /// A summary of the toggle coverage of each module, in hierarchical order.
+    pub fn summary(&self) -> Vec<ModuleToggleSummary> {
+        let mut modules: BTreeMap<&str, ModuleToggleSummary> = BTreeMap::new();
+        for record in &self.records {
+            let entry = modules
+                .entry(&record.module)
+                .or_insert_with(|| ModuleToggleSummary {
+                    module: record.module.clone(),
+                    bits: 0,
+                    toggled: 0,
+                });
+            entry.bits += record.last.len();
+            entry.toggled += record
+                .rose
+                .iter()
+                .zip(record.fell.iter())
+                .filter(|(r, f)| **r && **f)
+                .count();
+        }
+        modules.into_values().collect()
+    }
+    /// A machine-readable (JSON) report of the coverage, with the per-module summary
+    /// and the list of bits that did not toggle.
+    pub fn to_json(&self) -> String {
+        let modules = self
+            .summary()
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"module\":\"{}\",\"bits\":{},\"toggled\":{}}}",
+                    x.module, x.bits, x.toggled
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        let untoggled = self
+            .untoggled()
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"signal\":\"{}\",\"bit\":{},\"rose\":{},\"fell\":{}}}",
+                    x.signal, x.bit, x.rose, x.fell
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{\"modules\":[{}],\"untoggled\":[{}]}}",
+            modules, untoggled
+        )
+    }
+}
+
+impl Display for ToggleCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Toggle coverage:")?;
+        for module in self.summary() {
+            let percent = if module.bits == 0 {
+                100.0
+            } else {
+                100.0 * module.toggled as f64 / module.bits as f64
+            };
+            writeln!(
+                f,
+                "  {:<40} {:>6}/{:<6} {:>6.1}%",
+                module.module, module.toggled, module.bits, percent
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FsmRecord {
+    name: String,
+    states: Vec<String>,
+    visited: Vec<bool>,
+    legal: Vec<Vec<bool>>,
+    transitions: Vec<Vec<bool>>,
+    last: Option<usize>,
+}
+
+impl FsmRecord {
+    fn update(&mut self, state: Option<usize>) {
+        if let Some(state) = state {
+            self.visited[state] = true;
+            if let Some(last) = self.last {
+                if last != state {
+                    self.transitions[last][state] = true;
+                }
+            }
+        }
+        self.last = state;
+    }
+}
+
+/// The state and transition coverage of a single state machine.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FsmCoverageReport {
+    /// The hierarchical name of the state register, i.e., `uut.controller.state`
+    pub name: String,
+    /// All of the states of the machine
+    pub states: Vec<String>,
+    /// The states that were never entered
+    pub unvisited_states: Vec<String>,
+    /// The transitions (from, to) between distinct states that were taken at least once
+    pub taken_transitions: Vec<(String, String)>,
+    /// The transitions (from, to) that the kernel can make, but were never taken
+    pub untaken_transitions: Vec<(String, String)>,
+}
+
+impl FsmCoverageReport {
+    /// Returns `true` if every state of the machine was entered, and every transition taken.
+    pub fn is_complete(&self) -> bool {
+        self.unvisited_states.is_empty() && self.untaken_transitions.is_empty()
+    }
+}
+
+// The name of the state in an enum value or pattern of a kernel (i.e., `Light$Red`)
+fn state_index(states: &[String], name: &str) -> Option<usize> {
+    enum_label_index(states, name.rsplit('$').next()?)
+}
+
+// Find the transitions that a kernel can make from the assignments to the `d` input of the
+// state register `dff`.  An assignment in an arm of a `match` on the `q` output of the register
+// is a transition from the state of that arm, and any other assignment is a transition from
+// every state in `from`.
+fn legal_transitions(
+    block: &[VerilogStatement],
+    dff: &str,
+    states: &[String],
+    from: &[usize],
+    legal: &mut [Vec<bool>],
+) {
+    for statement in block {
+        match statement {
+            VerilogStatement::Assignment(
+                VerilogExpression::Signal(target),
+                VerilogExpression::Signal(value),
+            ) if *target == format!("{}$d$next", dff) => {
+                if let Some(to) = state_index(states, value) {
+                    for state in from {
+                        legal[*state][to] |= *state != to;
+                    }
+                }
+            }
+            VerilogStatement::If(cond) => {
+                legal_transitions(&cond.then, dff, states, from, legal);
+                match &cond.otherwise {
+                    VerilogBlockOrConditional::Block(block) => {
+                        legal_transitions(block, dff, states, from, legal)
+                    }
+                    VerilogBlockOrConditional::Conditional(statement) => legal_transitions(
+                        std::slice::from_ref(statement.as_ref()),
+                        dff,
+                        states,
+                        from,
+                        legal,
+                    ),
+                    VerilogBlockOrConditional::None => {}
+                }
+            }
+            VerilogStatement::Match(m) => {
+                let on_state =
+                    matches!(&m.test, VerilogExpression::Signal(x) if *x == format!("{}$q", dff));
+                let listed = m
+                    .cases
+                    .iter()
+                    .filter_map(|case| state_index(states, &case.condition))
+                    .collect::<Vec<_>>();
+                for case in &m.cases {
+                    let arm = if !on_state {
+                        from.to_vec()
+                    } else if case.condition == "default" {
+                        from.iter()
+                            .copied()
+                            .filter(|x| !listed.contains(x))
+                            .collect()
+                    } else {
+                        state_index(states, &case.condition)
+                            .filter(|x| from.contains(x))
+                            .into_iter()
+                            .collect()
+                    };
+                    legal_transitions(&case.block, dff, states, &arm, legal);
+                }
+            }
+            VerilogStatement::Loop(l) => legal_transitions(&l.block, dff, states, from, legal),
+            VerilogStatement::Macro(block) => legal_transitions(block, dff, states, from, legal),
+            _ => {}
+        }
+    }
+}
+
+/// The [FsmCoverage] collector finds every state machine in the circuit (i.e., every `DFF`
+/// that holds a `#[derive(LogicState)]` enum), and records which states were visited, and
+/// which state-to-state transitions were taken.  The states are named as in the
+/// [TypeKind::Enum] of the state type.  The transitions that a state machine can make are
+/// read from the `hdl_gen` kernel that drives its register: an assignment to the `d` input in
+/// an arm of a `match` on the `q` output is a transition from the state of that arm, and one
+/// outside of such a `match` is a transition from every state.  Enable it for a simulation
+/// with [Simulation::enable_fsm_coverage](crate::simulate::Simulation::enable_fsm_coverage),
+/// which also reports the state machines with unvisited states or untaken transitions at the
+/// end of a run.
+#[derive(Clone, Debug, Default)]
+pub struct FsmCoverage {
+    records: Vec<FsmRecord>,
+    index: HashMap<usize, usize>,
+}
+
+fn enum_state(signal: &dyn Atom, labels: &[String]) -> Option<usize> {
+    match signal.vcd() {
+        VCDValue::String(t) => enum_label_index(labels, &t),
+        _ => None,
+    }
+}
+
+struct FsmRegister<'a> {
+    coverage: &'a mut FsmCoverage,
+    path: NamedPath,
+    // The kernel of each block in the current scope
+    kernels: Vec<Verilog>,
+}
+
+impl<'a> Probe for FsmRegister<'a> {
+    fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
+        self.path.push(name);
+        self.kernels.push(node.hdl());
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        // The state of a state machine is held in the output of a DFF
+        if name != "q" || signal.kind() != AtomKind::OutputParameter {
+            return;
+        }
+        if let TypeKind::Enum(labels) = signal.descriptor().kind {
+            let n = labels.len();
+            let mut legal = vec![vec![false; n]; n];
+            // The register is driven by the kernel of the block that holds it
+            if let Some(Verilog::Combinatorial(kernel)) = self.kernels.iter().rev().nth(1) {
+                let all = (0..n).collect::<Vec<_>>();
+                legal_transitions(kernel, &self.path.last(), &labels, &all, &mut legal);
+            }
+            self.coverage
+                .index
+                .insert(signal.id(), self.coverage.records.len());
+            self.coverage.records.push(FsmRecord {
+                name: self.path.flat("."),
+                last: None,
+                visited: vec![false; n],
+                legal,
+                transitions: vec![vec![false; n]; n],
+                states: labels,
+            });
+        }
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+        self.kernels.pop();
+    }
+}
+
+struct FsmUpdate<'a>(&'a mut FsmCoverage);
+
+impl<'a> Probe for FsmUpdate<'a> {
+    fn visit_atom(&mut self, _name: &str, signal: &dyn Atom) {
+        if let Some(ndx) = self.0.index.get(&signal.id()) {
+            let record = &mut self.0.records[*ndx];
+            let state = enum_state(signal, &record.states);
+            record.update(state);
+        }
+    }
+}
+
+impl Tracer for FsmCoverage {
+    fn start(&mut self, uut: &dyn Block) {
+        self.records.clear();
+        self.index.clear();
+        uut.accept(
+            "uut",
+            &mut FsmRegister {
+                coverage: self,
+                path: Default::default(),
+                kernels: vec![],
+            },
+        );
+    }
+
+    fn step(&mut self, _time: u64, uut: &dyn Block) {
+        if !self.records.is_empty() {
+            uut.accept("uut", &mut FsmUpdate(self));
+        }
+    }
+}
+
+impl FsmCoverage {
+    /// The coverage of each state machine found in the circuit.
+    pub fn reports(&self) -> Vec<FsmCoverageReport> {
+        self.records
+            .iter()
+            .map(|record| {
+                let states = &record.states;
+                let mut taken_transitions = vec![];
+                let mut untaken_transitions = vec![];
+                for from in 0..states.len() {
+                    for to in 0..states.len() {
+                        let pair = (states[from].clone(), states[to].clone());
+                        if record.transitions[from][to] {
+                            taken_transitions.push(pair);
+                        } else if record.legal[from][to] {
+                            untaken_transitions.push(pair);
+                        }
+                    }
+                }
+                FsmCoverageReport {
+                    name: record.name.clone(),
+                    states: states.clone(),
+                    unvisited_states: states
+                        .iter()
+                        .zip(record.visited.iter())
+                        .filter(|(_, visited)| !**visited)
+                        .map(|(state, _)| state.clone())
+                        .collect(),
+                    taken_transitions,
+                    untaken_transitions,
+                }
+            })
+            .collect()
+    }
+    /// The coverage report for the state machine with the given hierarchical name.
+    pub fn report(&self, name: &str) -> Option<FsmCoverageReport> {
+        self.reports().into_iter().find(|x| x.name == name)
+    }
+    /// Returns `true` if every state machine entered all of its states, and took all of its transitions.
+    pub fn is_complete(&self) -> bool {
+        self.reports().iter().all(|x| x.is_complete())
+    }
+}
+
+impl Display for FsmCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "FSM coverage:")?;
+        for report in self.reports() {
+            let n = report.states.len();
+            let taken = report.taken_transitions.len();
+            writeln!(
+                f,
+                "  {:<40} states {:>3}/{:<3} transitions {:>3}/{:<3}",
+                report.name,
+                n - report.unvisited_states.len(),
+                n,
+                taken,
+                taken + report.untaken_transitions.len()
+            )?;
+            for state in &report.unvisited_states {
+                writeln!(f, "    unvisited state {}", state)?;
+            }
+            for (from, to) in &report.untaken_transitions {
+                writeln!(f, "    untaken transition {} -> {}", from, to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A single branch arm of an `hdl_gen` kernel.  These are created by the `hdl_gen` macro
+/// when the `branch-coverage` feature is enabled, and should not be used directly.
+#[doc(hidden)]
+pub struct BranchSite {
+    file: &'static str,
+    line: u32,
+    arm: &'static str,
+    hits: AtomicU64,
+}
+
+impl BranchSite {
+    pub const fn new(file: &'static str, line: u32, arm: &'static str) -> Self {
+        Self {
+            file,
+            line,
+            arm,
+            hits: AtomicU64::new(0),
+        }
+    }
+    pub fn hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+static BRANCH_REGISTRY: Mutex<Vec<(String, &'static [BranchSite])>> = Mutex::new(Vec::new());
+
+#[doc(hidden)]
+pub fn register_branches(type_name: &str, function: &str, sites: &'static [BranchSite]) {
+    // Strip the module path and any generic parameters from the type name
+    let type_name = type_name.split('<').next().unwrap_or(type_name);
+    let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+    BRANCH_REGISTRY
+        .lock()
+        .unwrap()
+        .push((format!("{}::{}", type_name, function), sites));
+}
+
+/// The number of times a branch arm of an `hdl_gen` kernel was taken.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BranchReport {
+    /// The kernel that contains the branch, i.e., `BaseController::update`
+    pub kernel: String,
+    /// The Rust source file of the kernel
+    pub file: String,
+    /// The line of the branch arm in the source file
+    pub line: u32,
+    /// A description of the arm, i.e., `match State::Idle` or `else (of if self.reset.val())`
+    pub arm: String,
+    /// The number of times the arm was taken
+    pub hits: u64,
+}
+
+/// Branch coverage of the `hdl_gen` kernels.  This is opt-in - when the `branch-coverage`
+/// feature is enabled, each `if` arm (including a missing `else`) and each `match` arm of the
+/// `update` function of an `hdl_gen` kernel counts the number of times it is taken during
+/// simulation.  The counters are global, and accumulate across all simulations run in the
+/// process (use [BranchCoverage::reset] to clear them).  Kernels that have never been
+/// simulated do not appear in the report.  The kernels of a generic block are named
+/// without their generic parameters, and count the branches of all of its instantiations together.
+///
+/// ```rust,no_run
+/// # use rust_hdl_core::prelude::*;
+/// // ... run some simulations, then
+/// let coverage = BranchCoverage::collect();
+/// for branch in coverage.untaken() {
+///     println!("{}:{} {} never taken", branch.file, branch.line, branch.arm);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct BranchCoverage {
+    branches: Vec<BranchReport>,
+}
+
+impl BranchCoverage {
+    /// Capture the current state of the branch counters.
+    pub fn collect() -> BranchCoverage {
+        let registry = BRANCH_REGISTRY.lock().unwrap();
+        let mut branches = vec![];
+        for (kernel, sites) in registry.iter() {
+            for site in sites.iter() {
+                branches.push(BranchReport {
+                    kernel: kernel.clone(),
+                    file: site.file.into(),
+                    line: site.line,
+                    arm: site.arm.into(),
+                    hits: site.hits.load(Ordering::Relaxed),
+                });
+            }
+        }
+        BranchCoverage { branches }
+    }
+    /// Clear all of the branch counters.
+    pub fn reset() {
+        for (_, sites) in BRANCH_REGISTRY.lock().unwrap().iter() {
+            for site in sites.iter() {
+                site.hits.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+    /// All of the instrumented branches.
+    pub fn branches(&self) -> &[BranchReport] {
+        &self.branches
+    }
+    /// The branches of the given kernel, i.e., `BaseController::update`.
+    pub fn kernel(&self, kernel: &str) -> Vec<&BranchReport> {
+        self.branches
+            .iter()
+            .filter(|x| x.kernel == kernel)
+            .collect()
+    }
+    /// The branches that were never taken.
+    pub fn untaken(&self) -> Vec<&BranchReport> {
+        self.branches.iter().filter(|x| x.hits == 0).collect()
+    }
+}
+
+impl Display for BranchCoverage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Branch coverage:")?;
+        let mut kernels: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for branch in &self.branches {
+            let entry = kernels.entry(&branch.kernel).or_default();
+            entry.0 += 1;
+            if branch.hits != 0 {
+                entry.1 += 1;
+            }
+        }
+        for (kernel, (total, taken)) in kernels {
+            writeln!(f, "  {:<40} {:>4}/{:<4} arms taken", kernel, taken, total)?;
+            for branch in self.untaken().iter().filter(|x| x.kernel == kernel) {
+                writeln!(
+                    f,
+                    "    {}:{} never took {}",
+                    branch.file, branch.line, branch.arm
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
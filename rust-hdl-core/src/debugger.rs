@@ -0,0 +1,299 @@
+use crate::atom::Atom;
+use crate::block::Block;
+use crate::coverage::atom_bits;
+use crate::signal_handle::{signal_paths, visit_signals, SignalHandle};
+use crate::simulate::Monitor;
+use crate::synth::VCDValue;
+use std::io::{BufRead, BufReader, Write};
+
+const HELP: &str = "\
+Commands:
+  c, continue           run until the next breakpoint
+  s, step [n]           run for n time steps (default 1)
+  n, next [n] [clock]   run for n cycles of a clock (default 1 cycle of the first clock)
+  p, print <name>...    print the signals whose path contains any of the names
+  l, list [name]        list the signals (whose path contains the name)
+  b, breakpoints        list the breakpoints
+  d, disable <name>     disable a breakpoint
+  e, enable <name>      enable a breakpoint
+  t, time               print the simulation time
+  q, quit               detach the debugger, and let the simulation run to completion
+  h, help               print this message";
+
+struct Breakpoint<T> {
+    name: String,
+    condition: Box<dyn Fn(&T) -> bool>,
+    enabled: bool,
+    active: bool,
+}
+
+enum Mode {
+    Run,
+    Steps(u64),
+    Cycles {
+        clock: SignalHandle,
+        remaining: u64,
+        last: Option<bool>,
+    },
+    Detached,
+}
+
+fn format_value(signal: &dyn Atom) -> String {
+    if let VCDValue::String(label) = signal.vcd() {
+        return label;
+    }
+    let bits = atom_bits(signal);
+    if bits.iter().any(|x| x.is_none()) {
+        let pattern = bits
+            .iter()
+            .rev()
+            .map(|x| match x {
+                Some(true) => '1',
+                Some(false) => '0',
+                None => 'x',
+            })
+            .collect::<String>();
+        return format!("{}'b{}", bits.len(), pattern);
+    }
+    let literal = signal.verilog();
+    format!("{}'h{:x}", signal.bits(), literal.as_biguint())
+}
+
+// The value of every signal in the circuit, by its full path (i.e., `uut.fifo.clock`)
+fn signal_values(x: &dyn Block) -> Vec<(String, String)> {
+    let mut values = vec![];
+    visit_signals(x, |path, _, signal| {
+        values.push((path.to_string(), format_value(signal)))
+    });
+    values
+}
+
+/// An interactive debugger for a simulation.  Attach it to a simulation with
+/// [Simulation::add_monitor](crate::simulate::Simulation::add_monitor).  The simulation
+/// stops when one of the breakpoints is hit (i.e., its condition changes from false to true),
+/// and then reads commands (type `help` for a list) until told to continue.  While stopped,
+/// the values of the signals in the circuit can be printed by their hierarchical name
+/// (e.g., `uut.router.clock`), and the simulation can be stepped by time steps or clock
+/// cycles.  By default, the commands are read from `stdin`, and the responses written to
+/// `stdout`.  At the end of the input, the debugger detaches, and the simulation runs to completion.
+pub struct Debugger<T> {
+    breakpoints: Vec<Breakpoint<T>>,
+    mode: Mode,
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+}
+
+impl<T: Block> Default for Debugger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Block> Debugger<T> {
+    /// Create a debugger that reads commands from `stdin`
+    pub fn new() -> Self {
+        Self::with_io(BufReader::new(std::io::stdin()), std::io::stdout())
+    }
+    /// Create a debugger that reads commands from `input`, and writes to `output`
+    pub fn with_io<R: BufRead + 'static, W: Write + 'static>(input: R, output: W) -> Self {
+        Self {
+            breakpoints: vec![],
+            mode: Mode::Run,
+            input: Box::new(input),
+            output: Box::new(output),
+        }
+    }
+    /// Add a breakpoint that stops the simulation when `condition` becomes true.
+    pub fn breakpoint<F: Fn(&T) -> bool + 'static>(mut self, name: &str, condition: F) -> Self {
+        self.breakpoints.push(Breakpoint {
+            name: name.into(),
+            condition: Box::new(condition),
+            enabled: true,
+            active: false,
+        });
+        self
+    }
+    /// Stop the simulation before it starts
+    pub fn break_on_start(mut self) -> Self {
+        self.mode = Mode::Steps(0);
+        self
+    }
+    // Update the breakpoints, and return the first one that was hit
+    fn check_breakpoints(&mut self, x: &T) -> Option<String> {
+        let mut hit = None;
+        for breakpoint in self.breakpoints.iter_mut() {
+            let active = (breakpoint.condition)(x);
+            if active && !breakpoint.active && breakpoint.enabled && hit.is_none() {
+                hit = Some(breakpoint.name.clone());
+            }
+            breakpoint.active = active;
+        }
+        hit
+    }
+    // Returns true if a step (or cycle) count has run out
+    fn step_done(&mut self, x: &T) -> bool {
+        match &mut self.mode {
+            Mode::Steps(0) => true,
+            Mode::Steps(n) => {
+                *n -= 1;
+                *n == 0
+            }
+            Mode::Cycles {
+                clock,
+                remaining,
+                last,
+            } => {
+                let value = clock.read(x) == 1_u32.into();
+                let rising = *last == Some(false) && value;
+                *last = Some(value);
+                if rising {
+                    *remaining -= 1;
+                }
+                *remaining == 0
+            }
+            _ => false,
+        }
+    }
+    fn write(&mut self, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+    }
+    // Find the clock to step by (the first signal with the given name, or that is named clock)
+    fn find_clock(&self, x: &T, name: Option<&str>) -> Result<SignalHandle, String> {
+        let path = signal_paths(x)
+            .into_iter()
+            .find(|path| match name {
+                Some(name) => path == name || path.ends_with(&format!(".{}", name)),
+                None => path.ends_with(".clock"),
+            })
+            .ok_or_else(|| match name {
+                Some(name) => format!("No signal named {}", name),
+                None => "No clock found".to_string(),
+            })?;
+        match SignalHandle::find(x, &path) {
+            Some(handle) if handle.bits() == 1 => Ok(handle),
+            _ => Err(format!("{} is not a clock", path)),
+        }
+    }
+    // Read and run commands until the simulation is allowed to continue
+    fn prompt(&mut self, time: u64, x: &T) {
+        loop {
+            let _ = write!(self.output, "(sim @ {} ps) ", time);
+            let _ = self.output.flush();
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Ok(0) | Err(_) => {
+                    self.mode = Mode::Detached;
+                    return;
+                }
+                Ok(_) => {}
+            }
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let count = |ndx: usize| {
+                words
+                    .get(ndx)
+                    .and_then(|x| x.parse::<u64>().ok())
+                    .unwrap_or(1)
+                    .max(1)
+            };
+            match words.first().copied() {
+                None => {}
+                Some("c") | Some("continue") => {
+                    self.mode = Mode::Run;
+                    return;
+                }
+                Some("s") | Some("step") => {
+                    self.mode = Mode::Steps(count(1));
+                    return;
+                }
+                Some("n") | Some("next") => {
+                    let remaining = count(1);
+                    let name = words[1..]
+                        .iter()
+                        .find(|x| x.parse::<u64>().is_err())
+                        .copied();
+                    match self.find_clock(x, name) {
+                        Ok(clock) => {
+                            self.mode = Mode::Cycles {
+                                clock,
+                                remaining,
+                                last: None,
+                            };
+                            return;
+                        }
+                        Err(msg) => self.write(&msg),
+                    }
+                }
+                Some("p") | Some("print") => {
+                    let values = signal_values(x);
+                    for name in &words[1..] {
+                        let matches = values
+                            .iter()
+                            .filter(|(path, _)| path.contains(name))
+                            .collect::<Vec<_>>();
+                        if matches.is_empty() {
+                            self.write(&format!("No signal matches {}", name));
+                        }
+                        for (path, value) in matches {
+                            self.write(&format!("{} = {}", path, value));
+                        }
+                    }
+                }
+                Some("l") | Some("list") => {
+                    let filter = words.get(1).copied().unwrap_or("");
+                    for (path, _) in signal_values(x) {
+                        if path.contains(filter) {
+                            self.write(&path);
+                        }
+                    }
+                }
+                Some("b") | Some("breakpoints") => {
+                    let list = self
+                        .breakpoints
+                        .iter()
+                        .map(|b| {
+                            format!(
+                                "{} ({})",
+                                b.name,
+                                if b.enabled { "enabled" } else { "disabled" }
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    for line in list {
+                        self.write(&line);
+                    }
+                }
+                Some(cmd @ ("d" | "disable" | "e" | "enable")) => {
+                    let enable = cmd.starts_with('e');
+                    let name = words.get(1).copied().unwrap_or("");
+                    match self.breakpoints.iter_mut().find(|b| b.name == name) {
+                        Some(breakpoint) => breakpoint.enabled = enable,
+                        None => self.write(&format!("No breakpoint named {}", name)),
+                    }
+                }
+                Some("t") | Some("time") => self.write(&format!("{} ps", time)),
+                Some("q") | Some("quit") => {
+                    self.mode = Mode::Detached;
+                    return;
+                }
+                Some("h") | Some("help") => self.write(HELP),
+                Some(cmd) => self.write(&format!("Unknown command {} (try help)", cmd)),
+            }
+        }
+    }
+}
+
+impl<T: Block> Monitor<T> for Debugger<T> {
+    fn sample(&mut self, time: u64, x: &T) {
+        if matches!(self.mode, Mode::Detached) {
+            return;
+        }
+        let hit = self.check_breakpoints(x);
+        let stepped = self.step_done(x);
+        if let Some(name) = &hit {
+            self.write(&format!("Breakpoint {} hit at {} ps", name, time));
+        } else if !stepped {
+            return;
+        }
+        self.prompt(time, x);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::atom::AtomKind;
+use crate::block::Block;
+use crate::code_writer::CodeWriter;
+use crate::simulate::SimError;
+use crate::tracer::Tracer;
+use crate::verilator::{top_level_ports, top_level_values, Port, VerilatorError, VerilatorModel};
+use num_bigint::BigUint;
+use std::env::temp_dir;
+use std::fmt::{Display, Formatter};
+use std::fs::{create_dir_all, remove_dir_all, File};
+use std::io::{Error, Write};
+use std::process::Command;
+
+/// The simulator used to run the generated Verilog in an equivalence check.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VerilogSimulator {
+    /// Icarus Verilog (`iverilog` and `vvp` must be in the path)
+    Icarus,
+    /// Verilator (see [VerilatorModel])
+    Verilator,
+}
+
+/// The first point at which the generated Verilog and the Rust model disagree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    /// The simulation time (in picoseconds) at which the outputs differ
+    pub time: u64,
+    /// The name of the top level output (as it appears in the generated Verilog)
+    pub signal: String,
+    /// The value produced by the Rust model (in hex)
+    pub expected: String,
+    /// The value produced by the generated Verilog (in hex, and may contain `x` or `z`)
+    pub actual: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Output {} diverged at time {} ps: Rust model has 0x{}, Verilog has 0x{}",
+            self.signal, self.time, self.expected, self.actual
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum EquivalenceError {
+    /// The Rust simulation itself failed
+    Sim(SimError),
+    /// The generated Verilog produced a different output from the Rust model
+    Diverged(Divergence),
+    SimulatorFailed {
+        stdout: String,
+        stderr: String,
+    },
+    UnsupportedPort(Vec<String>),
+    Verilator(VerilatorError),
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for EquivalenceError {
+    fn from(x: Error) -> Self {
+        EquivalenceError::IOError(x)
+    }
+}
+
+impl From<SimError> for EquivalenceError {
+    fn from(x: SimError) -> Self {
+        EquivalenceError::Sim(x)
+    }
+}
+
+impl From<VerilatorError> for EquivalenceError {
+    fn from(x: VerilatorError) -> Self {
+        EquivalenceError::Verilator(x)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Sample {
+    time: u64,
+    values: Vec<BigUint>,
+}
+
+/// A [StimulusRecorder] records the value of every top level port of a circuit
+/// at each time step of a simulation.  Attach it to a simulation with
+/// [Simulation::add_observer](crate::simulate::Simulation::add_observer), and
+/// once the simulation is done, use [StimulusRecorder::check_equivalence] to replay
+/// the recorded inputs into the generated Verilog and compare the outputs.
+/// [Simulation::run_equivalence_check](crate::simulate::Simulation::run_equivalence_check)
+/// does all of this in one step.
+#[derive(Clone, Debug, Default)]
+pub struct StimulusRecorder {
+    ports: Vec<Port>,
+    samples: Vec<Sample>,
+}
+
+impl Tracer for StimulusRecorder {
+    fn start(&mut self, uut: &dyn Block) {
+        self.ports = top_level_ports(uut);
+        self.samples.clear();
+    }
+
+    fn step(&mut self, time: u64, uut: &dyn Block) {
+        let values = top_level_values(uut);
+        // Only the settled state at the end of each time step is kept
+        match self.samples.last_mut() {
+            Some(last) if last.time == time => last.values = values,
+            _ => self.samples.push(Sample { time, values }),
+        }
+    }
+}
+
+fn to_hex(bits: &BigUint, width: usize) -> String {
+    format!("{:0width$x}", bits, width = width.div_ceil(4))
+}
+
+impl StimulusRecorder {
+    /// The number of time steps recorded
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Replay the recorded inputs into `verilog` (which must be the output of
+    /// [generate_verilog](crate::module_defines::generate_verilog) for the simulated
+    /// circuit) using the given simulator, and compare the outputs at every time
+    /// step.  The temporary files are written to a directory named `prefix`.
+    pub fn check_equivalence(
+        &self,
+        prefix: &str,
+        verilog: &str,
+        simulator: VerilogSimulator,
+    ) -> Result<(), EquivalenceError> {
+        let inouts = self
+            .ports
+            .iter()
+            .filter(|x| x.kind == AtomKind::InOutParameter)
+            .map(|x| x.name.clone())
+            .collect::<Vec<_>>();
+        if !inouts.is_empty() {
+            return Err(EquivalenceError::UnsupportedPort(inouts));
+        }
+        let actual = match simulator {
+            VerilogSimulator::Icarus => self.replay_icarus(prefix, verilog)?,
+            VerilogSimulator::Verilator => self.replay_verilator(prefix, verilog)?,
+        };
+        for (sample, actual) in self.samples.iter().zip(actual) {
+            for (ndx, port) in self.ports.iter().enumerate() {
+                if port.kind == AtomKind::InputParameter {
+                    continue;
+                }
+                let expected = to_hex(&sample.values[ndx], port.bits);
+                if expected != actual[ndx] {
+                    return Err(EquivalenceError::Diverged(Divergence {
+                        time: sample.time,
+                        signal: port.name.clone(),
+                        expected,
+                        actual: actual[ndx].clone(),
+                    }));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn replay_verilator(
+        &self,
+        prefix: &str,
+        verilog: &str,
+    ) -> Result<Vec<Vec<String>>, EquivalenceError> {
+        let mut model = VerilatorModel::build(prefix, self.ports.clone(), verilog)?;
+        let mut ret = vec![];
+        for sample in &self.samples {
+            for (ndx, port) in self.ports.iter().enumerate() {
+                if port.kind == AtomKind::InputParameter {
+                    model.set_port(ndx, &sample.values[ndx]);
+                }
+            }
+            model.eval();
+            ret.push(
+                self.ports
+                    .iter()
+                    .enumerate()
+                    .map(|(ndx, port)| to_hex(&model.get_port(ndx), port.bits))
+                    .collect(),
+            );
+        }
+        Ok(ret)
+    }
+
+    /// The Verilog testbench used to replay the recorded stimulus under Icarus.  The
+    /// value of every port is displayed at the end of each recorded time step.
+    pub fn icarus_testbench(&self) -> String {
+        let mut io = CodeWriter::default();
+        io.writeln("`timescale 1ps/1ps");
+        io.writeln("module testbench;");
+        io.push();
+        for port in &self.ports {
+            let kind = if port.kind == AtomKind::InputParameter {
+                "reg"
+            } else {
+                "wire"
+            };
+            io.writeln(format!("{} [{}:0] {};", kind, port.bits - 1, port.name));
+        }
+        let connections = self
+            .ports
+            .iter()
+            .map(|x| format!(".{}({})", x.name, x.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        io.writeln(format!("top dut({});", connections));
+        let display = format!(
+            "$display(\"@@{}\"{});",
+            " %h".repeat(self.ports.len()),
+            self.ports
+                .iter()
+                .map(|x| format!(", {}", x.name))
+                .collect::<String>()
+        );
+        io.writeln("initial begin");
+        io.push();
+        // The outputs for each sample are displayed just before the inputs of the
+        // next sample are applied
+        for (ndx, sample) in self.samples.iter().enumerate() {
+            if ndx > 0 {
+                io.writeln(format!("#{};", sample.time - self.samples[ndx - 1].time));
+                io.writeln(&display);
+            }
+            for (ndx, port) in self.ports.iter().enumerate() {
+                if port.kind == AtomKind::InputParameter {
+                    io.writeln(format!(
+                        "{} = {}'h{:x};",
+                        port.name, port.bits, sample.values[ndx]
+                    ));
+                }
+            }
+        }
+        io.writeln("#1;");
+        io.writeln(&display);
+        io.writeln("$finish;");
+        io.pop();
+        io.writeln("end");
+        io.pop();
+        io.writeln("endmodule");
+        io.to_string()
+    }
+
+    fn replay_icarus(
+        &self,
+        prefix: &str,
+        verilog: &str,
+    ) -> Result<Vec<Vec<String>>, EquivalenceError> {
+        let dir = temp_dir().as_path().join(prefix);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        let mut v_file = File::create(dir.join("top.v"))?;
+        write!(v_file, "{}", verilog)?;
+        let mut tb_file = File::create(dir.join("testbench.v"))?;
+        write!(tb_file, "{}", self.icarus_testbench())?;
+        let compile = Command::new("iverilog")
+            .current_dir(&dir)
+            .args(["-g2005", "-o", "testbench.vvp", "testbench.v", "top.v"])
+            .output()?;
+        if !compile.status.success() {
+            return Err(EquivalenceError::SimulatorFailed {
+                stdout: String::from_utf8_lossy(&compile.stdout).to_string(),
+                stderr: String::from_utf8_lossy(&compile.stderr).to_string(),
+            });
+        }
+        let run = Command::new("vvp")
+            .current_dir(&dir)
+            .arg("testbench.vvp")
+            .output()?;
+        let stdout = String::from_utf8_lossy(&run.stdout).to_string();
+        let ret = stdout
+            .lines()
+            .filter_map(|x| x.strip_prefix("@@"))
+            .map(|x| x.split_whitespace().map(|x| x.to_string()).collect())
+            .collect::<Vec<Vec<String>>>();
+        if !run.status.success() || ret.len() != self.samples.len() {
+            return Err(EquivalenceError::SimulatorFailed {
+                stdout,
+                stderr: String::from_utf8_lossy(&run.stderr).to_string(),
+            });
+        }
+        Ok(ret)
+    }
+}
@@ -0,0 +1,719 @@
+use crate::ast::{
+    Verilog, VerilogBlockOrConditional, VerilogExpression, VerilogLiteral, VerilogStatement,
+};
+use crate::atom::AtomMut;
+use crate::atom::{Atom, AtomKind};
+use crate::bits::{Bits, LiteralType};
+use crate::block::Block;
+use crate::coverage::atom_bits;
+use crate::named_path::NamedPath;
+use crate::probe::{Probe, ProbeMut};
+use crate::synth::{BigUint, Synth, VCDValue};
+use crate::tracer::Tracer;
+use crate::type_descriptor::{TypeDescriptor, TypeKind};
+use crate::verilog_visitor::{walk_ternary, VerilogVisitor};
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// A bit vector for simulation in which each bit can be `0`, `1`, `X` (unknown) or
+/// `Z` (undriven).  It can be used anywhere a [Bits] can be used in a circuit, and
+/// generates the same Verilog.  Unknown bits propagate through the operators in the
+/// same (pessimistic) way they would in a Verilog simulator:
+///
+/// * bitwise operators are evaluated a bit at a time, so that `0 & X = 0` and `1 | X = 1`
+/// * arithmetic on a value with any unknown bit produces a result that is all `X`
+/// * a `Z` is treated as an `X` when it is read
+///
+/// Use [mux] and [select] for multiplexers and `match` style selections that merge the
+/// candidates when the selector is unknown.  Both can be used in an `hdl_gen` kernel, where
+/// they become a `?:` in the Verilog, which merges the candidates in the same way.  A Rust
+/// `if` or `match` in a kernel only sees the known bits, so an unknown selector takes one of
+/// the branches (like it would in a Verilog `if`), instead of merging them.
+///
+/// Four-state values are opt-in - only the signals declared as [FourState] can hold an
+/// unknown value, and [Bits], [Bit](crate::bits::Bit) and the enums of state machines are
+/// always two-state.  A [FourState] starts out as zero, unless four-state simulation is
+/// turned on with [Simulation::set_four_state](crate::simulate::Simulation::set_four_state).
+/// In that case every [FourState] in the circuit starts out as `X`, a RAM location that was
+/// never written reads as `X` (see [uninitialized]), and a `Signal<InOut, FourState<N>>` that
+/// nothing drives reads as `Z`.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// let x: FourState<8> = 0x0F.into();
+/// let y = FourState::<8>::unknown();
+/// assert_eq!(x & y, FourState::from_str("0000xxxx"));
+/// assert!((x + y).is_unknown());
+/// assert_eq!(mux(FourState::unknown(), x, x), x);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FourState<const N: usize> {
+    val: Bits<N>,
+    x: Bits<N>,
+    z: Bits<N>,
+}
+
+impl<const N: usize> FourState<N> {
+    /// A value with every bit `X`
+    pub fn unknown() -> Self {
+        Self {
+            val: Bits::default(),
+            x: Bits::mask(),
+            z: Bits::default(),
+        }
+    }
+    /// A value with every bit `Z`
+    pub fn undriven() -> Self {
+        Self {
+            val: Bits::default(),
+            x: Bits::default(),
+            z: Bits::mask(),
+        }
+    }
+    /// Parse a value from a string of `0`, `1`, `x` and `z` characters (MSB first).
+    /// Underscores are ignored.  Panics if the string is not `N` characters long.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(txt: &str) -> Self {
+        let chars = txt.chars().filter(|x| *x != '_').collect::<Vec<_>>();
+        assert_eq!(chars.len(), N, "Wrong number of bits in {}", txt);
+        let mut ret = Self::from(Bits::<N>::default());
+        for (ndx, c) in chars.iter().rev().enumerate() {
+            match c.to_ascii_lowercase() {
+                '0' => {}
+                '1' => ret.val = ret.val.replace_bit(ndx, true),
+                'x' => ret.x = ret.x.replace_bit(ndx, true),
+                'z' => ret.z = ret.z.replace_bit(ndx, true),
+                _ => panic!("Invalid four state bit {}", c),
+            }
+        }
+        ret
+    }
+    // The bits that are not 0 or 1
+    fn unknown_mask(&self) -> Bits<N> {
+        self.x | self.z
+    }
+    /// Returns `true` if every bit is `0` or `1`
+    pub fn is_known(&self) -> bool {
+        !self.unknown_mask().any()
+    }
+    /// Returns `true` if any bit is `X` or `Z`
+    pub fn is_unknown(&self) -> bool {
+        !self.is_known()
+    }
+    /// The value as a [Bits], if every bit is known.
+    pub fn known(&self) -> Option<Bits<N>> {
+        self.is_known().then_some(self.val)
+    }
+    /// The value as a [Bits], with any unknown bits set to zero.  This is
+    /// what the two-state simulation would have used.
+    pub fn bits(&self) -> Bits<N> {
+        self.val
+    }
+    /// Returns `true` if every bit is known, and the value is not zero.  An unknown
+    /// condition is treated as false, as it is in a Verilog `if` statement.
+    pub fn is_true(&self) -> bool {
+        self.is_known() && self.val.any()
+    }
+    /// Returns a single bit that is `1` if the values are equal, `0` if they differ
+    /// in a known bit, and `X` otherwise.
+    pub fn eq4(&self, other: &Self) -> FourState<1> {
+        let unknown = self.unknown_mask() | other.unknown_mask();
+        if ((self.val ^ other.val) & !unknown).any() {
+            false.into()
+        } else if unknown.any() {
+            FourState::unknown()
+        } else {
+            true.into()
+        }
+    }
+    // Build a value from the known bits, with all other bits `X`
+    fn with_unknown(val: Bits<N>, unknown: Bits<N>) -> Self {
+        Self {
+            val: val & !unknown,
+            x: unknown,
+            z: Bits::default(),
+        }
+    }
+    fn arithmetic(self, rhs: Self, op: impl Fn(Bits<N>, Bits<N>) -> Bits<N>) -> Self {
+        if self.is_known() && rhs.is_known() {
+            op(self.val, rhs.val).into()
+        } else {
+            Self::unknown()
+        }
+    }
+}
+
+/// Select `on_true` if `sel` is `1`, and `on_false` if it is `0`.  If `sel` is
+/// unknown, the result has the bits on which both candidates agree, and is `X` elsewhere.
+/// In an `hdl_gen` kernel, this becomes `sel ? on_true : on_false`.
+pub fn mux<const N: usize>(
+    sel: FourState<1>,
+    on_true: FourState<N>,
+    on_false: FourState<N>,
+) -> FourState<N> {
+    match sel.known() {
+        Some(s) if s.any() => on_true,
+        Some(_) => on_false,
+        None => merge(on_true, on_false),
+    }
+}
+
+fn merge<const N: usize>(a: FourState<N>, b: FourState<N>) -> FourState<N> {
+    let unknown = a.unknown_mask() | b.unknown_mask() | (a.val ^ b.val);
+    FourState::with_unknown(a.val, unknown)
+}
+
+/// The four-state version of a `match` on a bit vector.  Returns the value of the
+/// first arm whose pattern equals `sel`, or `default` if there is none.  If `sel`
+/// has unknown bits, every arm that it could match (and the default) is merged as in
+/// [mux], so that the result is only known where all of the candidates agree.  In an
+/// `hdl_gen` kernel, the `arms` must be written out as a slice of tuples, and the
+/// selection becomes a chain of `(sel == pattern) ? value : ...` in the Verilog.
+pub fn select<const M: usize, const N: usize>(
+    sel: FourState<M>,
+    arms: &[(LiteralType, FourState<N>)],
+    default: FourState<N>,
+) -> FourState<N> {
+    if let Some(sel) = sel.known() {
+        return arms
+            .iter()
+            .find(|(pattern, _)| sel == *pattern)
+            .map(|(_, val)| *val)
+            .unwrap_or(default);
+    }
+    arms.iter()
+        .filter(|(pattern, _)| sel.eq4(&(*pattern).into()) != FourState::from(false))
+        .fold(default, |acc, (_, val)| merge(acc, *val))
+}
+
+impl<const N: usize> Default for FourState<N> {
+    fn default() -> Self {
+        Bits::default().into()
+    }
+}
+
+impl<const N: usize> From<Bits<N>> for FourState<N> {
+    fn from(val: Bits<N>) -> Self {
+        Self {
+            val,
+            x: Bits::default(),
+            z: Bits::default(),
+        }
+    }
+}
+
+impl<const N: usize> From<LiteralType> for FourState<N> {
+    fn from(val: LiteralType) -> Self {
+        Bits::<N>::from(val).into()
+    }
+}
+
+// Only a fully known value can equal a literal
+impl<const N: usize> PartialEq<LiteralType> for FourState<N> {
+    fn eq(&self, other: &LiteralType) -> bool {
+        self.is_known() && self.val == *other
+    }
+}
+
+impl From<bool> for FourState<1> {
+    fn from(val: bool) -> Self {
+        Bits::<1>::from(val).into()
+    }
+}
+
+impl<const N: usize> Display for FourState<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for ndx in (0..N).rev() {
+            let c = if self.z.get_bit(ndx) {
+                'z'
+            } else if self.x.get_bit(ndx) {
+                'x'
+            } else if self.val.get_bit(ndx) {
+                '1'
+            } else {
+                '0'
+            };
+            write!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const N: usize> std::ops::Not for FourState<N> {
+    type Output = FourState<N>;
+
+    fn not(self) -> Self::Output {
+        Self::with_unknown(!self.val, self.unknown_mask())
+    }
+}
+
+impl<const N: usize> std::ops::BitAnd for FourState<N> {
+    type Output = FourState<N>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        // A known zero on either side forces the result to zero
+        let zero = (!self.val & !self.unknown_mask()) | (!rhs.val & !rhs.unknown_mask());
+        let unknown = (self.unknown_mask() | rhs.unknown_mask()) & !zero;
+        Self::with_unknown(self.val & rhs.val, unknown)
+    }
+}
+
+impl<const N: usize> std::ops::BitOr for FourState<N> {
+    type Output = FourState<N>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        // A known one on either side forces the result to one
+        let one = (self.val & !self.unknown_mask()) | (rhs.val & !rhs.unknown_mask());
+        let unknown = (self.unknown_mask() | rhs.unknown_mask()) & !one;
+        Self::with_unknown(self.val | rhs.val, unknown)
+    }
+}
+
+impl<const N: usize> std::ops::BitXor for FourState<N> {
+    type Output = FourState<N>;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::with_unknown(self.val ^ rhs.val, self.unknown_mask() | rhs.unknown_mask())
+    }
+}
+
+impl<const N: usize> std::ops::Add for FourState<N> {
+    type Output = FourState<N>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> std::ops::Sub for FourState<N> {
+    type Output = FourState<N>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.arithmetic(rhs, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> std::ops::Add<LiteralType> for FourState<N> {
+    type Output = FourState<N>;
+
+    fn add(self, rhs: LiteralType) -> Self::Output {
+        self + FourState::from(rhs)
+    }
+}
+
+impl<const N: usize> std::ops::Sub<LiteralType> for FourState<N> {
+    type Output = FourState<N>;
+
+    fn sub(self, rhs: LiteralType) -> Self::Output {
+        self - FourState::from(rhs)
+    }
+}
+
+impl<const N: usize> std::ops::Shl<LiteralType> for FourState<N> {
+    type Output = FourState<N>;
+
+    fn shl(self, rhs: LiteralType) -> Self::Output {
+        Self {
+            val: self.val << rhs,
+            x: self.x << rhs,
+            z: self.z << rhs,
+        }
+    }
+}
+
+impl<const N: usize> std::ops::Shr<LiteralType> for FourState<N> {
+    type Output = FourState<N>;
+
+    fn shr(self, rhs: LiteralType) -> Self::Output {
+        Self {
+            val: self.val >> rhs,
+            x: self.x >> rhs,
+            z: self.z >> rhs,
+        }
+    }
+}
+
+impl<const N: usize> Synth for FourState<N> {
+    const BITS: usize = N;
+
+    fn descriptor() -> TypeDescriptor {
+        TypeDescriptor {
+            name: format!("FourState::<{}>", N),
+            kind: TypeKind::Bits(N),
+        }
+    }
+
+    fn vcd(self) -> VCDValue {
+        let bit = |ndx: usize| {
+            if self.z.get_bit(ndx) {
+                vcd::Value::Z
+            } else if self.x.get_bit(ndx) {
+                vcd::Value::X
+            } else if self.val.get_bit(ndx) {
+                vcd::Value::V1
+            } else {
+                vcd::Value::V0
+            }
+        };
+        if N == 1 {
+            VCDValue::Single(bit(0))
+        } else {
+            VCDValue::Vector((0..N).rev().map(bit).collect())
+        }
+    }
+
+    // Unknown bits are written as zero
+    fn verilog(self) -> VerilogLiteral {
+        self.val.into()
+    }
+
+    fn from_bits(bits: &BigUint) -> Option<Self> {
+        Bits::<N>::from_bits(bits).map(|x| x.into())
+    }
+
+    fn unknown() -> Option<Self> {
+        Some(Self::unknown())
+    }
+
+    fn undriven() -> Option<Self> {
+        Some(Self::undriven())
+    }
+}
+
+thread_local! {
+    static FOUR_STATE: Cell<bool> = const { Cell::new(false) };
+}
+
+// The setting is per thread, since the circuit is updated on the thread that runs the simulation
+pub(crate) fn install_four_state(enabled: bool) {
+    FOUR_STATE.with(|x| x.set(enabled));
+}
+
+/// Returns `true` if the simulation running on the calling thread has four-state simulation
+/// turned on (see [Simulation::set_four_state](crate::simulate::Simulation::set_four_state)).
+pub fn four_state_enabled() -> bool {
+    FOUR_STATE.with(|x| x.get())
+}
+
+/// The value read from storage that was never written (i.e., a RAM location).  This is
+/// all `X` for a [FourState] in a four-state simulation, and the default value otherwise.
+pub fn uninitialized<T: Synth>() -> T {
+    if four_state_enabled() {
+        T::unknown().unwrap_or_default()
+    } else {
+        T::default()
+    }
+}
+
+struct Unknowns;
+
+impl ProbeMut for Unknowns {
+    fn visit_atom(&mut self, _name: &str, signal: &mut dyn AtomMut) {
+        signal.set_unknown();
+    }
+}
+
+// Set every [FourState] signal in the circuit to `X`
+pub(crate) fn make_unknown(uut: &mut dyn Block) {
+    uut.accept_mut("uut", &mut Unknowns);
+}
+
+/// Where an unknown value was found by the [XChecker]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum XSite {
+    /// A top level output of the circuit
+    Output,
+    /// A clock input of a block (i.e., a `Signal<In, Clock>`)
+    Clock,
+    /// A signal that decides whether (or what) a register loads, or one marked as an
+    /// enable with [XChecker::enable]
+    Enable,
+}
+
+/// An unknown (`X` or `Z`) value that reached a signal watched by the [XChecker]
+#[derive(Clone, Debug, PartialEq)]
+pub struct XViolation {
+    /// The simulation time (in picoseconds) at which the value was seen
+    pub time: u64,
+    /// The full path to the signal (i.e., `uut.fifo.clock`)
+    pub signal: String,
+    pub site: XSite,
+    /// The value of the signal, MSB first, using `0`, `1`, `x` and `z`
+    pub value: String,
+}
+
+impl Display for XViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unknown value {} on {:?} {} at time {} ps",
+            self.value, self.site, self.signal, self.time
+        )
+    }
+}
+
+/// A [Tracer] that flags unknown (`X` or `Z`) values that reach the top level outputs
+/// of a circuit, the clock inputs of any block in it, or the enables of its registers.
+/// The enables are found in the `hdl_gen` kernels: the conditions of the `if` and `match`
+/// statements that guard an assignment to the `d` input of a register, and the selectors of
+/// the [mux] and [select] calls that feed one.  Other signals can be watched as enables with
+/// [XChecker::enable].  Attach it with
+/// [Simulation::add_observer](crate::simulate::Simulation::add_observer).  The checker
+/// records each signal the first time it goes unknown.  It is most useful with
+/// [FourState] signals and four-state simulation turned on (see
+/// [Simulation::set_four_state](crate::simulate::Simulation::set_four_state)), but also
+/// catches undriven tristate buses in a two-state simulation.
+#[derive(Clone, Debug, Default)]
+pub struct XChecker {
+    enables: HashSet<String>,
+    // The enables given with [XChecker::enable], and the ones found in the kernels
+    watched: HashSet<String>,
+    violations: Vec<XViolation>,
+}
+
+// The names of the signals in an expression of a kernel
+#[derive(Default)]
+struct SignalNames(Vec<String>);
+
+impl VerilogVisitor for SignalNames {
+    fn visit_signal(&mut self, c: &str) {
+        self.0.push(c.to_owned());
+    }
+}
+
+fn signal_names(e: &VerilogExpression) -> Vec<String> {
+    let mut names = SignalNames::default();
+    names.visit_expression(e);
+    names.0
+}
+
+// The signals in the selectors of the `?:` (i.e., `mux` and `select`) in an expression
+struct Selectors<'a>(&'a mut HashSet<String>);
+
+impl<'a> VerilogVisitor for Selectors<'a> {
+    fn visit_ternary(
+        &mut self,
+        a: &VerilogExpression,
+        b: &VerilogExpression,
+        c: &VerilogExpression,
+    ) {
+        self.0.extend(signal_names(a));
+        walk_ternary(self, a, b, c);
+    }
+}
+
+fn loads_register(target: &VerilogExpression) -> bool {
+    matches!(target, VerilogExpression::Signal(x) if x.ends_with("$d$next"))
+}
+
+// Collect the enables of the registers loaded in a block of a kernel, and return `true` if
+// the block loads any register
+fn register_enables(block: &[VerilogStatement], enables: &mut HashSet<String>) -> bool {
+    let mut loads = false;
+    for statement in block {
+        loads |= match statement {
+            VerilogStatement::Assignment(target, value)
+            | VerilogStatement::SliceAssignment {
+                base: target,
+                replacement: value,
+                ..
+            } if loads_register(target) => {
+                Selectors(enables).visit_expression(value);
+                true
+            }
+            VerilogStatement::If(cond) => {
+                let mut guarded = register_enables(&cond.then, enables);
+                guarded |= match &cond.otherwise {
+                    VerilogBlockOrConditional::Block(block) => register_enables(block, enables),
+                    VerilogBlockOrConditional::Conditional(statement) => {
+                        register_enables(std::slice::from_ref(statement.as_ref()), enables)
+                    }
+                    VerilogBlockOrConditional::None => false,
+                };
+                if guarded {
+                    enables.extend(signal_names(&cond.test));
+                }
+                guarded
+            }
+            VerilogStatement::Match(m) => {
+                let mut guarded = false;
+                for case in &m.cases {
+                    guarded |= register_enables(&case.block, enables);
+                }
+                if guarded {
+                    enables.extend(signal_names(&m.test));
+                }
+                guarded
+            }
+            VerilogStatement::Loop(l) => register_enables(&l.block, enables),
+            VerilogStatement::Macro(block) => register_enables(block, enables),
+            _ => false,
+        };
+    }
+    loads
+}
+
+#[derive(Default)]
+struct EnableScan {
+    path: NamedPath,
+    enables: HashSet<String>,
+}
+
+impl Probe for EnableScan {
+    fn visit_start_scope(&mut self, name: &str, node: &dyn Block) {
+        self.path.push(name);
+        if let Verilog::Combinatorial(kernel) = node.hdl() {
+            let mut enables = HashSet::new();
+            register_enables(&kernel, &mut enables);
+            let scope = self.path.flat(".");
+            self.enables.extend(
+                enables
+                    .into_iter()
+                    .map(|x| format!("{}.{}", scope, x.replace('$', "."))),
+            );
+        }
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+}
+
+struct XScan<'a> {
+    time: u64,
+    path: NamedPath,
+    depth: usize,
+    enables: &'a HashSet<String>,
+    violations: &'a mut Vec<XViolation>,
+}
+
+impl<'a> XScan<'a> {
+    fn site(&self, path: &str, signal: &dyn Atom) -> Option<XSite> {
+        let kind = signal.kind();
+        if self.depth == 1
+            && matches!(
+                kind,
+                AtomKind::OutputParameter | AtomKind::OutputPassthrough
+            )
+        {
+            Some(XSite::Output)
+        } else if kind == AtomKind::InputParameter && signal.descriptor().name == "clock" {
+            Some(XSite::Clock)
+        } else if self.enables.contains(path) {
+            Some(XSite::Enable)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Probe for XScan<'a> {
+    fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+        self.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        self.path.push(name);
+        let path = self.path.flat(".");
+        self.path.pop();
+        let site = match self.site(&path, signal) {
+            Some(site) => site,
+            None => return,
+        };
+        let bits = atom_bits(signal);
+        if bits.iter().all(|x| x.is_some()) {
+            return;
+        }
+        if self.violations.iter().any(|x| x.signal == path) {
+            return;
+        }
+        let value = match signal.vcd() {
+            VCDValue::Single(x) => x.to_string(),
+            VCDValue::Vector(x) => x.iter().map(|x| x.to_string()).collect(),
+            _ => bits
+                .iter()
+                .rev()
+                .map(|x| match x {
+                    Some(true) => '1',
+                    Some(false) => '0',
+                    None => 'x',
+                })
+                .collect(),
+        };
+        self.violations.push(XViolation {
+            time: self.time,
+            signal: path,
+            site,
+            value,
+        });
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+        self.depth -= 1;
+    }
+}
+
+impl Tracer for XChecker {
+    fn start(&mut self, uut: &dyn Block) {
+        self.violations.clear();
+        let mut scan = EnableScan::default();
+        uut.accept("uut", &mut scan);
+        self.watched = scan.enables;
+        self.watched.extend(self.enables.iter().cloned());
+    }
+
+    fn step(&mut self, time: u64, uut: &dyn Block) {
+        let mut scan = XScan {
+            time,
+            path: Default::default(),
+            depth: 0,
+            enables: &self.watched,
+            violations: &mut self.violations,
+        };
+        uut.accept("uut", &mut scan);
+    }
+}
+
+impl XChecker {
+    /// Also check the signal with the given path (i.e., `uut.counter.enable`) for unknown
+    /// values, and report them as an [XSite::Enable].
+    pub fn enable(mut self, path: &str) -> Self {
+        self.enables.insert(path.to_owned());
+        self
+    }
+    /// The violations found, in the order they happened
+    pub fn violations(&self) -> &[XViolation] {
+        &self.violations
+    }
+    /// The first violation found (if any)
+    pub fn first(&self) -> Option<&XViolation> {
+        self.violations.first()
+    }
+    /// Returns `true` if no unknown values were found
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl Display for XChecker {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.violations.is_empty() {
+            return writeln!(f, "No unknown values found");
+        }
+        for violation in &self.violations {
+            writeln!(f, "{}", violation)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+use crate::atom::{Atom, AtomKind};
+use crate::bits::clog2;
+use crate::block::Block;
+use crate::coverage::enum_label_index;
+use crate::named_path::NamedPath;
+use crate::probe::Probe;
+use crate::synth::VCDValue;
+use crate::trace_filter::{PathMatcher, ScopeStack, TraceFilter};
+use crate::type_descriptor::{TypeDescriptor, TypeKind};
+use fst_writer::{
+    open_fst, FstBodyWriter, FstFileType, FstHeaderWriter, FstInfo, FstScopeType, FstSignalId,
+    FstSignalType, FstVarDirection, FstVarType, FstWriteError,
+};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+#[derive(Clone, Debug)]
+enum FSTIDCode {
+    Singleton(FstSignalId),
+    Enum(FstSignalId, Vec<String>),
+    Composite(Vec<FSTIDCode>),
+}
+
+/// The value changes that are held in memory before they are written to the file as a
+/// block (64 MiB).
+pub const FST_FLUSH_THRESHOLD: usize = 64 << 20;
+
+// The writer reports I/O errors wrapped in its own error type
+fn io_error(e: FstWriteError) -> std::io::Error {
+    match e {
+        FstWriteError::Io(e) => e,
+        e => std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+    }
+}
+
+/// The FST equivalent of the [VCDProbe](crate::vcd_probe::VCDProbe).  FST is the
+/// compressed waveform format used by GTKWave, and is much more compact than VCD
+/// for long simulations.  Because the format requires seeking, it can only be
+/// written to a file.  Value changes are collected in memory, and written out as
+/// a block whenever they pass the flush threshold (see [FST_FLUSH_THRESHOLD]).
+pub struct FSTProbe {
+    fst: FstBodyWriter<BufWriter<File>>,
+    id_map: HashMap<usize, FSTIDCode>,
+    flush_threshold: usize,
+    time: u64,
+}
+
+impl FSTProbe {
+    pub fn timestamp(&mut self, ts: u64) -> std::io::Result<()> {
+        // The writer can only start a new block on a new time step
+        if ts > self.time && self.fst.size() > self.flush_threshold {
+            self.fst.flush().map_err(io_error)?;
+        }
+        self.time = self.time.max(ts);
+        self.fst.time_change(ts).map_err(io_error)
+    }
+
+    /// Set the size (in bytes) of the value changes that are held in memory before they
+    /// are written to the file.
+    pub fn set_flush_threshold(&mut self, bytes: usize) {
+        self.flush_threshold = bytes;
+    }
+
+    /// Flush the remaining value changes and close out the file.
+    pub fn finish(self) -> std::io::Result<()> {
+        self.fst.finish().map_err(io_error)
+    }
+}
+
+struct FSTHeader {
+    fst: FstHeaderWriter<BufWriter<File>>,
+    id_map: HashMap<usize, FSTIDCode>,
+    path: NamedPath,
+    depth: usize,
+    matcher: PathMatcher,
+    scopes: ScopeStack<(String, FstScopeType)>,
+    error: Option<FstWriteError>,
+}
+
+impl FSTHeader {
+    // Keep the first error, and skip the rest of the header once there is one
+    fn check<T>(&mut self, result: Result<T, FstWriteError>) -> Option<T> {
+        match result {
+            Ok(x) => Some(x),
+            Err(e) => {
+                self.error.get_or_insert(e);
+                None
+            }
+        }
+    }
+}
+
+fn enum_width(labels: &[String]) -> u32 {
+    clog2(labels.len()).max(1) as u32
+}
+
+fn register_signal(
+    name: &str,
+    descriptor: &TypeDescriptor,
+    direction: FstVarDirection,
+    fst: &mut FstHeaderWriter<BufWriter<File>>,
+) -> Result<FSTIDCode, FstWriteError> {
+    Ok(match &descriptor.kind {
+        TypeKind::Bits(width) | TypeKind::Signed(width) => FSTIDCode::Singleton(fst.var(
+            name,
+            FstSignalType::bit_vec(*width as u32),
+            FstVarType::Wire,
+            direction,
+            None,
+        )?),
+        TypeKind::Enum(labels) => FSTIDCode::Enum(
+            fst.var(
+                name,
+                FstSignalType::bit_vec(enum_width(labels)),
+                FstVarType::Wire,
+                direction,
+                None,
+            )?,
+            labels.clone(),
+        ),
+        TypeKind::Composite(k) => {
+            let mut ret = vec![];
+            for field in k {
+                let sub_name = format!("{}${}", name, field.fieldname);
+                let code = register_signal(&sub_name, &field.kind, direction, fst)?;
+                ret.push(code);
+            }
+            FSTIDCode::Composite(ret)
+        }
+    })
+}
+
+fn direction(kind: AtomKind) -> FstVarDirection {
+    match kind {
+        AtomKind::InputParameter => FstVarDirection::Input,
+        AtomKind::OutputParameter | AtomKind::OutputPassthrough => FstVarDirection::Output,
+        AtomKind::InOutParameter => FstVarDirection::InOut,
+        _ => FstVarDirection::Implicit,
+    }
+}
+
+impl Probe for FSTHeader {
+    fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
+        self.scopes.push((name.into(), FstScopeType::Module));
+        self.path.push(name);
+        self.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.scopes.push((name.into(), FstScopeType::Interface));
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        self.path.push(name);
+        if self.error.is_none()
+            && self
+                .matcher
+                .includes(&self.path.flat("."), self.depth.saturating_sub(1))
+        {
+            let fst = &mut self.fst;
+            let mut opened = Ok(());
+            self.scopes.open(|(name, kind)| {
+                if opened.is_ok() {
+                    opened = fst.scope(name, "", *kind);
+                }
+            });
+            let code = opened.and_then(|_| {
+                register_signal(
+                    name,
+                    &signal.descriptor(),
+                    direction(signal.kind()),
+                    &mut self.fst,
+                )
+            });
+            if let Some(code) = self.check(code) {
+                self.id_map.insert(signal.id(), code);
+            }
+        }
+        self.path.pop();
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        if self.scopes.pop() {
+            let result = self.fst.up_scope();
+            self.check(result);
+        }
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        if self.scopes.pop() {
+            let result = self.fst.up_scope();
+            self.check(result);
+        }
+        self.path.pop();
+        self.depth -= 1;
+    }
+}
+
+/// Create an FST file at `path`, and write the hierarchy of `uut` into it.  The
+/// timescale of the file is 1 picosecond, which matches the simulation time.
+pub fn write_fst_header<P: AsRef<Path>>(path: P, uut: &dyn Block) -> std::io::Result<FSTProbe> {
+    write_fst_header_filtered(path, uut, &TraceFilter::default())
+}
+
+/// Create an FST file at `path`, and write the hierarchy of `uut` into it, but only
+/// include those signals that pass the path and depth checks of the [TraceFilter].
+pub fn write_fst_header_filtered<P: AsRef<Path>>(
+    path: P,
+    uut: &dyn Block,
+    filter: &TraceFilter,
+) -> std::io::Result<FSTProbe> {
+    let info = FstInfo {
+        start_time: 0,
+        timescale_exponent: -12,
+        version: "rust-hdl".to_string(),
+        date: "".to_string(),
+        file_type: FstFileType::Verilog,
+    };
+    let mut visitor = FSTHeader {
+        fst: open_fst(path, &info).map_err(io_error)?,
+        id_map: HashMap::default(),
+        path: Default::default(),
+        depth: 0,
+        matcher: filter.matcher(),
+        scopes: Default::default(),
+        error: None,
+    };
+    uut.accept("uut", &mut visitor);
+    if let Some(e) = visitor.error {
+        return Err(io_error(e));
+    }
+    Ok(FSTProbe {
+        fst: visitor.fst.finish().map_err(io_error)?,
+        id_map: visitor.id_map,
+        flush_threshold: FST_FLUSH_THRESHOLD,
+        time: 0,
+    })
+}
+
+fn value_char(x: &vcd::Value) -> u8 {
+    match x {
+        vcd::Value::V0 => b'0',
+        vcd::Value::V1 => b'1',
+        vcd::Value::X => b'x',
+        vcd::Value::Z => b'z',
+    }
+}
+
+fn do_fst_change(
+    fst: &mut FstBodyWriter<BufWriter<File>>,
+    idc: &FSTIDCode,
+    val: &VCDValue,
+) -> Result<(), FstWriteError> {
+    match idc {
+        FSTIDCode::Singleton(idc) => match val {
+            VCDValue::Single(s) => fst.signal_change(*idc, &[value_char(s)]),
+            VCDValue::Vector(v) => {
+                let v = v.iter().map(value_char).collect::<Vec<_>>();
+                fst.signal_change(*idc, &v)
+            }
+            _ => {
+                panic!("Non-bit data received for bit vector type");
+            }
+        },
+        FSTIDCode::Enum(idc, labels) => match val {
+            VCDValue::String(t) => {
+                let width = enum_width(labels) as usize;
+                // A label that is not part of the enum is written as unknown
+                let v = match enum_label_index(labels, t) {
+                    Some(index) => (0..width)
+                        .map(|i| {
+                            if index & (1 << (width - 1 - i)) != 0 {
+                                b'1'
+                            } else {
+                                b'0'
+                            }
+                        })
+                        .collect::<Vec<_>>(),
+                    None => vec![b'x'; width],
+                };
+                fst.signal_change(*idc, &v)
+            }
+            _ => {
+                panic!("Non-string data received for enum type");
+            }
+        },
+        FSTIDCode::Composite(idcs) => match val {
+            VCDValue::Composite(vals) => {
+                assert_eq!(
+                    idcs.len(),
+                    vals.len(),
+                    "Mismatch in values versus type information"
+                );
+                for (idc, val) in idcs.iter().zip(vals.iter()) {
+                    do_fst_change(fst, idc, val)?;
+                }
+                Ok(())
+            }
+            _ => {
+                panic!("Scalar data received for composite type");
+            }
+        },
+    }
+}
+
+struct FSTChange(FSTProbe, Option<FstWriteError>);
+
+impl Probe for FSTChange {
+    fn visit_atom(&mut self, _name: &str, signal: &dyn Atom) {
+        if self.1.is_some() {
+            return;
+        }
+        if let Some(idc) = self.0.id_map.get(&signal.id()) {
+            if let Err(e) = do_fst_change(&mut self.0.fst, idc, &signal.vcd()) {
+                self.1 = Some(e);
+            }
+        }
+    }
+}
+
+/// Record the current state of `uut` in the FST file.  FST only stores values that
+/// actually change, so this is used for both the initial dump and for every
+/// subsequent time step.
+pub fn write_fst_change(fst: FSTProbe, uut: &dyn Block) -> std::io::Result<FSTProbe> {
+    let mut visitor = FSTChange(fst, None);
+    uut.accept("uut", &mut visitor);
+    match visitor.1 {
+        Some(e) => Err(io_error(e)),
+        None => Ok(visitor.0),
+    }
+}
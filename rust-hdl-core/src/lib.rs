@@ -6,32 +6,50 @@ pub mod bits;
 #[doc(hidden)]
 pub mod bitvec;
 pub mod block;
+pub mod cdc_jitter;
+pub mod check_cdc;
 pub mod check_connected;
 pub mod check_error;
+pub mod check_latches;
 pub mod check_logic_loops;
+pub mod check_nets;
+pub mod check_report;
 pub mod check_timing;
+pub mod check_widths;
 pub mod check_write_inputs;
 pub mod clock;
 pub mod code_writer;
 pub mod constant;
 pub mod constraint;
+pub mod coverage;
+pub mod debugger;
 pub mod direction;
+pub mod equivalence;
+pub mod four_state;
+pub mod fst_probe;
 pub mod logic;
 pub mod module_defines;
 pub mod named_path;
 pub mod path_tools;
 pub mod prelude;
 pub mod probe;
+pub mod regression;
 #[doc(hidden)]
 pub mod short_bit_vec;
 pub mod signal;
+pub mod signal_handle;
 pub mod signed;
 pub mod simulate;
 pub mod synth;
+pub mod time_units;
 pub mod timing;
 pub mod top_wrap;
+pub mod trace_filter;
+pub mod tracer;
 pub mod type_descriptor;
 pub mod vcd_probe;
+pub mod vcd_replay;
+pub mod verilator;
 pub mod verilog_gen;
 pub mod verilog_visitor;
 pub mod yosys;
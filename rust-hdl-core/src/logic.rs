@@ -1,4 +1,4 @@
-use crate::ast::{Verilog, VerilogLink};
+use crate::ast::{SourceLocation, Verilog, VerilogLink};
 use crate::timing::TimingInfo;
 
 pub trait Logic {
@@ -7,6 +7,9 @@ pub trait Logic {
     fn hdl(&self) -> Verilog {
         Verilog::Empty
     }
+    fn hdl_location(&self) -> Option<SourceLocation> {
+        None
+    }
     fn timing(&self) -> Vec<TimingInfo> {
         vec![]
     }
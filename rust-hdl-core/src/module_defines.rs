@@ -132,6 +132,20 @@ impl ModuleDefines {
         };
         entry.code = code;
     }
+    // The code of each module, along with the widths of the signals it can refer to
+    pub(crate) fn module_code(&self) -> Vec<(String, BTreeMap<String, usize>, Verilog)> {
+        self.details
+            .iter()
+            .map(|(module, details)| {
+                let widths = details
+                    .atoms
+                    .iter()
+                    .map(|x| (x.name.clone(), x.width))
+                    .collect();
+                (module.clone(), widths, details.code.clone())
+            })
+            .collect()
+    }
 }
 
 impl Probe for ModuleDefines {
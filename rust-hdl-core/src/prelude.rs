@@ -3,7 +3,7 @@ pub use crate::ast::BlackBox;
 pub use crate::ast::Verilog;
 pub use crate::ast::VerilogLiteral;
 pub use crate::ast::Wrapper;
-pub use crate::atom::{Atom, AtomKind};
+pub use crate::atom::{Atom, AtomKind, AtomMut};
 pub use crate::bits::bit_cast;
 pub use crate::bits::bits;
 pub use crate::bits::clog2;
@@ -12,9 +12,18 @@ pub use crate::bits::ToBits;
 pub use crate::bits::{Bit, Bits};
 pub use crate::block;
 pub use crate::block::Block;
+pub use crate::cdc_cross;
+pub use crate::cdc_jitter::{cdc_jitter, cdc_resolve, CDCJitter, ClockCrossing};
+pub use crate::check_cdc::check_cdc;
 pub use crate::check_connected::check_connected;
 pub use crate::check_error::check_all;
-pub use crate::check_timing::check_timing;
+pub use crate::check_latches::check_latches;
+pub use crate::check_nets::{check_multiple_drivers, check_unused_signals};
+pub use crate::check_report::{check_all_report, CheckReport, CheckSeverity, CheckViolation};
+pub use crate::check_timing::{
+    check_timing, RegisterPath, TimedRegister, TimingReport, UnclockedPath,
+};
+pub use crate::check_widths::{check_widths, WidthCheck, WidthMismatch, WidthMismatchKind};
 pub use crate::clock;
 pub use crate::clock::freq_hz_to_period_femto;
 pub use crate::clock::Clock;
@@ -22,7 +31,18 @@ pub use crate::clock::NANOS_PER_FEMTO;
 pub use crate::constant::Constant;
 pub use crate::constraint::Timing::*;
 pub use crate::constraint::*;
+pub use crate::coverage;
+pub use crate::coverage::{
+    register_branches, BranchCoverage, BranchReport, BranchSite, FsmCoverage, FsmCoverageReport,
+    ModuleToggleSummary, ToggleCoverage, UntoggledBit,
+};
+pub use crate::debugger::Debugger;
 pub use crate::direction::{Direction, In, InOut, Local, Out};
+pub use crate::equivalence::{Divergence, EquivalenceError, StimulusRecorder, VerilogSimulator};
+pub use crate::four_state::{
+    four_state_enabled, mux, select, uninitialized, FourState, XChecker, XSite, XViolation,
+};
+pub use crate::fst_probe::{write_fst_change, write_fst_header, write_fst_header_filtered};
 pub use crate::logic;
 pub use crate::logic::Logic;
 pub use crate::logic::LogicJoin;
@@ -31,8 +51,12 @@ pub use crate::module_defines::ModuleDefines;
 pub use crate::module_defines::{generate_verilog, generate_verilog_unchecked};
 pub use crate::named_path::NamedPath;
 pub use crate::probe;
-pub use crate::probe::Probe;
+pub use crate::probe::{Probe, ProbeMut};
+pub use crate::regression::{
+    seed_sim_rng, sim_rng, Regression, RegressionReport, SeedResult, SimRng,
+};
 pub use crate::signal::Signal;
+pub use crate::signal_handle::{signal_paths, SignalHandle};
 pub use crate::signed::ToSignedBits;
 pub use crate::signed::{
     signed, signed_bit_cast, signed_cast, unsigned_bit_cast, unsigned_cast, Signed,
@@ -42,18 +66,28 @@ pub use crate::sim_assert_eq;
 pub use crate::simple_sim;
 pub use crate::simulate::sim_time;
 pub use crate::simulate::simulate;
+pub use crate::simulate::simulate_pending;
 pub use crate::simulate::SIMULATION_TIME_ONE_SECOND;
-pub use crate::simulate::{Sim, SimError, Simulation};
+pub use crate::simulate::{
+    Checkpoint, Executor, Monitor, Scheduler, Sim, SimBackend, SimError, SimFailure, Simulation,
+};
 pub use crate::synth;
 pub use crate::synth::Synth;
 pub use crate::synth::VCDValue;
 pub use crate::target_path;
+pub use crate::time_units::{Frequency, SimTime};
 pub use crate::timing::TimingInfo;
 pub use crate::top_wrap::TopWrap;
+pub use crate::trace_filter::TraceFilter;
+pub use crate::tracer::{FSTTracer, TraceFormat, Tracer, VCDTracer};
 pub use crate::type_descriptor;
 pub use crate::type_descriptor::{TypeDescriptor, TypeField, TypeKind};
 pub use crate::vcd_path;
-pub use crate::vcd_probe::{write_vcd_change, write_vcd_dump, write_vcd_header};
+pub use crate::vcd_probe::{
+    write_vcd_change, write_vcd_dump, write_vcd_header, write_vcd_header_filtered,
+};
+pub use crate::vcd_replay::{ReplayError, VCDReplay};
+pub use crate::verilator::{verilator_harness, VerilatorError, VerilatorModel};
 pub use crate::verilog_gen::filter_blackbox_directives;
 pub use crate::verilog_visitor::VerilogVisitor;
 pub use crate::wait_clock_cycle;
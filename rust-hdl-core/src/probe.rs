@@ -1,4 +1,4 @@
-use crate::atom::Atom;
+use crate::atom::{Atom, AtomMut};
 use crate::block::Block;
 
 pub trait Probe {
@@ -8,3 +8,13 @@ pub trait Probe {
     fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {}
     fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {}
 }
+
+/// Like [Probe], but the visitor is given mutable access to the atoms of the
+/// circuit, so that it can drive them with new values.
+pub trait ProbeMut {
+    fn visit_start_scope(&mut self, _name: &str) {}
+    fn visit_start_namespace(&mut self, _name: &str) {}
+    fn visit_atom(&mut self, _name: &str, _signal: &mut dyn AtomMut) {}
+    fn visit_end_namespace(&mut self, _name: &str) {}
+    fn visit_end_scope(&mut self, _name: &str) {}
+}
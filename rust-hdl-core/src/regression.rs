@@ -0,0 +1,245 @@
+use crate::block::Block;
+use crate::simulate::{panic_failure, SimError, Simulation};
+use rand::rngs::StdRng;
+use rand::{Error, RngCore, SeedableRng};
+use std::cell::RefCell;
+use std::fmt::{Display, Formatter};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static SIM_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Reseed the simulation random number generator (see [sim_rng]) of the calling thread.
+pub fn seed_sim_rng(seed: u64) {
+    SIM_RNG.with(|x| *x.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+// The generator of a testbench that runs as a task of the cooperative executor.  It is
+// swapped into the thread-local generator while the task runs, and out again when the
+// task yields, so that the testbenches sharing the thread do not share a generator.
+pub(crate) struct TaskRng(StdRng);
+
+impl TaskRng {
+    pub(crate) fn new(seed: Option<u64>) -> Self {
+        match seed {
+            Some(seed) => Self(StdRng::seed_from_u64(seed)),
+            None => Self(StdRng::from_entropy()),
+        }
+    }
+    // Exchange the generator of the task with the one of the thread
+    pub(crate) fn swap(&mut self) {
+        SIM_RNG.with(|x| std::mem::swap(&mut *x.borrow_mut(), &mut self.0));
+    }
+}
+
+// Derive the seed for one of the threads (or tasks) of a simulation from the
+// seed of the simulation, so that they do not all produce the same sequence.
+pub(crate) fn derive_seed(seed: u64, stream: u64) -> u64 {
+    seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+/// A handle to the simulation random number generator.  Each testbench has its own
+/// generator (whether it runs on its own thread or as a task of the cooperative executor),
+/// and the clocks and custom logic share the generator of the thread that runs the
+/// simulation.  The generators are seeded by a [Simulation] that has a seed (see
+/// [Simulation::set_seed]), and by a [Regression] run.  Testbenches that draw
+/// their random numbers from it are reproducible.  Without a seed, it is seeded from the OS.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// use rand::Rng;
+/// seed_sim_rng(42);
+/// let first = sim_rng().gen::<u32>();
+/// seed_sim_rng(42);
+/// assert_eq!(sim_rng().gen::<u32>(), first);
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SimRng;
+
+/// Get a handle to the simulation random number generator of the calling testbench (or thread).
+pub fn sim_rng() -> SimRng {
+    SimRng
+}
+
+impl RngCore for SimRng {
+    fn next_u32(&mut self) -> u32 {
+        SIM_RNG.with(|x| x.borrow_mut().next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        SIM_RNG.with(|x| x.borrow_mut().next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        SIM_RNG.with(|x| x.borrow_mut().fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        SIM_RNG.with(|x| x.borrow_mut().try_fill_bytes(dest))
+    }
+}
+
+/// The outcome of one seed of a [Regression]
+#[derive(Clone, Debug)]
+pub struct SeedResult {
+    pub seed: u64,
+    pub result: Result<(), SimError>,
+    /// The wall clock time taken by the run
+    pub elapsed: Duration,
+}
+
+/// The outcome of all of the seeds of a [Regression], in the order of the seeds.
+#[derive(Clone, Debug, Default)]
+pub struct RegressionReport {
+    pub results: Vec<SeedResult>,
+}
+
+impl RegressionReport {
+    pub fn passed(&self) -> impl Iterator<Item = &SeedResult> {
+        self.results.iter().filter(|x| x.result.is_ok())
+    }
+    pub fn failed(&self) -> impl Iterator<Item = &SeedResult> {
+        self.results.iter().filter(|x| x.result.is_err())
+    }
+    /// The seeds that failed
+    pub fn failing_seeds(&self) -> Vec<u64> {
+        self.failed().map(|x| x.seed).collect()
+    }
+    /// Returns `true` if every seed passed
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+impl Display for RegressionReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for result in &self.results {
+            match &result.result {
+                Ok(()) => writeln!(f, "seed {:>20}: pass ({:?})", result.seed, result.elapsed)?,
+                Err(e) => writeln!(f, "seed {:>20}: FAIL {}", result.seed, e)?,
+            }
+        }
+        writeln!(
+            f,
+            "{} of {} seeds passed",
+            self.passed().count(),
+            self.results.len()
+        )
+    }
+}
+
+type SimFactory<T> = dyn Fn(u64) -> (Simulation<T>, Box<T>) + Send + Sync;
+
+/// A [Regression] runs a randomized simulation once for each of a set of seeds, using a pool
+/// of threads.  The simulation is built by a factory function from the seed, and the simulation
+/// random number generator (see [sim_rng]) is seeded for the factory and for each testbench,
+/// so that any seed can be run again (for example, with a trace) to reproduce a failure.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// use rand::Rng;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Foo {
+///    pub clock: Signal<In, Clock>
+/// }
+///
+/// impl Logic for Foo {
+///   #[hdl_gen]
+///   fn update(&mut self) {
+///   }
+/// }
+///
+/// let regression = Regression::new(10_000, |_seed| {
+///     let mut sim = Simulation::new();
+///     sim.add_clock(5, |x: &mut Box<Foo>| x.clock.next = !x.clock.val());
+///     sim.add_testbench(|mut ep: Sim<Foo>| {
+///         let mut x = ep.init()?;
+///         let cycles = sim_rng().gen_range(1..10);
+///         wait_clock_cycles!(ep, clock, x, cycles);
+///         ep.done(x)
+///     });
+///     (sim, Box::new(Foo::default()))
+/// });
+/// let report = regression.run_seeds(0..8);
+/// assert!(report.is_success(), "{}", report);
+/// ```
+pub struct Regression<T> {
+    factory: Box<SimFactory<T>>,
+    max_time: u64,
+    threads: usize,
+}
+
+impl<T: Send + 'static + Block> Regression<T> {
+    /// Create a regression, where `factory` builds the simulation (and the circuit) for a
+    /// seed, and each simulation is run for at most `max_time` picoseconds.
+    pub fn new<F>(max_time: u64, factory: F) -> Self
+    where
+        F: Fn(u64) -> (Simulation<T>, Box<T>) + Send + Sync + 'static,
+    {
+        Self {
+            factory: Box::new(factory),
+            max_time,
+            threads: std::thread::available_parallelism()
+                .map(|x| x.get())
+                .unwrap_or(1),
+        }
+    }
+    /// Set the number of seeds to run at the same time (the default is the number of CPUs)
+    pub fn threads(self, threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+    fn run_one(&self, seed: u64, trace: Option<&str>) -> Result<(), SimError> {
+        seed_sim_rng(seed);
+        let (mut sim, x) = std::panic::catch_unwind(AssertUnwindSafe(|| (self.factory)(seed)))
+            .map_err(|e| SimError::SimPanic(Box::new(panic_failure(e))))?;
+        sim.set_seed(seed);
+        match trace {
+            Some(name) => sim.run_to_file(x, self.max_time, name),
+            None => sim.run(x, self.max_time),
+        }
+    }
+    /// Run the simulation once for each of the seeds, and report the result of each one.
+    pub fn run_seeds<I: IntoIterator<Item = u64>>(&self, seeds: I) -> RegressionReport {
+        let seeds = seeds.into_iter().collect::<Vec<_>>();
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(vec![]);
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.min(seeds.len()) {
+                scope.spawn(|| loop {
+                    let ndx = next.fetch_add(1, Ordering::SeqCst);
+                    if ndx >= seeds.len() {
+                        break;
+                    }
+                    let start = Instant::now();
+                    let result = self.run_one(seeds[ndx], None);
+                    results.lock().unwrap().push((
+                        ndx,
+                        SeedResult {
+                            seed: seeds[ndx],
+                            result,
+                            elapsed: start.elapsed(),
+                        },
+                    ));
+                });
+            }
+        });
+        let mut results = results.into_inner().unwrap();
+        results.sort_by_key(|x| x.0);
+        RegressionReport {
+            results: results.into_iter().map(|x| x.1).collect(),
+        }
+    }
+    /// Run the simulation for a single seed, and write a trace to the given file (see
+    /// [Simulation::run_to_file]).  Use this to investigate a seed that failed.
+    pub fn rerun_traced(&self, seed: u64, name: &str) -> Result<(), SimError> {
+        self.run_one(seed, Some(name))
+    }
+}
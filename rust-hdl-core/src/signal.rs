@@ -2,16 +2,18 @@ use std::marker::PhantomData;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::ast::{VerilogLink, VerilogLinkDetails, VerilogLiteral};
-use crate::atom::{Atom, AtomKind};
+use crate::atom::{Atom, AtomKind, AtomMut};
 use crate::bits::Bit;
 use crate::block::Block;
 use crate::clock::Clock;
 use crate::constraint::{Constraint, PinConstraint, SignalType};
 use crate::direction::{Direction, In, InOut, Local, Out};
+use crate::four_state::four_state_enabled;
 use crate::logic::{Logic, LogicJoin, LogicLink};
-use crate::probe::Probe;
+use crate::probe::{Probe, ProbeMut};
 use crate::synth::{Synth, VCDValue};
 use crate::type_descriptor::TypeDescriptor;
+use num_bigint::BigUint;
 
 static GLOBAL_THREAD_COUNT: AtomicUsize = AtomicUsize::new(1);
 
@@ -30,6 +32,7 @@ pub struct Signal<D: Direction, T: Synth> {
     tristate_is_output: bool,
     signal_is_undriven: bool,
     constraints: Vec<PinConstraint>,
+    forced: Option<T>,
     dir: std::marker::PhantomData<D>,
 }
 
@@ -210,6 +213,67 @@ impl<D: Direction, T: Synth> Atom for Signal<D, T> {
     }
 }
 
+impl<D: Direction, T: Synth> AtomMut for Signal<D, T> {
+    fn next_bits(&self) -> BigUint {
+        self.next.verilog().as_biguint()
+    }
+
+    fn set_bits(&mut self, bits: &BigUint) -> bool {
+        match T::from_bits(bits) {
+            Some(val) => {
+                self.next = val;
+                self.update_all();
+                self.changed
+            }
+            None => false,
+        }
+    }
+
+    fn set_next_bits(&mut self, bits: &BigUint) -> bool {
+        match T::from_bits(bits) {
+            Some(val) => {
+                self.next = val;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn force_bits(&mut self, bits: &BigUint) -> bool {
+        match T::from_bits(bits) {
+            Some(val) => {
+                self.forced = Some(val);
+                self.next = val;
+                // Mark the signal as changed (but not as an edge), so that its readers are updated
+                self.prev = self.val;
+                self.changed = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn release(&mut self) {
+        if self.forced.take().is_some() {
+            // Mark the signal as changed (but not as an edge), so that its driver is updated
+            self.prev = self.val;
+            self.changed = true;
+        }
+    }
+
+    fn set_unknown(&mut self) -> bool {
+        match T::unknown() {
+            Some(val) => {
+                self.next = val;
+                self.val = val;
+                self.prev = val;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 impl<D: Direction, T: Synth> Logic for Signal<D, T> {
     fn update(&mut self) {}
     fn connect(&mut self) {
@@ -221,6 +285,9 @@ impl<D: Direction, T: Synth> Block for Signal<D, T> {
     fn connect_all(&mut self) {}
 
     fn update_all(&mut self) {
+        if let Some(forced) = self.forced {
+            self.next = forced;
+        }
         self.changed = self.val != self.next;
         if self.changed {
             self.prev = self.val;
@@ -232,9 +299,25 @@ impl<D: Direction, T: Synth> Block for Signal<D, T> {
         self.changed
     }
 
+    fn has_changed_ports(&self) -> bool {
+        self.changed
+    }
+
+    fn has_changed_interface(&self) -> bool {
+        self.changed
+    }
+
+    fn update_pending(&mut self) {
+        self.update_all()
+    }
+
     fn accept(&self, name: &str, probe: &mut dyn Probe) {
         probe.visit_atom(name, self);
     }
+
+    fn accept_mut(&mut self, name: &str, probe: &mut dyn ProbeMut) {
+        probe.visit_atom(name, self);
+    }
 }
 
 impl Signal<In, Clock> {
@@ -260,6 +343,7 @@ impl<T: Synth> Signal<Out, T> {
             tristate_is_output: false,
             signal_is_undriven: false,
             constraints: vec![],
+            forced: None,
             dir: PhantomData,
         }
     }
@@ -286,6 +370,7 @@ impl<D: Direction, T: Synth> Default for Signal<D, T> {
             tristate_is_output: false,
             signal_is_undriven: false,
             constraints: vec![],
+            forced: None,
             dir: PhantomData,
         }
     }
@@ -359,7 +444,12 @@ impl<T: Synth> Signal<Out, T> {
 }
 
 impl<T: Synth> Signal<InOut, T> {
+    // In a four-state simulation, a bus that nothing drives reads as `Z`
     pub fn val(&self) -> T {
-        self.val
+        if self.signal_is_undriven && four_state_enabled() {
+            T::undriven().unwrap_or(self.val)
+        } else {
+            self.val
+        }
     }
 }
@@ -0,0 +1,261 @@
+use crate::atom::{Atom, AtomMut};
+use crate::block::Block;
+use crate::named_path::NamedPath;
+use crate::probe::{Probe, ProbeMut};
+use crate::synth::{BigUint, Synth};
+
+// Visits each signal of a circuit along with its hierarchical path (i.e., `uut.fifo.clock`)
+// and the depth of the block that holds it (the circuit itself is at depth 1)
+struct SignalWalker<F> {
+    path: NamedPath,
+    depth: usize,
+    visit: F,
+}
+
+impl<F> SignalWalker<F> {
+    fn new(visit: F) -> Self {
+        Self {
+            path: Default::default(),
+            depth: 0,
+            visit,
+        }
+    }
+}
+
+impl<F: FnMut(&str, usize, &dyn Atom)> Probe for SignalWalker<F> {
+    fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+        self.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        self.path.push(name);
+        (self.visit)(&self.path.flat("."), self.depth, signal);
+        self.path.pop();
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.pop();
+        self.depth -= 1;
+    }
+}
+
+impl<F: FnMut(&str, usize, &mut dyn AtomMut)> ProbeMut for SignalWalker<F> {
+    fn visit_start_scope(&mut self, name: &str) {
+        self.path.push(name);
+        self.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str) {
+        self.path.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &mut dyn AtomMut) {
+        self.path.push(name);
+        (self.visit)(&self.path.flat("."), self.depth, signal);
+        self.path.pop();
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str) {
+        self.path.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str) {
+        self.path.pop();
+        self.depth -= 1;
+    }
+}
+
+// Call `visit` with the path, the depth and the value of every signal in the circuit
+pub(crate) fn visit_signals<F: FnMut(&str, usize, &dyn Atom)>(uut: &dyn Block, visit: F) {
+    uut.accept("uut", &mut SignalWalker::new(visit));
+}
+
+// Call `visit` with the path, the depth and mutable access to every signal in the circuit
+pub(crate) fn visit_signals_mut<F: FnMut(&str, usize, &mut dyn AtomMut)>(
+    uut: &mut dyn Block,
+    visit: F,
+) {
+    uut.accept_mut("uut", &mut SignalWalker::new(visit));
+}
+
+// Visits only the signal at a given position in the order the circuit is walked, without
+// building any paths along the way
+struct AtomAt<F> {
+    position: usize,
+    target: usize,
+    visit: Option<F>,
+}
+
+impl<F> AtomAt<F> {
+    fn new(target: usize, visit: F) -> Self {
+        Self {
+            position: 0,
+            target,
+            visit: Some(visit),
+        }
+    }
+    fn take(&mut self) -> Option<F> {
+        self.position += 1;
+        if self.position == self.target + 1 {
+            self.visit.take()
+        } else {
+            None
+        }
+    }
+}
+
+impl<F: FnOnce(&dyn Atom)> Probe for AtomAt<F> {
+    fn visit_atom(&mut self, _name: &str, signal: &dyn Atom) {
+        if let Some(visit) = self.take() {
+            visit(signal);
+        }
+    }
+}
+
+impl<F: FnOnce(&mut dyn AtomMut)> ProbeMut for AtomAt<F> {
+    fn visit_atom(&mut self, _name: &str, signal: &mut dyn AtomMut) {
+        if let Some(visit) = self.take() {
+            visit(signal);
+        }
+    }
+}
+
+// Force (or release) the signal at `position`
+fn force(uut: &mut dyn Block, position: usize, value: Option<&BigUint>) -> bool {
+    let mut done = false;
+    uut.accept_mut(
+        "uut",
+        &mut AtomAt::new(position, |signal: &mut dyn AtomMut| {
+            done = match value {
+                Some(bits) => signal.force_bits(bits),
+                None => {
+                    signal.release();
+                    true
+                }
+            };
+        }),
+    );
+    done
+}
+
+/// The hierarchical paths of all of the signals in a circuit (i.e., `uut.controller.state.q`).
+/// The circuit itself is named `uut`, as it is in the traces written by the simulation.
+pub fn signal_paths(uut: &dyn Block) -> Vec<String> {
+    let mut paths = vec![];
+    visit_signals(uut, |path, _, _| paths.push(path.to_string()));
+    paths
+}
+
+/// A [SignalHandle] refers to a signal in a circuit by its hierarchical path (like
+/// `uut.controller.state.q`), which is resolved at run time, instead of through the
+/// fields of the circuit.  The path is made of the names of the blocks (and interfaces)
+/// that lead to the signal, starting with `uut` for the circuit itself.  This is the name
+/// the circuit has in the traces and in the reports of the checks; note that it is not the
+/// `top` module of the generated Verilog.  The path is resolved once, when the handle is
+/// found, to the position of the signal in the circuit, so reading or forcing it does not
+/// search by name again.  The handle can be used with any circuit of the same type (i.e., a
+/// checkpoint of the one it was found in).  Besides reading
+/// the value of the signal, the handle can force it to a value, which overrides
+/// whatever drives the signal until it is released.  This is useful for injecting faults
+/// into the internal nodes of a circuit from a testbench.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// #[derive(LogicBlock, Default)]
+/// struct Foo {
+///    pub enable: Signal<In, Bit>,
+///    pub active: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Foo {
+///   #[hdl_gen]
+///   fn update(&mut self) {
+///      self.active.next = self.enable.val();
+///   }
+/// }
+///
+/// let mut uut = Foo::default();
+/// let active = SignalHandle::find(&uut, "uut.active").unwrap();
+/// active.force(&mut uut, true);
+/// simulate(&mut uut, 10);
+/// assert_eq!(active.read_as::<Bit>(&uut), Some(true));
+/// active.release(&mut uut);
+/// simulate(&mut uut, 10);
+/// assert_eq!(active.read_as::<Bit>(&uut), Some(false));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignalHandle {
+    path: String,
+    position: usize,
+    bits: usize,
+    type_name: String,
+}
+
+impl SignalHandle {
+    /// Find the signal at `path` in the circuit.  Returns `None` if there is no such signal.
+    pub fn find(uut: &dyn Block, path: &str) -> Option<Self> {
+        let mut position = 0;
+        let mut found = None;
+        visit_signals(uut, |name, _, signal| {
+            if found.is_none() && name == path {
+                found = Some(Self {
+                    path: path.to_string(),
+                    position,
+                    bits: signal.bits(),
+                    type_name: signal.descriptor().name,
+                });
+            }
+            position += 1;
+        });
+        found
+    }
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    /// The width of the signal in bits
+    pub fn bits(&self) -> usize {
+        self.bits
+    }
+    /// The name of the type of the signal
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+    /// The current value of the signal, as a bit pattern (laid out as in the generated Verilog)
+    pub fn read(&self, uut: &dyn Block) -> BigUint {
+        let mut value = BigUint::default();
+        uut.accept(
+            "uut",
+            &mut AtomAt::new(self.position, |signal: &dyn Atom| {
+                value = signal.verilog().as_biguint()
+            }),
+        );
+        value
+    }
+    /// The current value of the signal, if it can be represented as a `S`
+    pub fn read_as<S: Synth>(&self, uut: &dyn Block) -> Option<S> {
+        S::from_bits(&self.read(uut))
+    }
+    /// Force the signal to hold `bits` until it is released.  Returns `false` if the bit
+    /// pattern is not a valid value for the signal (or the signal cannot be forced, like a
+    /// constant).  The circuit is updated the next time the simulation runs.
+    pub fn force_bits(&self, uut: &mut dyn Block, bits: &BigUint) -> bool {
+        force(uut, self.position, Some(bits))
+    }
+    /// Force the signal to hold `value` until it is released (see [SignalHandle::force_bits]).
+    pub fn force<S: Synth>(&self, uut: &mut dyn Block, value: S) -> bool {
+        self.force_bits(uut, &value.verilog().as_biguint())
+    }
+    /// Release the signal, so that it follows its driver again
+    pub fn release(&self, uut: &mut dyn Block) {
+        force(uut, self.position, None);
+    }
+}
@@ -1,11 +1,30 @@
+use corosensei::stack::DefaultStack;
+use corosensei::{Coroutine, CoroutineResult, Yielder};
 use crossbeam::channel::{bounded, Receiver, Sender};
 use crossbeam::channel::{RecvError, SendError};
 
 use crate::block::Block;
+use crate::cdc_jitter::{install_cdc_jitter, CDCJitter};
 use crate::check_error::{check_all, CheckError};
-use crate::vcd_probe::{write_vcd_change, write_vcd_dump, write_vcd_header};
+use crate::coverage::FsmCoverage;
+use crate::equivalence::{EquivalenceError, StimulusRecorder, VerilogSimulator};
+use crate::four_state::{install_four_state, make_unknown};
+use crate::module_defines::generate_verilog;
+use crate::regression::{derive_seed, seed_sim_rng, TaskRng};
+use crate::time_units::{Frequency, SimTime};
+use crate::trace_filter::TraceFilter;
+use crate::tracer::{FSTTracer, TraceFormat, Tracer, VCDTracer};
+use crate::vcd_replay::VCDReplay;
+use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::cell::{Cell, RefCell};
+use std::fmt::{Display, Formatter};
 use std::io::Write;
-use std::thread::JoinHandle;
+use std::panic::{AssertUnwindSafe, RefUnwindSafe};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+use std::thread::{JoinHandle, ThreadId};
 
 /// Update changes to a circuit until it stabilizes
 ///
@@ -26,6 +45,74 @@ pub fn simulate<B: Block>(uut: &mut B, max_iters: usize) -> bool {
     false
 }
 
+/// Update changes to a circuit until it stabilizes, but only re-evaluate the blocks
+/// for which one of the signals they read changed (see [Block::update_pending]).
+///
+/// # Arguments
+///
+/// * `uut` - reference to the circuit - must implement the [Block] trait
+/// * `max_iters` - the maximum number of iterations to try and stabilize the circuit
+///
+/// Returns `true` if the circuit stabilizes, and `false` if not.  A block that has never
+/// been evaluated has not computed its outputs yet, so the circuit must be stabilized with
+/// [simulate] once after it is connected.  From then on, the results are identical to
+/// [simulate].
+pub fn simulate_pending<B: Block>(uut: &mut B, max_iters: usize) -> bool {
+    for _ in 0..max_iters {
+        uut.update_pending();
+        if !uut.has_changed() {
+            return true;
+        }
+    }
+    false
+}
+
+/// The [Scheduler] determines how a [Simulation] evaluates the circuit on
+/// each delta cycle.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Scheduler {
+    /// Every block in the circuit is re-evaluated on every delta cycle.  This is the default.
+    #[default]
+    Sweep,
+    /// Only the blocks for which one of the signals they read changed on the previous delta
+    /// cycle are re-evaluated (after a first delta cycle that evaluates every block).  This
+    /// produces identical results to [Scheduler::Sweep], but can be much faster for large
+    /// circuits where most of the logic is idle on any given delta cycle.
+    EventDriven,
+}
+
+/// A [SimBackend] takes over the evaluation of the circuit from its Rust model.  After each
+/// testbench (or clock) has run, the backend is handed the circuit, and is responsible for
+/// latching the top level inputs, evaluating the design, and driving the top level outputs.
+/// See [VerilatorModel](crate::verilator::VerilatorModel) for a backend that runs the
+/// generated Verilog.
+pub trait SimBackend<T> {
+    /// Evaluate the circuit.  Returns `true` if any of the top level signals changed.
+    fn evaluate(&mut self, x: &mut T) -> bool;
+}
+
+/// A [Monitor] passively watches a simulation of a circuit `T`.  Unlike a [Tracer], it is
+/// shown the circuit itself (rather than a [Block]), so it can read the signals of the
+/// interfaces it is interested in directly.  A monitor must not change the circuit.
+pub trait Monitor<T> {
+    /// Called with the state of the circuit after every time step (including the initial state).
+    fn sample(&mut self, time: u64, x: &T);
+}
+
+/// The [Executor] determines how the testbenches (and clocks) of a [Simulation] are run.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Executor {
+    /// Each testbench runs in its own thread, and hands the circuit back and forth
+    /// with the simulation over a channel.  This is the default.
+    #[default]
+    Threaded,
+    /// Each testbench runs as a cooperative task on the thread that calls `run`.  The
+    /// testbenches are written exactly as before, but no threads are spawned, the
+    /// order of execution is deterministic, and the whole simulation can be stepped
+    /// through in a debugger.
+    Cooperative,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 /// The error type returned by a simulation
 pub enum SimError {
@@ -34,13 +121,146 @@ pub enum SimError {
     /// The simulation reached the maximum allowed time for the simulation
     MaxTimeReached,
     /// The simulation halted - usually this means an assertion failed
-    SimHalted,
+    SimHalted(Box<SimFailure>),
     /// The circuit failed to converge.  This means the logic has some issue (like an oscillation).
     FailedToConverge,
     /// Something went wrong with the circuit check (either a missing connection or other issue, like a latching write).
     Check(CheckError),
+    /// The trace could not be written (i.e., the file could not be created, or the disk is full)
+    TraceFailed(String),
     /// The simulation panicked.  This usually means `.unwrap` was called on a result in the testbench.
-    SimPanic,
+    SimPanic(Box<SimFailure>),
+}
+
+/// The details of a failed assertion (or a panic) in a simulation.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct SimFailure {
+    /// The assertion that failed, or the panic message
+    pub message: String,
+    /// The expressions in the assertion, and their values (formatted with `Debug`)
+    pub values: Vec<(String, String)>,
+    /// The simulation time (in picoseconds) at which the failure happened
+    pub time: u64,
+    /// The testbench (or clock) that failed.  This is the order in which it was added to the simulation.
+    pub testbench: Option<usize>,
+    /// The source location (`file:line`) of the assertion or panic
+    pub location: Option<String>,
+    /// The backtrace of a panic.  This is only captured if `RUST_BACKTRACE` is set.
+    pub backtrace: Option<String>,
+}
+
+impl SimFailure {
+    pub fn new<S: ToString>(message: S) -> Self {
+        Self {
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+    /// Record the value of an expression in the failed assertion
+    pub fn with_value<S: ToString, V: std::fmt::Debug>(mut self, expression: S, value: V) -> Self {
+        self.values
+            .push((expression.to_string(), format!("{:?}", value)));
+        self
+    }
+    /// Record the source location of the failed assertion
+    pub fn at(mut self, file: &str, line: u32) -> Self {
+        self.location = Some(format!("{}:{}", file, line));
+        self
+    }
+    // Fill in the part of the failure only known to the simulation
+    fn locate(mut self, time: u64, testbench: usize) -> Self {
+        self.time = time;
+        self.testbench = Some(testbench);
+        self
+    }
+}
+
+impl Display for SimFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at time {} ps", self.message, self.time)?;
+        if let Some(testbench) = self.testbench {
+            write!(f, " in testbench {}", testbench)?;
+        }
+        if let Some(location) = &self.location {
+            write!(f, " ({})", location)?;
+        }
+        for (expression, value) in &self.values {
+            write!(f, "\n  {} = {}", expression, value)?;
+        }
+        if let Some(backtrace) = &self.backtrace {
+            write!(f, "\n{}", backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for SimError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimError::SimHalted(failure) => write!(f, "Simulation halted: {}", failure),
+            SimError::SimPanic(failure) => write!(f, "Simulation panicked: {}", failure),
+            SimError::Check(check) => write!(f, "Circuit check failed: {:?}", check),
+            SimError::TraceFailed(e) => write!(f, "Unable to write the trace: {}", e),
+            _ => write!(f, "{:?}", self),
+        }
+    }
+}
+
+impl SimError {
+    // Fill in where a halt or panic happened, if the worker that failed did not know
+    fn locate(self, time: u64, testbench: usize) -> Self {
+        match self {
+            SimError::SimHalted(x) if x.testbench.is_none() => {
+                SimError::SimHalted(Box::new(x.locate(time, testbench)))
+            }
+            SimError::SimPanic(x) if x.testbench.is_none() => {
+                SimError::SimPanic(Box::new(x.locate(time, testbench)))
+            }
+            x => x,
+        }
+    }
+}
+
+thread_local! {
+    // The location and backtrace of the last panic on this thread
+    static LAST_PANIC: RefCell<(Option<String>, Option<String>)> = const { RefCell::new((None, None)) };
+}
+
+// Chain a panic hook that records where a panic happened, so that the
+// simulation can report it once the panic is caught.
+fn install_panic_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let location = info
+                .location()
+                .map(|x| format!("{}:{}", x.file(), x.line()));
+            let backtrace = Backtrace::capture();
+            let backtrace =
+                (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string());
+            LAST_PANIC.with(|x| *x.borrow_mut() = (location, backtrace));
+            previous(info);
+        }));
+    });
+}
+
+// Convert a caught panic into a failure
+pub(crate) fn panic_failure(payload: Box<dyn Any + Send>) -> SimFailure {
+    let message = if let Some(x) = payload.downcast_ref::<&str>() {
+        x.to_string()
+    } else if let Some(x) = payload.downcast_ref::<String>() {
+        x.clone()
+    } else {
+        "Unknown panic".to_string()
+    };
+    let (location, backtrace) = LAST_PANIC.with(|x| x.take());
+    SimFailure {
+        message,
+        location,
+        backtrace,
+        ..Default::default()
+    }
 }
 
 impl From<CheckError> for SimError {
@@ -69,7 +289,7 @@ enum TriggerType<T> {
     Time(u64),
     Function(Box<dyn Fn(&T) -> bool + Send>),
     Clock(u64),
-    Halt,
+    Halt(Box<SimFailure>),
 }
 
 struct Message<T> {
@@ -79,20 +299,104 @@ struct Message<T> {
 
 enum MessageOrPanic<T> {
     Message(Message<T>),
-    Panic,
+    Panic(SimFailure),
+}
+
+// A suspended testbench, that is resumed by handing it the circuit
+type Task<T> = Box<dyn FnMut(Message<T>) -> CoroutineResult<Message<T>, Result<()>>>;
+
+// Matches the default stack size of a spawned thread
+const TASK_STACK_SIZE: usize = 2 * 1024 * 1024;
+
+type ClockFn<T> = dyn Fn(&mut Box<T>);
+
+#[derive(Copy, Clone)]
+enum ClockPhase {
+    Start,
+    Delayed,
+    Running,
+    Resume(u64),
+}
+
+// Clocks are run by the simulation itself, so that their phase can be checkpointed
+struct ClockWorker<T> {
+    // The time until the first toggle, and between the second and the third, and
+    // so on, followed by the time between the first and the second toggle
+    intervals: [u64; 2],
+    toggles: u64,
+    phase_delay: Option<u64>,
+    clock_fn: Rc<ClockFn<T>>,
+    phase: ClockPhase,
+}
+
+impl<T> ClockWorker<T> {
+    fn next(&mut self, time: u64, x: &mut Box<T>) -> Result<TriggerType<T>> {
+        match self.phase {
+            ClockPhase::Start => {
+                if let Some(delay) = self.phase_delay {
+                    self.phase = ClockPhase::Delayed;
+                    return Ok(TriggerType::Time(time + delay));
+                }
+            }
+            ClockPhase::Delayed => {}
+            ClockPhase::Resume(wake) => {
+                self.phase = ClockPhase::Running;
+                return Ok(TriggerType::Clock(wake));
+            }
+            ClockPhase::Running => {
+                std::panic::catch_unwind(AssertUnwindSafe(|| (self.clock_fn)(x)))
+                    .map_err(|e| SimError::SimPanic(Box::new(panic_failure(e))))?;
+                self.toggles += 1;
+            }
+        }
+        self.phase = ClockPhase::Running;
+        Ok(TriggerType::Clock(
+            time + self.intervals[(self.toggles % 2) as usize],
+        ))
+    }
+    fn resume_at(&self, wake: u64) -> ClockWorker<T> {
+        ClockWorker {
+            phase: ClockPhase::Resume(wake),
+            ..self.clone()
+        }
+    }
+}
+
+impl<T> Clone for ClockWorker<T> {
+    fn clone(&self) -> Self {
+        ClockWorker {
+            intervals: self.intervals,
+            toggles: self.toggles,
+            phase_delay: self.phase_delay,
+            clock_fn: self.clock_fn.clone(),
+            phase: self.phase,
+        }
+    }
+}
+
+enum WorkerLink<T> {
+    Channel(Sender<Message<T>>),
+    Task(Task<T>),
+    Clock(ClockWorker<T>),
 }
 
 struct Worker<T> {
     id: usize,
-    channel_to_worker: Sender<Message<T>>,
+    link: WorkerLink<T>,
     kind: TriggerType<T>,
 }
 
+type TestbenchFn<T> = Box<dyn Fn(Sim<T>) -> Result<()> + Send + RefUnwindSafe>;
+
 /// The [CustomLogicFn] is a boxed function that can be used to implement
 /// things (like tri-state buffers or open collector shared busses) that
 /// are otherwise difficult or impossible to model.
 pub type CustomLogicFn<T> = Box<dyn Fn(&mut T) -> ()>;
 
+/// The [TriggerFn] is a boxed predicate on the circuit that is used to
+/// hold off tracing until the circuit reaches some state of interest.
+pub type TriggerFn<T> = Rc<dyn Fn(&T) -> bool>;
+
 /// This type represents a simulation over a circuit `T`.   To simulate
 /// a circuit, you will need to construct one of these structs.
 pub struct Simulation<T> {
@@ -101,7 +405,83 @@ pub struct Simulation<T> {
     channel_to_sim: Sender<MessageOrPanic<T>>,
     time: u64,
     testbenches: Vec<JoinHandle<Result<()>>>,
-    custom_logic: Vec<CustomLogicFn<T>>,
+    pending: Vec<(usize, Sim<T>, TestbenchFn<T>)>,
+    executor: Executor,
+    resuming: Rc<Cell<bool>>,
+    custom_logic: Vec<Rc<CustomLogicFn<T>>>,
+    scheduler: Scheduler,
+    trace_filter: TraceFilter,
+    trace_trigger: Option<TriggerFn<T>>,
+    observers: Vec<Rc<RefCell<dyn Tracer>>>,
+    monitors: Vec<Rc<RefCell<dyn Monitor<T>>>>,
+    backend: Option<Box<dyn SimBackend<T>>>,
+    seed: Option<u64>,
+    cdc_jitter: Option<CDCJitter>,
+    fsm_coverage: Option<Rc<RefCell<FsmCoverage>>>,
+    fsm_report: bool,
+    four_state: bool,
+    // Every block has been evaluated at least once in this run
+    swept: bool,
+}
+
+/// A [Checkpoint] captures the state of a [Simulation] once all of its testbenches
+/// have finished - the circuit, the simulation time, and the phase of each clock.
+/// A checkpoint is created by [Simulation::run_to_checkpoint], and can then be
+/// restored (or forked any number of times) to run new testbenches, starting from
+/// that state, without re-simulating the preamble.
+///
+/// The settings of the simulation (the scheduler, executor, trace filter and trigger,
+/// seed and CDC jitter) are carried over to the restored simulation.  Observers, monitors
+/// and the [SimBackend] are not - they hold the results (or the state) of the run that
+/// made the checkpoint, so attach new ones to the restored simulation if they are needed.
+///
+/// # Example
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+///
+/// #[derive(LogicBlock, Clone, Default)]
+/// struct Foo {
+///    pub clock: Signal<In, Clock>
+/// }
+///
+/// impl Logic for Foo {
+///   #[hdl_gen]
+///   fn update(&mut self) {
+///   }
+/// }
+///
+/// let mut sim : Simulation<Foo> = Default::default();
+/// sim.add_clock(5, |x| x.clock.next = !x.clock.val());
+/// sim.add_testbench(|mut ep: Sim<Foo>| {
+///     let x = ep.init()?;
+///     let x = ep.wait(1000, x)?;   // Warm up
+///     ep.done(x)
+/// });
+/// let checkpoint = sim.run_to_checkpoint(Box::new(Foo::default()), 10_000).unwrap();
+/// assert_eq!(checkpoint.time(), 1000);
+/// for _ in 0..2 {
+///     let (mut sim, uut) = checkpoint.fork();
+///     sim.add_testbench(|mut ep: Sim<Foo>| {
+///         let x = ep.init()?;
+///         assert_eq!(ep.time(), 1000);
+///         let x = ep.wait(500, x)?;
+///         ep.done(x)
+///     });
+///     sim.run(uut, 10_000).unwrap();
+/// }
+/// ```
+pub struct Checkpoint<T> {
+    circuit: Box<T>,
+    time: u64,
+    clocks: Vec<ClockWorker<T>>,
+    custom_logic: Vec<Rc<CustomLogicFn<T>>>,
+    scheduler: Scheduler,
+    executor: Executor,
+    trace_filter: TraceFilter,
+    trace_trigger: Option<TriggerFn<T>>,
+    seed: Option<u64>,
+    cdc_jitter: Option<CDCJitter>,
 }
 
 /// The `Sim` struct is used to communicate with a simulation.  Every testbench
@@ -109,15 +489,44 @@ pub struct Simulation<T> {
 /// with the core simulation.
 pub struct Sim<T> {
     time: u64,
-    to_sim: Sender<MessageOrPanic<T>>,
-    from_sim: Receiver<Message<T>>,
+    link: SimLink<T>,
+}
+
+enum SimLink<T> {
+    Thread {
+        to_sim: Sender<MessageOrPanic<T>>,
+        from_sim: Receiver<Message<T>>,
+    },
+    Task(TaskLink<T>),
+}
+
+struct TaskLink<T> {
+    yielder: *const Yielder<Message<T>, Message<T>>,
+    owner: ThreadId,
+    init: Cell<Option<Box<T>>>,
+}
+
+// The yielder is only ever dereferenced on the owning thread (which is checked)
+unsafe impl<T: Send> Send for TaskLink<T> {}
+
+impl<T> TaskLink<T> {
+    fn suspend(&self, msg: Message<T>) -> Message<T> {
+        assert_eq!(
+            std::thread::current().id(),
+            self.owner,
+            "A cooperative testbench cannot be moved to another thread"
+        );
+        // Safety - the yielder lives as long as the task that runs the testbench,
+        // and the link is only used by the testbench itself.
+        unsafe { (*self.yielder).suspend(msg) }
+    }
 }
 
 struct NextTime {
     time: u64,
     idx: usize,
     clocks_only: bool,
-    halted: bool,
+    halted: Option<SimFailure>,
 }
 
 impl<T: Send + 'static + Block> Default for Simulation<T> {
@@ -136,9 +545,193 @@ impl<T: Send + 'static + Block> Simulation<T> {
             channel_to_sim: send,
             time: 0,
             testbenches: vec![],
+            pending: vec![],
+            executor: Executor::default(),
+            resuming: Rc::new(Cell::new(false)),
             custom_logic: vec![],
+            scheduler: Scheduler::default(),
+            trace_filter: TraceFilter::default(),
+            trace_trigger: None,
+            observers: vec![],
+            monitors: vec![],
+            seed: None,
+            cdc_jitter: None,
+            backend: None,
+            fsm_coverage: None,
+            fsm_report: true,
+            four_state: false,
+            swept: false,
+        }
+    }
+    /// Select the [Scheduler] used to evaluate the circuit
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rust_hdl_core::prelude::*;
+    ///
+    /// #[derive(LogicBlock)]
+    /// struct Foo {
+    ///    pub clock: Signal<In, Clock>
+    /// }
+    ///
+    /// impl Logic for Foo {
+    ///   #[hdl_gen]
+    ///   fn update(&mut self) {
+    ///   }
+    /// }
+    ///
+    /// let mut sim : Simulation<Foo> = Default::default();
+    /// sim.set_scheduler(Scheduler::EventDriven);
+    /// ```
+    pub fn set_scheduler(&mut self, scheduler: Scheduler) {
+        self.scheduler = scheduler;
+    }
+    /// Select the [Executor] used to run the testbenches.  This can be changed at any
+    /// point before the simulation is run.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rust_hdl_core::prelude::*;
+    ///
+    /// #[derive(LogicBlock)]
+    /// struct Foo {
+    ///    pub clock: Signal<In, Clock>
+    /// }
+    ///
+    /// impl Logic for Foo {
+    ///   #[hdl_gen]
+    ///   fn update(&mut self) {
+    ///   }
+    /// }
+    ///
+    /// let mut sim : Simulation<Foo> = Default::default();
+    /// sim.add_clock(5, |x| x.clock.next = !x.clock.val());
+    /// sim.set_executor(Executor::Cooperative);
+    /// ```
+    pub fn set_executor(&mut self, executor: Executor) {
+        self.executor = executor;
+    }
+    /// Restrict the traces written by the simulation to the signals and time window
+    /// selected by a [TraceFilter].  This applies to all of the traced `run` methods.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rust_hdl_core::prelude::*;
+    ///
+    /// #[derive(LogicBlock)]
+    /// struct Foo {
+    ///    pub clock: Signal<In, Clock>
+    /// }
+    ///
+    /// impl Logic for Foo {
+    ///   #[hdl_gen]
+    ///   fn update(&mut self) {
+    ///   }
+    /// }
+    ///
+    /// let mut sim : Simulation<Foo> = Default::default();
+    /// sim.set_trace_filter(TraceFilter {
+    ///     paths: vec!["uut.clock".into()],
+    ///     start_time: 1_000,
+    ///     ..Default::default()
+    /// });
+    /// ```
+    pub fn set_trace_filter(&mut self, filter: TraceFilter) {
+        self.trace_filter = filter;
+    }
+    /// Hold off tracing until the `trigger` returns `true` for the circuit.  Once the
+    /// trigger fires, the trace records a full dump of the circuit and then continues
+    /// as normal (subject to any [TraceFilter]).
+    pub fn set_trace_trigger<F>(&mut self, trigger: F)
+    where
+        F: Fn(&T) -> bool + 'static,
+    {
+        self.trace_trigger = Some(Rc::new(trigger));
+    }
+    /// Attach an observer to the simulation.  Like a trace, the observer is shown the state of
+    /// the circuit after every time step, but it is used by all of the `run` methods, and is
+    /// not affected by the [TraceFilter] or trace trigger.  Returns a shared handle to the
+    /// observer, so that it can be inspected once the simulation is complete (see
+    /// [ToggleCoverage](crate::coverage::ToggleCoverage) for an example).
+    pub fn add_observer<O: Tracer + 'static>(&mut self, observer: O) -> Rc<RefCell<O>> {
+        let observer = Rc::new(RefCell::new(observer));
+        self.observers.push(observer.clone());
+        observer
+    }
+    /// Collect the state and transition coverage of the state machines in the circuit (see
+    /// [FsmCoverage]).  Returns a shared handle to the coverage, which can also be read with
+    /// [Simulation::fsm_coverage].  Calling it again returns the same handle.
+    pub fn enable_fsm_coverage(&mut self) -> Rc<RefCell<FsmCoverage>> {
+        if let Some(fsm_coverage) = &self.fsm_coverage {
+            return fsm_coverage.clone();
+        }
+        let fsm_coverage = self.add_observer(FsmCoverage::default());
+        self.fsm_coverage = Some(fsm_coverage.clone());
+        fsm_coverage
+    }
+    /// The state and transition coverage of the state machines in the circuit, as of the
+    /// last run, if it was enabled with [Simulation::enable_fsm_coverage].
+    pub fn fsm_coverage(&self) -> Option<std::cell::Ref<'_, FsmCoverage>> {
+        self.fsm_coverage.as_ref().map(|x| x.borrow())
+    }
+    /// Once FSM coverage is enabled, the [FsmCoverage] of a run is printed (to `stderr`) when
+    /// it finishes, if any of the state machines did not enter all of their states, or take
+    /// all of their transitions.  Pass `false` to turn that off.
+    pub fn set_fsm_report(&mut self, report: bool) {
+        self.fsm_report = report;
+    }
+    /// Replace the Rust model of the circuit with a [SimBackend].  The testbenches are
+    /// unchanged - they still set the inputs and read the outputs of the top level circuit,
+    /// but the internal state of the circuit is no longer updated.
+    pub fn set_backend<B: SimBackend<T> + 'static>(&mut self, backend: B) {
+        self.backend = Some(Box::new(backend));
+    }
+    /// Seed the simulation random number generator (see [sim_rng](crate::regression::sim_rng)).
+    /// Each testbench gets its own generator, seeded from `seed` and the order in which it was
+    /// added, and the generator of the thread that runs the simulation (used by the clocks
+    /// and custom logic) is seeded with `seed` itself.  This makes a testbench that draws its
+    /// random numbers from [sim_rng](crate::regression::sim_rng) reproducible.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+    }
+    /// Turn four-state simulation on or off for this simulation.  When it is on, every
+    /// [FourState](crate::four_state::FourState) signal in the circuit is set to `X` when the
+    /// run starts, so that a register that is read before it is first written (or an input
+    /// that the testbench never drives) shows up as unknown, instead of as zero.  Signals of
+    /// the two-state types ([Bits](crate::bits::Bits), `Bit` and enums) are not affected.
+    /// The default is off.  A [Checkpoint] does not keep this setting, since restoring the
+    /// state of the circuit is the whole point.
+    pub fn set_four_state(&mut self, enabled: bool) {
+        self.four_state = enabled;
+    }
+    /// Inject clock domain crossing faults into the synchronizers of the circuit (see [CDCJitter]).
+    /// The crossings in `BitSynchronizer`, `VectorSynchronizer` (and so `AsynchronousFIFO` and
+    /// the cross-clock FIFOs) then resolve some changes one clock late.  Use it with [Simulation::set_seed]
+    /// (or a [Regression](crate::regression::Regression)) to make a failure reproducible.
+    pub fn set_cdc_jitter(&mut self, jitter: CDCJitter) {
+        self.cdc_jitter = Some(jitter);
+    }
+    /// Attach a [Monitor] to the simulation.  Returns a shared handle to the monitor, so that
+    /// the transactions it collected can be inspected once the simulation is complete.
+    pub fn add_monitor<M: Monitor<T> + 'static>(&mut self, monitor: M) -> Rc<RefCell<M>> {
+        let monitor = Rc::new(RefCell::new(monitor));
+        self.monitors.push(monitor.clone());
+        monitor
+    }
+    fn observe(&self, x: &T) {
+        for observer in &self.observers {
+            observer.borrow_mut().step(self.time, x);
+        }
+        for monitor in &self.monitors {
+            monitor.borrow_mut().sample(self.time, x);
         }
     }
+    fn trace_triggered(&self, x: &T) -> bool {
+        self.trace_trigger.as_ref().map(|f| f(x)).unwrap_or(true)
+    }
     /// Add a clock function to the simulation
     ///
     /// # Arguments
@@ -170,12 +763,12 @@ impl<T: Send + 'static + Block> Simulation<T> {
     where
         F: Fn(&mut Box<T>) -> () + Send + 'static + std::panic::RefUnwindSafe,
     {
-        self.add_testbench(move |mut ep: Sim<T>| {
-            let mut x = ep.init()?;
-            loop {
-                x = ep.clock(interval, x)?;
-                clock_fn(&mut x);
-            }
+        self.add_clock_worker(ClockWorker {
+            intervals: [interval, interval],
+            toggles: 0,
+            phase_delay: None,
+            clock_fn: Rc::new(clock_fn),
+            phase: ClockPhase::Start,
         });
     }
     /// Add a phased clock to the simulation
@@ -215,13 +808,85 @@ impl<T: Send + 'static + Block> Simulation<T> {
     where
         F: Fn(&mut Box<T>) -> () + Send + 'static + std::panic::RefUnwindSafe,
     {
-        self.add_testbench(move |mut ep: Sim<T>| {
-            let mut x = ep.init()?;
-            x = ep.wait(phase_delay, x)?;
-            loop {
-                x = ep.clock(interval, x)?;
-                clock_fn(&mut x);
-            }
+        self.add_clock_worker(ClockWorker {
+            intervals: [interval, interval],
+            toggles: 0,
+            phase_delay: Some(phase_delay),
+            clock_fn: Rc::new(clock_fn),
+            phase: ClockPhase::Start,
+        });
+    }
+    /// Add a clock with the given frequency (and a 50% duty cycle) to the simulation.
+    /// This is the same as calling [Simulation::add_clock] with the half period of the clock.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rust_hdl_core::prelude::*;
+    ///
+    /// #[derive(LogicBlock)]
+    /// struct Foo {
+    ///    pub clock: Signal<In, Clock>
+    /// }
+    ///
+    /// impl Logic for Foo {
+    ///   #[hdl_gen]
+    ///   fn update(&mut self) {
+    ///   }
+    /// }
+    ///
+    /// let mut sim : Simulation<Foo> = Default::default();
+    /// // Toggles every 5 nanoseconds
+    /// sim.add_clock_frequency(Frequency::from_mhz(100.0), |x| x.clock.next = !x.clock.val());
+    /// ```
+    pub fn add_clock_frequency<F>(&mut self, frequency: Frequency, clock_fn: F)
+    where
+        F: Fn(&mut Box<T>) + Send + 'static + std::panic::RefUnwindSafe,
+    {
+        self.add_clock(frequency.half_period().ps(), clock_fn)
+    }
+    /// Add a clock with the given frequency and duty cycle (the fraction of each period
+    /// that the clock is high) to the simulation.  The clock is assumed to start low, so
+    /// the first call to `clock_fn` (which toggles the clock) is the first rising edge.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use rust_hdl_core::prelude::*;
+    ///
+    /// #[derive(LogicBlock)]
+    /// struct Foo {
+    ///    pub clock: Signal<In, Clock>
+    /// }
+    ///
+    /// impl Logic for Foo {
+    ///   #[hdl_gen]
+    ///   fn update(&mut self) {
+    ///   }
+    /// }
+    ///
+    /// let mut sim : Simulation<Foo> = Default::default();
+    /// // High for 2.5 nanoseconds, and low for 7.5 nanoseconds
+    /// sim.add_clock_duty(Frequency::from_mhz(100.0), 0.25, |x| x.clock.next = !x.clock.val());
+    /// ```
+    pub fn add_clock_duty<F>(&mut self, frequency: Frequency, duty_cycle: f64, clock_fn: F)
+    where
+        F: Fn(&mut Box<T>) + Send + 'static + std::panic::RefUnwindSafe,
+    {
+        let (high, low) = frequency.high_low(duty_cycle);
+        self.add_clock_worker(ClockWorker {
+            intervals: [low.ps(), high.ps()],
+            toggles: 0,
+            phase_delay: None,
+            clock_fn: Rc::new(clock_fn),
+            phase: ClockPhase::Start,
+        });
+    }
+    fn add_clock_worker(&mut self, clock: ClockWorker<T>) {
+        self.workers.push(Worker {
+            id: self.workers.len(),
+            link: WorkerLink::Clock(clock),
+            kind: TriggerType::Never,
         });
     }
     /// Add a testbench to the simulation
@@ -238,51 +903,140 @@ impl<T: Send + 'static + Block> Simulation<T> {
     where
         F: Fn(Sim<T>) -> Result<()> + Send + 'static + std::panic::RefUnwindSafe,
     {
+        let id = self.workers.len();
         let ep = self.endpoint();
-        self.testbenches.push(std::thread::spawn(move || {
-            let ep_panic = ep.to_sim.clone();
-            let result = std::panic::catch_unwind(|| testbench(ep));
-            match result {
-                Ok(x) => x,
-                Err(_e) => {
-                    ep_panic.send(MessageOrPanic::Panic).unwrap();
-                    Err(SimError::SimPanic)
+        self.pending.push((id, ep, Box::new(testbench)));
+    }
+    /// Drive the inputs of the circuit from a VCD file (see [VCDReplay]).  The replay runs as
+    /// a testbench, so the simulation does not end until every change in the file has been applied.
+    pub fn add_vcd_replay(&mut self, replay: VCDReplay) {
+        self.add_testbench(move |ep: Sim<T>| replay.drive(ep));
+    }
+    // Start the testbenches that have been added to the simulation, using the
+    // selected executor.
+    fn launch(&mut self) {
+        for (id, mut ep, testbench) in std::mem::take(&mut self.pending) {
+            ep.time = self.time;
+            let seed = self.seed.map(|x| derive_seed(x, id as u64));
+            let four_state = self.four_state;
+            match self.executor {
+                Executor::Threaded => {
+                    self.testbenches.push(std::thread::spawn(move || {
+                        let ep_panic = match &ep.link {
+                            SimLink::Thread { to_sim, .. } => to_sim.clone(),
+                            SimLink::Task(_) => unreachable!(),
+                        };
+                        if let Some(seed) = seed {
+                            seed_sim_rng(seed);
+                        }
+                        // The testbench reads the signals on its own thread
+                        install_four_state(four_state);
+                        let result = std::panic::catch_unwind(AssertUnwindSafe(|| testbench(ep)));
+                        match result {
+                            Ok(x) => x,
+                            Err(e) => {
+                                let failure = panic_failure(e);
+                                ep_panic
+                                    .send(MessageOrPanic::Panic(failure.clone()))
+                                    .unwrap();
+                                Err(SimError::SimPanic(Box::new(failure)))
+                            }
+                        }
+                    }));
+                }
+                Executor::Cooperative => {
+                    let resuming = self.resuming.clone();
+                    let mut rng = TaskRng::new(seed);
+                    let stack = DefaultStack::new(TASK_STACK_SIZE).unwrap();
+                    let mut task = Coroutine::with_stack(
+                        stack,
+                        move |yielder: &Yielder<Message<T>, Message<T>>, first: Message<T>| {
+                            let ep = Sim {
+                                time: match first.kind {
+                                    TriggerType::Time(t) => t,
+                                    _ => 0,
+                                },
+                                link: SimLink::Task(TaskLink {
+                                    yielder,
+                                    owner: std::thread::current().id(),
+                                    init: Cell::new(Some(first.circuit)),
+                                }),
+                            };
+                            match std::panic::catch_unwind(AssertUnwindSafe(|| testbench(ep))) {
+                                Ok(x) => x,
+                                // A task that is dropped while suspended is unwound, and that
+                                // unwind must be allowed to continue.
+                                Err(e) if !resuming.get() => std::panic::resume_unwind(e),
+                                Err(e) => Err(SimError::SimPanic(Box::new(panic_failure(e)))),
+                            }
+                        },
+                    );
+                    self.workers[id].link = WorkerLink::Task(Box::new(move |x| {
+                        rng.swap();
+                        let result = task.resume(x);
+                        rng.swap();
+                        result
+                    }));
                 }
             }
-        }));
+        }
     }
     pub fn add_custom_logic<F>(&mut self, logic: F)
     where
         F: Fn(&mut T) -> () + 'static,
     {
-        self.custom_logic.push(Box::new(logic));
+        self.custom_logic.push(Rc::new(Box::new(logic)));
     }
     pub fn endpoint(&mut self) -> Sim<T> {
         let (send_to_worker, recv_from_sim_to_worker) = bounded(0);
         let id = self.workers.len();
         let worker = Worker {
             id,
-            channel_to_worker: send_to_worker,
+            link: WorkerLink::Channel(send_to_worker),
             kind: TriggerType::Never,
         };
         self.workers.push(worker);
         Sim {
-            to_sim: self.channel_to_sim.clone(),
-            from_sim: recv_from_sim_to_worker,
+            link: SimLink::Thread {
+                to_sim: self.channel_to_sim.clone(),
+                from_sim: recv_from_sim_to_worker,
+            },
             time: 0,
         }
     }
     fn dispatch(&mut self, idx: usize, x: Box<T>) -> Result<Box<T>> {
         let worker = &mut self.workers[idx];
-        worker.channel_to_worker.send(Message {
+        let msg = Message {
             kind: TriggerType::Time(self.time),
             circuit: x,
-        })?;
-        let x = self.recv.recv()?;
-        let mut x = match x {
-            MessageOrPanic::Message(x) => x,
-            MessageOrPanic::Panic => {
-                return Err(SimError::SimPanic);
+        };
+        let mut x = match &mut worker.link {
+            WorkerLink::Channel(channel_to_worker) => {
+                channel_to_worker.send(msg)?;
+                match self.recv.recv()? {
+                    MessageOrPanic::Message(x) => x,
+                    MessageOrPanic::Panic(failure) => {
+                        return Err(SimError::SimPanic(Box::new(failure.locate(self.time, idx))));
+                    }
+                }
+            }
+            WorkerLink::Clock(clock) => {
+                let Message { mut circuit, .. } = msg;
+                let kind = clock
+                    .next(self.time, &mut circuit)
+                    .map_err(|e| e.locate(self.time, idx))?;
+                Message { kind, circuit }
+            }
+            WorkerLink::Task(task) => {
+                self.resuming.set(true);
+                let result = task(msg);
+                self.resuming.set(false);
+                match result {
+                    CoroutineResult::Yield(x) => x,
+                    // The testbench exited without handing back the circuit
+                    CoroutineResult::Return(Ok(())) => return Err(SimError::SimTerminated),
+                    CoroutineResult::Return(Err(e)) => return Err(e.locate(self.time, idx)),
+                }
             }
         };
         worker.kind = x.kind;
@@ -292,8 +1046,19 @@ impl<T: Send + 'static + Block> Simulation<T> {
             for l in &self.custom_logic {
                 l(&mut x.circuit);
             }
-            x.circuit.update_all();
-            if !x.circuit.has_changed() {
+            let changed = match (&mut self.backend, self.scheduler) {
+                (Some(backend), _) => backend.evaluate(&mut x.circuit),
+                (None, Scheduler::EventDriven) if self.swept => {
+                    x.circuit.update_pending();
+                    x.circuit.has_changed()
+                }
+                (None, _) => {
+                    x.circuit.update_all();
+                    self.swept = true;
+                    x.circuit.has_changed()
+                }
+            };
+            if !changed {
                 converged = true;
                 break;
             }
@@ -310,9 +1075,9 @@ impl<T: Send + 'static + Block> Simulation<T> {
         let mut only_clock_waiters = true;
         for worker in self.workers.iter() {
             match &worker.kind {
-                TriggerType::Halt => {
+                TriggerType::Halt(failure) => {
                     return NextTime {
-                        halted: true,
+                        halted: Some(failure.as_ref().clone().locate(failure.time, worker.id)),
                         time: !0,
                         idx: !0,
                         clocks_only: false,
@@ -346,78 +1111,253 @@ impl<T: Send + 'static + Block> Simulation<T> {
             time: min_time,
             idx: min_idx,
             clocks_only: only_clock_waiters,
-            halted: false,
+            halted: None,
         }
     }
-    fn terminate(&mut self) {
+    // Stop the testbenches and finish the observers, and return the first error from the observers
+    fn terminate(&mut self) -> std::io::Result<()> {
+        let mut observed = Ok(());
+        for observer in &self.observers {
+            let finished = observer.borrow_mut().finish();
+            observed = observed.and(finished);
+        }
+        if let Some(fsm_coverage) = &self.fsm_coverage {
+            if self.fsm_report && !fsm_coverage.borrow().is_complete() {
+                eprint!("{}", fsm_coverage.borrow());
+            }
+        }
         self.workers.clear();
         for handle in std::mem::take(&mut self.testbenches) {
             let _ = handle.join().unwrap();
         }
+        observed
     }
-    pub fn run(&mut self, mut x: Box<T>, max_time: u64) -> Result<()> {
-        x.as_mut().connect_all();
-        check_all(x.as_mut())?;
-        // First initialize the workers.
-        for id in 0..self.workers.len() {
-            x = self.dispatch(id, x)?;
-        }
-        // Next run until we have no one else waiting
-        let mut halted = false;
-        while self.time < max_time {
-            let next = self.scan_workers(&x);
-            if next.time == !0 || next.clocks_only || next.halted {
-                halted = next.halted;
-                break;
+    pub fn run(&mut self, x: Box<T>, max_time: u64) -> Result<()> {
+        let result = self.run_loop(x, max_time, None);
+        let observed = self.terminate();
+        result?;
+        observed.map_err(|e| SimError::TraceFailed(e.to_string()))
+    }
+    /// Run the simulation until all of the testbenches have finished (so that only the
+    /// clocks are left running), and then capture a [Checkpoint] of the simulation.  This
+    /// is useful for long initialization sequences that are shared by many test cases.
+    pub fn run_to_checkpoint(&mut self, x: Box<T>, max_time: u64) -> Result<Checkpoint<T>> {
+        let result = self.run_loop(x, max_time, None);
+        let clocks = self
+            .workers
+            .iter()
+            .filter_map(|worker| match (&worker.link, &worker.kind) {
+                (WorkerLink::Clock(clock), TriggerType::Clock(wake)) => {
+                    Some(clock.resume_at(*wake))
+                }
+                _ => None,
+            })
+            .collect();
+        let observed = self.terminate();
+        let circuit = result?;
+        observed.map_err(|e| SimError::TraceFailed(e.to_string()))?;
+        Ok(Checkpoint {
+            circuit,
+            time: self.time,
+            clocks,
+            custom_logic: self.custom_logic.clone(),
+            scheduler: self.scheduler,
+            executor: self.executor,
+            trace_filter: self.trace_filter.clone(),
+            trace_trigger: self.trace_trigger.clone(),
+            seed: self.seed,
+            cdc_jitter: self.cdc_jitter,
+        })
+    }
+    /// Run the simulation, while recording the top level inputs and outputs of the circuit.
+    /// The recorded inputs are then replayed into the Verilog generated for the circuit
+    /// (using the given simulator, in a temporary directory named `prefix`), and the outputs
+    /// of the Verilog are compared with those of the Rust model at every time step.  The
+    /// first output that differs is reported as an [EquivalenceError::Diverged].
+    pub fn run_equivalence_check(
+        &mut self,
+        mut x: Box<T>,
+        max_time: u64,
+        prefix: &str,
+        simulator: VerilogSimulator,
+    ) -> std::result::Result<(), EquivalenceError> {
+        x.connect_all();
+        let verilog = generate_verilog(x.as_ref());
+        let recorder = self.add_observer(StimulusRecorder::default());
+        self.run(x, max_time)?;
+        let recorder = recorder.borrow();
+        recorder.check_equivalence(prefix, &verilog, simulator)
+    }
+    /// Run the simulation, and write the trace to a file.  The format of the
+    /// trace is picked based on the extension of the file name (see [TraceFormat::from_path]).
+    pub fn run_to_file(&mut self, x: Box<T>, max_time: u64, name: &str) -> Result<()> {
+        match TraceFormat::from_path(name) {
+            TraceFormat::VCD => {
+                let mut vcd = vec![];
+                let result = self.run_traced(x, max_time, &mut vcd);
+                std::fs::write(name, vcd).unwrap();
+                result
+            }
+            TraceFormat::FST => {
+                let mut tracer = FSTTracer::with_filter(name, self.trace_filter.clone());
+                self.run_with_tracer(x, max_time, &mut tracer)
             }
-            self.time = next.time;
-            x = self.dispatch(next.idx, x)?;
-        }
-        self.terminate();
-        if self.time >= max_time {
-            return Err(SimError::MaxTimeReached);
         }
-        if halted {
-            return Err(SimError::SimHalted);
+    }
+    /// Run the simulation, and write a VCD trace to the provided writer.
+    pub fn run_traced<W: Write>(&mut self, x: Box<T>, max_time: u64, trace: W) -> Result<()> {
+        let mut tracer = VCDTracer::with_filter(trace, self.trace_filter.clone());
+        self.run_with_tracer(x, max_time, &mut tracer)
+    }
+    /// Run the simulation, and write a trace in the given [TraceFormat] to the provided
+    /// writer.  FST files must be written to disk as they are built, so an FST trace is
+    /// staged in a temporary file and then copied to `trace`.  If you are writing to a file
+    /// anyway, [Simulation::run_to_file] avoids the extra copy.
+    pub fn run_traced_as<W: Write>(
+        &mut self,
+        x: Box<T>,
+        max_time: u64,
+        mut trace: W,
+        format: TraceFormat,
+    ) -> Result<()> {
+        match format {
+            TraceFormat::VCD => self.run_traced(x, max_time, trace),
+            TraceFormat::FST => {
+                static STAGE_COUNT: AtomicUsize = AtomicUsize::new(0);
+                let stage = std::env::temp_dir().join(format!(
+                    "rust_hdl_trace_{}_{}.fst",
+                    std::process::id(),
+                    STAGE_COUNT.fetch_add(1, Ordering::SeqCst)
+                ));
+                let mut tracer = FSTTracer::with_filter(&stage, self.trace_filter.clone());
+                let result = self.run_with_tracer(x, max_time, &mut tracer);
+                if let Ok(mut staged) = std::fs::File::open(&stage) {
+                    std::io::copy(&mut staged, &mut trace).unwrap();
+                }
+                let _ = std::fs::remove_file(&stage);
+                result
+            }
         }
-        Ok(())
     }
-    pub fn run_to_file(&mut self, x: Box<T>, max_time: u64, name: &str) -> Result<()> {
-        let mut vcd = vec![];
-        let result = self.run_traced(x, max_time, &mut vcd);
-        std::fs::write(name, vcd).unwrap();
-        result
+    /// Run the simulation, and pass the state of the circuit to the given [Tracer]
+    /// after every time step.  If the simulation succeeds, but the tracer (or an observer)
+    /// fails to write its output, the result is a [SimError::TraceFailed].
+    pub fn run_with_tracer(
+        &mut self,
+        x: Box<T>,
+        max_time: u64,
+        tracer: &mut dyn Tracer,
+    ) -> Result<()> {
+        let result = self.run_loop(x, max_time, Some(&mut *tracer));
+        let observed = self.terminate();
+        let traced = tracer.finish();
+        result?;
+        observed
+            .and(traced)
+            .map_err(|e| SimError::TraceFailed(e.to_string()))
     }
-    pub fn run_traced<W: Write>(&mut self, mut x: Box<T>, max_time: u64, trace: W) -> Result<()> {
+    fn run_loop(
+        &mut self,
+        mut x: Box<T>,
+        max_time: u64,
+        mut tracer: Option<&mut dyn Tracer>,
+    ) -> Result<Box<T>> {
+        install_panic_hook();
+        if let Some(seed) = self.seed {
+            seed_sim_rng(seed);
+        }
+        install_cdc_jitter(self.cdc_jitter);
+        install_four_state(self.four_state);
+        self.swept = false;
+        self.launch();
         x.as_mut().connect_all();
         check_all(x.as_mut())?;
-        let mut vcd = write_vcd_header(trace, x.as_ref());
+        if self.four_state {
+            make_unknown(x.as_mut());
+        }
+        // Observers see the state of the circuit before the testbenches initialize it
+        for observer in &self.observers {
+            observer.borrow_mut().start(x.as_ref());
+        }
+        self.observe(x.as_ref());
         // First initialize the workers.
         for id in 0..self.workers.len() {
             x = self.dispatch(id, x)?;
+            self.observe(x.as_ref());
+        }
+        let mut triggered = self.trace_triggered(x.as_ref());
+        if let Some(tracer) = tracer.as_mut() {
+            tracer.start(x.as_ref());
+            if triggered {
+                tracer.step(self.time, x.as_ref());
+            }
         }
-        vcd = write_vcd_dump(vcd, x.as_ref());
-        let mut halted = false;
+        let mut halted = None;
         // Next run until we have no one else waiting
         while self.time < max_time {
             let next = self.scan_workers(x.as_ref());
-            if next.time == !0 || next.clocks_only || next.halted {
+            if next.time == !0 || next.clocks_only || next.halted.is_some() {
                 halted = next.halted;
                 break;
             }
             self.time = next.time;
             x = self.dispatch(next.idx, x)?;
-            vcd.timestamp(next.time).unwrap();
-            vcd = write_vcd_change(vcd, x.as_ref());
+            self.observe(x.as_ref());
+            if let Some(tracer) = tracer.as_mut() {
+                triggered = triggered || self.trace_triggered(x.as_ref());
+                if triggered {
+                    tracer.step(next.time, x.as_ref());
+                }
+            }
         }
-        self.terminate();
         if self.time >= max_time {
             return Err(SimError::MaxTimeReached);
         }
-        if halted {
-            return Err(SimError::SimHalted);
+        if let Some(failure) = halted {
+            return Err(SimError::SimHalted(Box::new(failure)));
         }
-        Ok(())
+        Ok(x)
+    }
+}
+
+impl<T: Send + 'static + Block> Checkpoint<T> {
+    /// The simulation time (in picoseconds) at which the checkpoint was taken.
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+    /// The state of the circuit when the checkpoint was taken.
+    pub fn circuit(&self) -> &T {
+        &self.circuit
+    }
+    fn simulation(&self) -> Simulation<T> {
+        let mut sim = Simulation::new();
+        sim.time = self.time;
+        for clock in &self.clocks {
+            sim.add_clock_worker(clock.clone());
+        }
+        sim.custom_logic = self.custom_logic.clone();
+        sim.scheduler = self.scheduler;
+        sim.executor = self.executor;
+        sim.trace_filter = self.trace_filter.clone();
+        sim.trace_trigger = self.trace_trigger.clone();
+        sim.seed = self.seed;
+        sim.cdc_jitter = self.cdc_jitter;
+        sim
+    }
+    /// Restore the checkpoint.  Returns a new [Simulation] with the clocks (and any custom
+    /// logic) of the original, and the circuit to run it on.  Testbenches added to the new
+    /// simulation start at the time of the checkpoint.
+    pub fn restore(self) -> (Simulation<T>, Box<T>) {
+        let sim = self.simulation();
+        (sim, self.circuit)
+    }
+}
+
+impl<T: Send + 'static + Block + Clone> Checkpoint<T> {
+    /// Like [Checkpoint::restore], but leaves the checkpoint intact, so that it can be
+    /// used to start any number of simulations.  Requires the circuit to be [Clone].
+    pub fn fork(&self) -> (Simulation<T>, Box<T>) {
+        (self.simulation(), self.circuit.clone())
     }
 }
 
@@ -431,61 +1371,82 @@ pub mod sim_time {
 
 impl<T> Sim<T> {
     pub fn init(&self) -> Result<Box<T>> {
-        Ok(self.from_sim.recv()?.circuit)
+        match &self.link {
+            SimLink::Thread { from_sim, .. } => Ok(from_sim.recv()?.circuit),
+            SimLink::Task(task) => task.init.take().ok_or(SimError::SimTerminated),
+        }
     }
-    pub fn watch<S>(&mut self, check: S, x: Box<T>) -> Result<Box<T>>
-    where
-        S: Fn(&T) -> bool + Send + 'static,
-    {
-        self.to_sim.send(MessageOrPanic::Message(Message {
-            kind: TriggerType::Function(Box::new(check)),
-            circuit: x,
-        }))?;
-        let t = self.from_sim.recv()?;
+    // Hand the circuit back to the simulation, and wait for it to be returned.
+    fn exchange(&mut self, kind: TriggerType<T>, x: Box<T>) -> Result<Box<T>> {
+        let t = self.send(kind, x)?;
         if let TriggerType::Time(t0) = t.kind {
             self.time = t0;
         }
         Ok(t.circuit)
     }
-    pub fn clock(&mut self, delta: u64, x: Box<T>) -> Result<Box<T>> {
-        self.to_sim.send(MessageOrPanic::Message(Message {
-            kind: TriggerType::Clock(delta + self.time),
-            circuit: x,
-        }))?;
-        let t = self.from_sim.recv()?;
-        if let TriggerType::Time(t0) = t.kind {
-            self.time = t0;
+    fn send(&self, kind: TriggerType<T>, x: Box<T>) -> Result<Message<T>> {
+        let msg = Message { kind, circuit: x };
+        match &self.link {
+            SimLink::Thread { to_sim, from_sim } => {
+                to_sim.send(MessageOrPanic::Message(msg))?;
+                Ok(from_sim.recv()?)
+            }
+            SimLink::Task(task) => Ok(task.suspend(msg)),
         }
-        Ok(t.circuit)
     }
-    pub fn wait(&mut self, delta: u64, x: Box<T>) -> Result<Box<T>> {
-        self.to_sim.send(MessageOrPanic::Message(Message {
-            kind: TriggerType::Time(delta + self.time),
-            circuit: x,
-        }))?;
-        let t = self.from_sim.recv()?;
-        if let TriggerType::Time(t0) = t.kind {
-            self.time = t0;
+    // Hand the circuit back to the simulation for the last time.
+    fn finish(&self, kind: TriggerType<T>, x: Box<T>) -> Result<()> {
+        let msg = Message { kind, circuit: x };
+        match &self.link {
+            SimLink::Thread { to_sim, .. } => {
+                to_sim.send(MessageOrPanic::Message(msg))?;
+            }
+            SimLink::Task(task) => {
+                // The simulation will not resume this task again
+                task.suspend(msg);
+            }
         }
-        Ok(t.circuit)
+        Ok(())
+    }
+    pub fn watch<S>(&mut self, check: S, x: Box<T>) -> Result<Box<T>>
+    where
+        S: Fn(&T) -> bool + Send + 'static,
+    {
+        self.exchange(TriggerType::Function(Box::new(check)), x)
+    }
+    pub fn clock(&mut self, delta: u64, x: Box<T>) -> Result<Box<T>> {
+        self.exchange(TriggerType::Clock(delta + self.time), x)
+    }
+    pub fn wait(&mut self, delta: u64, x: Box<T>) -> Result<Box<T>> {
+        self.exchange(TriggerType::Time(delta + self.time), x)
+    }
+    /// Wait for a span of time, given as a [SimTime] or a [Duration](std::time::Duration).
+    pub fn wait_for<D: Into<SimTime>>(&mut self, delta: D, x: Box<T>) -> Result<Box<T>> {
+        self.wait(delta.into().ps(), x)
     }
     pub fn done(&self, x: Box<T>) -> Result<()> {
-        self.to_sim.send(MessageOrPanic::Message(Message {
-            kind: TriggerType::Never,
-            circuit: x,
-        }))?;
-        Ok(())
+        self.finish(TriggerType::Never, x)
     }
     pub fn halt(&self, x: Box<T>) -> Result<()> {
-        self.to_sim.send(MessageOrPanic::Message(Message {
-            kind: TriggerType::Halt,
-            circuit: x,
-        }))?;
-        Err(SimError::SimHalted)
+        self.halt_with(SimFailure::new("Testbench halted"), x)
+    }
+    /// Halt the simulation, and report the given failure (which is stamped with
+    /// the current simulation time).  This is used by [sim_assert!] and [sim_assert_eq!].
+    pub fn halt_with(&self, failure: SimFailure, x: Box<T>) -> Result<()> {
+        let failure = SimFailure {
+            time: self.time,
+            ..failure
+        };
+        self.finish(TriggerType::Halt(Box::new(failure.clone())), x)?;
+        Err(SimError::SimHalted(Box::new(failure)))
     }
     pub fn time(&self) -> u64 {
         self.time
     }
+    /// The current simulation time as a [SimTime]
+    pub fn now(&self) -> SimTime {
+        SimTime::from_ps(self.time)
+    }
 }
 
 #[macro_export]
@@ -533,26 +1494,36 @@ macro_rules! wait_clock_cycles {
 macro_rules! sim_assert {
     ($sim: ident, $test: expr, $circuit: ident) => {
         if !($test) {
-            println!("HALT {}", stringify!($test));
-            return $sim.halt($circuit);
+            return $sim.halt_with(
+                $crate::simulate::SimFailure::new(stringify!($test)).at(file!(), line!()),
+                $circuit,
+            );
         }
     };
 }
 
 #[macro_export]
 macro_rules! sim_assert_eq {
-    ($sim: ident, $lhs: expr, $rhs: expr, $circuit: ident) => {
-        if !($lhs == $rhs) {
-            println!(
-                "HALT {} != {},  {:?} != {:?}",
-                stringify!($lhs),
-                stringify!($rhs),
-                $lhs,
-                $rhs
-            );
-            return $sim.halt($circuit);
+    ($sim: ident, $lhs: expr, $rhs: expr, $circuit: ident) => {{
+        // Each side is evaluated once, and the failure is built before the
+        // circuit is handed back (the sides may borrow from it)
+        let failure = match (&$lhs, &$rhs) {
+            (lhs, rhs) if !(*lhs == *rhs) => Some(
+                $crate::simulate::SimFailure::new(format!(
+                    "{} != {}",
+                    stringify!($lhs),
+                    stringify!($rhs)
+                ))
+                .with_value(stringify!($lhs), lhs)
+                .with_value(stringify!($rhs), rhs)
+                .at(file!(), line!()),
+            ),
+            _ => None,
+        };
+        if let Some(failure) = failure {
+            return $sim.halt_with(failure, $circuit);
         }
-    };
+    }};
 }
 
 #[macro_export]
@@ -1,9 +1,11 @@
 use std::fmt::Debug;
 
+pub use num_bigint::BigUint;
+
 use crate::ast::VerilogLiteral;
 use crate::bits::{Bit, Bits};
 use crate::clock::Clock;
-use crate::signed::Signed;
+use crate::signed::{signed_cast, Signed};
 use crate::type_descriptor::{TypeDescriptor, TypeKind};
 
 #[derive(Clone, PartialEq, Debug)]
@@ -29,6 +31,22 @@ pub trait Synth: Default + Copy + PartialEq + Debug {
     fn descriptor() -> TypeDescriptor;
     fn vcd(self) -> VCDValue;
     fn verilog(self) -> VerilogLiteral;
+    /// Rebuild a value from its bit pattern (as it would appear in the generated Verilog).
+    /// Returns `None` if the pattern does not correspond to a valid value, or if the type
+    /// does not support being rebuilt from raw bits.
+    fn from_bits(_bits: &BigUint) -> Option<Self> {
+        None
+    }
+    /// A value with every bit unknown, for the types that can represent one (see
+    /// [FourState](crate::four_state::FourState)).  Returns `None` for two-state types.
+    fn unknown() -> Option<Self> {
+        None
+    }
+    /// A value with every bit undriven (`Z`), for the types that can represent one.
+    /// Returns `None` for two-state types.
+    fn undriven() -> Option<Self> {
+        None
+    }
 }
 
 impl<const N: usize> Synth for Bits<N> {
@@ -48,6 +66,10 @@ impl<const N: usize> Synth for Bits<N> {
     fn verilog(self) -> VerilogLiteral {
         self.into()
     }
+
+    fn from_bits(bits: &BigUint) -> Option<Self> {
+        (bits.bits() <= N as u64).then(|| bits.clone().into())
+    }
 }
 
 impl Synth for Bit {
@@ -71,6 +93,10 @@ impl Synth for Bit {
     fn verilog(self) -> VerilogLiteral {
         self.into()
     }
+
+    fn from_bits(bits: &BigUint) -> Option<Self> {
+        (bits.bits() <= 1).then(|| bits.bit(0))
+    }
 }
 
 impl Synth for Clock {
@@ -90,6 +116,10 @@ impl Synth for Clock {
     fn verilog(self) -> VerilogLiteral {
         self.clk.into()
     }
+
+    fn from_bits(bits: &BigUint) -> Option<Self> {
+        bool::from_bits(bits).map(|clk| Clock { clk })
+    }
 }
 
 impl<const N: usize> Synth for Signed<N> {
@@ -106,4 +136,7 @@ impl<const N: usize> Synth for Signed<N> {
     fn verilog(self) -> VerilogLiteral {
         self.inner().into()
     }
+    fn from_bits(bits: &BigUint) -> Option<Self> {
+        Bits::<N>::from_bits(bits).map(signed_cast)
+    }
 }
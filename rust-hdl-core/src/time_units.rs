@@ -0,0 +1,157 @@
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// A span of simulation time, with a resolution of one picosecond (the
+/// resolution of the simulator).  Use it in place of raw picosecond counts,
+/// as in
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// assert_eq!(SimTime::from_ns(10), SimTime::from_ps(10_000));
+/// assert_eq!(SimTime::from(std::time::Duration::from_micros(2)).ps(), 2_000_000);
+/// assert_eq!(Frequency::from_mhz(100.0).half_period(), SimTime::from_ns(5));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct SimTime(u64);
+
+impl SimTime {
+    pub const fn from_ps(ps: u64) -> Self {
+        Self(ps)
+    }
+    pub const fn from_ns(ns: u64) -> Self {
+        Self(ns * 1_000)
+    }
+    pub const fn from_us(us: u64) -> Self {
+        Self(us * 1_000_000)
+    }
+    pub const fn from_ms(ms: u64) -> Self {
+        Self(ms * 1_000_000_000)
+    }
+    /// The time in picoseconds (the units used by [Simulation](crate::simulate::Simulation))
+    pub const fn ps(&self) -> u64 {
+        self.0
+    }
+    /// The time in (fractional) nanoseconds
+    pub fn ns(&self) -> f64 {
+        self.0 as f64 / 1.0e3
+    }
+    /// The time in (fractional) microseconds
+    pub fn us(&self) -> f64 {
+        self.0 as f64 / 1.0e6
+    }
+}
+
+impl From<Duration> for SimTime {
+    fn from(x: Duration) -> Self {
+        Self(u64::try_from(x.as_nanos() * 1_000).unwrap_or(u64::MAX))
+    }
+}
+
+impl From<SimTime> for Duration {
+    fn from(x: SimTime) -> Self {
+        Duration::from_nanos(x.0 / 1_000)
+    }
+}
+
+impl From<SimTime> for u64 {
+    fn from(x: SimTime) -> Self {
+        x.0
+    }
+}
+
+impl std::ops::Add for SimTime {
+    type Output = SimTime;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl std::ops::Sub for SimTime {
+    type Output = SimTime;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl std::ops::Mul<u64> for SimTime {
+    type Output = SimTime;
+
+    fn mul(self, rhs: u64) -> Self::Output {
+        Self(self.0 * rhs)
+    }
+}
+
+impl std::ops::Div<u64> for SimTime {
+    type Output = SimTime;
+
+    fn div(self, rhs: u64) -> Self::Output {
+        Self(self.0 / rhs)
+    }
+}
+
+impl Display for SimTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0 >= 1_000_000 && self.0.is_multiple_of(1_000_000) {
+            write!(f, "{} us", self.0 / 1_000_000)
+        } else if self.0 >= 1_000 && self.0.is_multiple_of(1_000) {
+            write!(f, "{} ns", self.0 / 1_000)
+        } else {
+            write!(f, "{} ps", self.0)
+        }
+    }
+}
+
+/// A clock frequency.  The period of the clock is rounded to the nearest picosecond.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct Frequency(f64);
+
+impl Frequency {
+    pub fn from_hz(hz: f64) -> Self {
+        assert!(hz > 0.0, "Frequency must be positive");
+        Self(hz)
+    }
+    pub fn from_khz(khz: f64) -> Self {
+        Self::from_hz(khz * 1.0e3)
+    }
+    pub fn from_mhz(mhz: f64) -> Self {
+        Self::from_hz(mhz * 1.0e6)
+    }
+    /// The frequency in Hz (the units used by widgets like `Strobe`)
+    pub fn hz(&self) -> f64 {
+        self.0
+    }
+    /// The period of one clock cycle
+    pub fn period(&self) -> SimTime {
+        SimTime((1.0e12 / self.0).round() as u64)
+    }
+    /// The time the clock spends high (or low) with a 50% duty cycle.  This is
+    /// the interval to use with [Simulation::add_clock](crate::simulate::Simulation::add_clock).
+    pub fn half_period(&self) -> SimTime {
+        SimTime((0.5e12 / self.0).round() as u64)
+    }
+    /// The time the clock spends high and low for the given duty cycle (the fraction
+    /// of each period that the clock is high).
+    pub fn high_low(&self, duty_cycle: f64) -> (SimTime, SimTime) {
+        assert!(
+            duty_cycle > 0.0 && duty_cycle < 1.0,
+            "Duty cycle must be between 0 and 1"
+        );
+        let period = self.period();
+        let high = SimTime((period.0 as f64 * duty_cycle).round() as u64);
+        (high, period - high)
+    }
+}
+
+impl Display for Frequency {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.0 >= 1.0e6 {
+            write!(f, "{} MHz", self.0 / 1.0e6)
+        } else if self.0 >= 1.0e3 {
+            write!(f, "{} kHz", self.0 / 1.0e3)
+        } else {
+            write!(f, "{} Hz", self.0)
+        }
+    }
+}
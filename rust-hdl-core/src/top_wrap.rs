@@ -1,4 +1,10 @@
-use crate::{ast::Verilog, block::Block, logic::Logic, probe::Probe, timing::TimingInfo};
+use crate::{
+    ast::Verilog,
+    block::Block,
+    logic::Logic,
+    probe::{Probe, ProbeMut},
+    timing::TimingInfo,
+};
 
 pub struct TopWrap<U: Block> {
     pub uut: U,
@@ -26,9 +32,26 @@ impl<U: Block> Block for TopWrap<U> {
     fn has_changed(&self) -> bool {
         self.uut.has_changed()
     }
+    fn has_changed_ports(&self) -> bool {
+        self.uut.has_changed_interface()
+    }
+    fn has_changed_interface(&self) -> bool {
+        false
+    }
+    fn update_pending(&mut self) {
+        if self.uut.has_changed_ports() {
+            self.update();
+        }
+        self.uut.update_pending();
+    }
     fn accept(&self, name: &str, probe: &mut dyn Probe) {
         probe.visit_start_scope(name, self);
         self.uut.accept("uut", probe);
         probe.visit_end_scope(name, self);
     }
+    fn accept_mut(&mut self, name: &str, probe: &mut dyn ProbeMut) {
+        probe.visit_start_scope(name);
+        self.uut.accept_mut("uut", probe);
+        probe.visit_end_scope(name);
+    }
 }
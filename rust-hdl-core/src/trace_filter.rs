@@ -0,0 +1,124 @@
+use regex::Regex;
+
+/// A [TraceFilter] restricts a trace to a region of interest, both in the
+/// design hierarchy and in time.  Paths are written with `.` as the separator,
+/// starting with `uut` for the top level circuit, i.e., `uut.controller.state.q`.
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// let filter = TraceFilter {
+///     paths: vec!["uut.controller.*".into(), "uut.sdram.bank*".into()],
+///     max_depth: Some(3),
+///     start_time: 10 * sim_time::ONE_MICROSECOND,
+///     ..Default::default()
+/// };
+/// assert!(filter.includes("uut.controller.state.q", 2));
+/// assert!(!filter.includes("uut.fifo.full", 1));
+/// assert!(!filter.in_window(0));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraceFilter {
+    /// Globs over hierarchical paths.  `*` matches any sequence of characters, and `?`
+    /// matches a single character.  A signal is recorded if its path, or the path of
+    /// any module containing it, matches one of the globs.  If empty, all paths match.
+    pub paths: Vec<String>,
+    /// If set, only signals in modules at most this many levels below the top are recorded.
+    pub max_depth: Option<usize>,
+    /// Recording starts at this simulation time (in picoseconds).
+    pub start_time: u64,
+    /// If set, recording stops after this simulation time (in picoseconds).
+    pub stop_time: Option<u64>,
+}
+
+pub(crate) fn glob_to_regex(glob: &str) -> Regex {
+    let mut pattern = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).unwrap()
+}
+
+/// The compiled form of the path globs in a [TraceFilter].
+pub(crate) struct PathMatcher {
+    globs: Vec<Regex>,
+    max_depth: Option<usize>,
+}
+
+impl PathMatcher {
+    pub(crate) fn includes(&self, path: &str, depth: usize) -> bool {
+        if let Some(max_depth) = self.max_depth {
+            if depth > max_depth {
+                return false;
+            }
+        }
+        if self.globs.is_empty() {
+            return true;
+        }
+        let mut prefixes = path.match_indices('.').map(|(ndx, _)| &path[0..ndx]);
+        self.globs.iter().any(|x| x.is_match(path))
+            || prefixes.any(|prefix| self.globs.iter().any(|x| x.is_match(prefix)))
+    }
+}
+
+/// The scopes (modules and interfaces) that enclose the signal being written to a trace
+/// header.  A scope is only written when the first signal inside it is recorded, so that
+/// modules that are filtered out entirely do not leave empty scopes in the trace.
+pub(crate) struct ScopeStack<S> {
+    scopes: Vec<S>,
+    opened: usize,
+}
+
+impl<S> Default for ScopeStack<S> {
+    fn default() -> Self {
+        Self {
+            scopes: vec![],
+            opened: 0,
+        }
+    }
+}
+
+impl<S> ScopeStack<S> {
+    pub(crate) fn push(&mut self, scope: S) {
+        self.scopes.push(scope);
+    }
+    /// Write out any enclosing scopes that have not been written yet
+    pub(crate) fn open(&mut self, mut write: impl FnMut(&S)) {
+        for scope in &self.scopes[self.opened..] {
+            write(scope);
+        }
+        self.opened = self.scopes.len();
+    }
+    /// Leave the innermost scope.  Returns `true` if it was written (and so needs closing).
+    pub(crate) fn pop(&mut self) -> bool {
+        self.scopes.pop();
+        if self.opened > self.scopes.len() {
+            self.opened = self.scopes.len();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl TraceFilter {
+    pub(crate) fn matcher(&self) -> PathMatcher {
+        PathMatcher {
+            globs: self.paths.iter().map(|x| glob_to_regex(x)).collect(),
+            max_depth: self.max_depth,
+        }
+    }
+    /// Returns `true` if the signal at `path`, in a module `depth` levels below the
+    /// top, should be recorded.
+    pub fn includes(&self, path: &str, depth: usize) -> bool {
+        self.matcher().includes(path, depth)
+    }
+    /// Returns `true` if the simulation time `time` is inside the recording window.
+    pub fn in_window(&self, time: u64) -> bool {
+        time >= self.start_time && self.stop_time.map(|t| time <= t).unwrap_or(true)
+    }
+}
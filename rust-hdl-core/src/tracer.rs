@@ -0,0 +1,162 @@
+use crate::block::Block;
+use crate::fst_probe::{
+    write_fst_change, write_fst_header_filtered, FSTProbe, FST_FLUSH_THRESHOLD,
+};
+use crate::trace_filter::TraceFilter;
+use crate::vcd_probe::{write_vcd_change, write_vcd_dump, write_vcd_header_filtered, VCDProbe};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The waveform formats that a [Simulation](crate::simulate::Simulation) can write.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TraceFormat {
+    /// Value Change Dump - plain text, and readable by essentially every waveform viewer.
+    VCD,
+    /// GTKWave's compressed and seekable format.  Much smaller than VCD for long runs.
+    FST,
+}
+
+impl TraceFormat {
+    /// Pick a format based on the extension of a file name.  Files ending in `.fst`
+    /// are written as FST, and everything else is written as VCD.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> TraceFormat {
+        match path.as_ref().extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("fst") => TraceFormat::FST,
+            _ => TraceFormat::VCD,
+        }
+    }
+}
+
+/// A [Tracer] records the state of a circuit as a simulation progresses.
+pub trait Tracer {
+    /// Called once all of the testbenches have initialized the circuit.  The tracer
+    /// should record the structure of the circuit.
+    fn start(&mut self, uut: &dyn Block);
+    /// Called with the state of the circuit at simulation time `time`.  The first call
+    /// is made with the initial state of the circuit, unless tracing is held off by a
+    /// trigger (see [Simulation::set_trace_trigger](crate::simulate::Simulation::set_trace_trigger)).
+    fn step(&mut self, time: u64, uut: &dyn Block);
+    /// Called when the simulation is over, whether or not it succeeded.  Returns the first
+    /// error the tracer ran into while writing the trace, if any.
+    fn finish(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A [Tracer] that writes a VCD file to the given writer.
+pub struct VCDTracer<W: Write> {
+    writer: Option<W>,
+    vcd: Option<VCDProbe<W>>,
+    filter: TraceFilter,
+    dumped: bool,
+}
+
+impl<W: Write> VCDTracer<W> {
+    pub fn new(writer: W) -> VCDTracer<W> {
+        Self::with_filter(writer, TraceFilter::default())
+    }
+    /// Create a [VCDTracer] that only records the signals and time window selected by `filter`.
+    pub fn with_filter(writer: W, filter: TraceFilter) -> VCDTracer<W> {
+        Self {
+            writer: Some(writer),
+            vcd: None,
+            filter,
+            dumped: false,
+        }
+    }
+}
+
+impl<W: Write> Tracer for VCDTracer<W> {
+    fn start(&mut self, uut: &dyn Block) {
+        if let Some(writer) = self.writer.take() {
+            self.vcd = Some(write_vcd_header_filtered(writer, uut, &self.filter));
+        }
+    }
+
+    fn step(&mut self, time: u64, uut: &dyn Block) {
+        if !self.filter.in_window(time) {
+            return;
+        }
+        if let Some(mut vcd) = self.vcd.take() {
+            if self.dumped {
+                vcd.timestamp(time).unwrap();
+                self.vcd = Some(write_vcd_change(vcd, uut));
+            } else {
+                // The first record is a full dump of all of the signals
+                if time != 0 {
+                    vcd.timestamp(time).unwrap();
+                }
+                self.vcd = Some(write_vcd_dump(vcd, uut));
+                self.dumped = true;
+            }
+        }
+    }
+}
+
+/// A [Tracer] that writes an FST file to the given path.  If writing the file fails, the
+/// tracer stops, and the error is returned when the simulation finishes.
+pub struct FSTTracer {
+    path: PathBuf,
+    fst: Option<FSTProbe>,
+    filter: TraceFilter,
+    flush_threshold: usize,
+    error: Option<std::io::Error>,
+}
+
+impl FSTTracer {
+    pub fn new<P: AsRef<Path>>(path: P) -> FSTTracer {
+        Self::with_filter(path, TraceFilter::default())
+    }
+    /// Create an [FSTTracer] that only records the signals and time window selected by `filter`.
+    pub fn with_filter<P: AsRef<Path>>(path: P, filter: TraceFilter) -> FSTTracer {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            fst: None,
+            filter,
+            flush_threshold: FST_FLUSH_THRESHOLD,
+            error: None,
+        }
+    }
+    /// Write the value changes to the file whenever more than `bytes` of them are held in
+    /// memory (see [FST_FLUSH_THRESHOLD]).
+    pub fn with_flush_threshold(self, bytes: usize) -> FSTTracer {
+        Self {
+            flush_threshold: bytes,
+            ..self
+        }
+    }
+}
+
+impl Tracer for FSTTracer {
+    fn start(&mut self, uut: &dyn Block) {
+        match write_fst_header_filtered(&self.path, uut, &self.filter) {
+            Ok(mut fst) => {
+                fst.set_flush_threshold(self.flush_threshold);
+                self.fst = Some(fst);
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn step(&mut self, time: u64, uut: &dyn Block) {
+        if !self.filter.in_window(time) {
+            return;
+        }
+        if let Some(mut fst) = self.fst.take() {
+            match fst.timestamp(time).and_then(|_| write_fst_change(fst, uut)) {
+                Ok(fst) => self.fst = Some(fst),
+                Err(e) => self.error = Some(e),
+            }
+        }
+    }
+
+    fn finish(&mut self) -> std::io::Result<()> {
+        if let Some(fst) = self.fst.take() {
+            fst.finish()?;
+        }
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
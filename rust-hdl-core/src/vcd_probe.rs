@@ -1,7 +1,9 @@
 use crate::atom::Atom;
 use crate::block::Block;
+use crate::named_path::NamedPath;
 use crate::probe::Probe;
 use crate::synth::VCDValue;
+use crate::trace_filter::{PathMatcher, ScopeStack, TraceFilter};
 use crate::type_descriptor::TypeDescriptor;
 use crate::type_descriptor::TypeKind;
 use std::collections::HashMap;
@@ -33,7 +35,13 @@ impl<W: Write> VCDProbe<W> {
     }
 }
 
-struct VCDHeader<W: Write>(VCDProbe<W>);
+struct VCDHeader<W: Write> {
+    probe: VCDProbe<W>,
+    path: NamedPath,
+    depth: usize,
+    matcher: PathMatcher,
+    scopes: ScopeStack<String>,
+}
 
 fn register_signal<W: Write>(
     name: &str,
@@ -59,35 +67,75 @@ fn register_signal<W: Write>(
 
 impl<W: Write> Probe for VCDHeader<W> {
     fn visit_start_scope(&mut self, name: &str, _node: &dyn Block) {
-        self.0.vcd.add_module(name).unwrap();
+        self.scopes.push(name.into());
+        self.path.push(name);
+        self.depth += 1;
     }
 
     fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
-        self.0.vcd.add_module(name).unwrap();
+        self.scopes.push(name.into());
+        self.path.push(name);
     }
 
     fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
-        self.0.id_map.insert(
-            signal.id(),
-            register_signal(name, &signal.descriptor(), &mut self.0.vcd),
-        );
+        self.path.push(name);
+        if self
+            .matcher
+            .includes(&self.path.flat("."), self.depth.saturating_sub(1))
+        {
+            let vcd = &mut self.probe.vcd;
+            self.scopes.open(|x| vcd.add_module(x).unwrap());
+            self.probe.id_map.insert(
+                signal.id(),
+                register_signal(name, &signal.descriptor(), &mut self.probe.vcd),
+            );
+        }
+        self.path.pop();
     }
 
     fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
-        self.0.vcd.upscope().unwrap();
+        if self.scopes.pop() {
+            self.probe.vcd.upscope().unwrap();
+        }
+        self.path.pop();
     }
 
     fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
-        self.0.vcd.upscope().unwrap();
+        if self.scopes.pop() {
+            self.probe.vcd.upscope().unwrap();
+        }
+        self.path.pop();
+        self.depth -= 1;
     }
 }
 
 pub fn write_vcd_header<W: Write>(writer: W, uut: &dyn Block) -> VCDProbe<W> {
-    let mut visitor = VCDHeader(VCDProbe::new(writer));
-    visitor.0.vcd.timescale(1, vcd::TimescaleUnit::PS).unwrap();
+    write_vcd_header_filtered(writer, uut, &TraceFilter::default())
+}
+
+/// Write the VCD header for `uut`, but only include those signals that
+/// pass the path and depth checks of the [TraceFilter].  Signals that are
+/// not in the header are ignored by [write_vcd_change] and [write_vcd_dump].
+pub fn write_vcd_header_filtered<W: Write>(
+    writer: W,
+    uut: &dyn Block,
+    filter: &TraceFilter,
+) -> VCDProbe<W> {
+    let mut visitor = VCDHeader {
+        probe: VCDProbe::new(writer),
+        path: Default::default(),
+        depth: 0,
+        matcher: filter.matcher(),
+        scopes: Default::default(),
+    };
+    visitor
+        .probe
+        .vcd
+        .timescale(1, vcd::TimescaleUnit::PS)
+        .unwrap();
     uut.accept("uut", &mut visitor);
-    visitor.0.vcd.enddefinitions().unwrap();
-    visitor.0
+    visitor.probe.vcd.enddefinitions().unwrap();
+    visitor.probe
 }
 
 struct VCDChange<W: Write>(VCDProbe<W>);
@@ -0,0 +1,453 @@
+use crate::atom::AtomKind;
+use crate::bits::clog2;
+use crate::block::Block;
+use crate::coverage::enum_label_index;
+use crate::named_path::NamedPath;
+use crate::signal_handle::{visit_signals, visit_signals_mut};
+use crate::simulate::{Result, Sim, SimFailure};
+use crate::type_descriptor::{TypeDescriptor, TypeKind};
+use num_bigint::BigUint;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+use std::path::Path;
+use vcd::{Command, Header, ScopeItem, Value};
+
+#[derive(Debug)]
+pub enum ReplayError {
+    IOError(std::io::Error),
+    /// The file does not declare any signals
+    NoSignals,
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(x: std::io::Error) -> Self {
+        ReplayError::IOError(x)
+    }
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::IOError(e) => write!(f, "Unable to read the VCD file: {}", e),
+            ReplayError::NoSignals => write!(f, "The VCD file does not declare any signals"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ReplayValue {
+    Bits(BigUint),
+    // Enums are written to a VCD by the name of the variant
+    Label(String),
+}
+
+#[derive(Clone, Debug)]
+struct ReplaySignal {
+    path: String,
+    changes: Vec<(u64, ReplayValue)>,
+    // Set if any of the changes has an unknown or undriven bit
+    unknown: bool,
+}
+
+// Unknown and undriven bits are replayed as zeros
+fn value_bits(values: &[Value]) -> BigUint {
+    values.iter().fold(BigUint::default(), |acc, x| {
+        (acc << 1_u32) + BigUint::from((*x == Value::V1) as u32)
+    })
+}
+
+// A part of a signal in the circuit that a signal in the file can drive.  Composite signals
+// are written to a VCD one field at a time (as `cmd$mode`), so each field is a target of its
+// own, as well as the signal as a whole.
+#[derive(Clone, Debug)]
+struct Target {
+    // The name of the target, i.e., `uut.cmd$mode`
+    name: String,
+    // The path of the signal that holds it, i.e., `uut.cmd`
+    path: String,
+    offset: usize,
+    width: usize,
+    // The variants, if the target is an enum
+    labels: Vec<String>,
+}
+
+impl Target {
+    // Replace the bits of the target in `bits` with `value`
+    fn apply(&self, bits: &BigUint, value: &ReplayValue) -> Option<BigUint> {
+        let one = BigUint::from(1_u32);
+        let value = match value {
+            ReplayValue::Bits(x) => x & ((&one << self.width) - 1_u32),
+            ReplayValue::Label(label) => enum_label_index(&self.labels, label)?.into(),
+        };
+        let above = (bits >> (self.offset + self.width)) << (self.offset + self.width);
+        let below = bits & ((&one << self.offset) - 1_u32);
+        Some(above | below | (value << self.offset))
+    }
+}
+
+// Collect the targets for a signal (and the fields within it), and return its width
+fn collect_targets(
+    name: &str,
+    path: &str,
+    descriptor: &TypeDescriptor,
+    offset: usize,
+    targets: &mut Vec<Target>,
+) -> usize {
+    let (width, labels) = match &descriptor.kind {
+        TypeKind::Bits(width) | TypeKind::Signed(width) => (*width, vec![]),
+        TypeKind::Enum(labels) => (clog2(labels.len()), labels.clone()),
+        TypeKind::Composite(fields) => {
+            let mut width = 0;
+            for field in fields {
+                width += collect_targets(
+                    &format!("{}${}", name, field.fieldname),
+                    path,
+                    &field.kind,
+                    offset + width,
+                    targets,
+                );
+            }
+            (width, vec![])
+        }
+    };
+    targets.push(Target {
+        name: name.to_string(),
+        path: path.to_string(),
+        offset,
+        width,
+        labels,
+    });
+    width
+}
+
+fn collect_vars(
+    items: &[ScopeItem],
+    path: &mut NamedPath,
+    signals: &mut Vec<ReplaySignal>,
+    codes: &mut HashMap<vcd::IdCode, Vec<usize>>,
+) {
+    for item in items {
+        match item {
+            ScopeItem::Scope(scope) => {
+                path.push(&scope.identifier);
+                collect_vars(&scope.children, path, signals, codes);
+                path.pop();
+            }
+            ScopeItem::Var(var) => {
+                path.push(&var.reference);
+                codes.entry(var.code).or_default().push(signals.len());
+                signals.push(ReplaySignal {
+                    path: path.flat("."),
+                    changes: vec![],
+                    unknown: false,
+                });
+                path.pop();
+            }
+        }
+    }
+}
+
+// The first top level scope of the file
+fn first_scope(header: &Header) -> Option<String> {
+    header.items.iter().find_map(|x| match x {
+        ScopeItem::Scope(scope) => Some(scope.identifier.clone()),
+        _ => None,
+    })
+}
+
+/// A [VCDReplay] drives the inputs of a circuit from the value changes recorded in a VCD
+/// file, such as a capture from a logic analyzer, or the trace of an earlier simulation.
+/// Signals in the file are matched to the top level inputs of the circuit by their
+/// hierarchical name, with the top scope of the file standing in for the circuit itself
+/// (i.e., `top.clock` in the file drives the `clock` input).  This is the same naming used
+/// for the traces written by [Simulation](crate::simulate::Simulation), so a trace can be
+/// replayed as is, including the fields of struct valued inputs (written as `cmd$mode`).
+/// Signals with other names can be mapped with [VCDReplay::rename], which can also drive
+/// signals below the top level.  A signal in the file that does not name a signal of the
+/// circuit (see [VCDReplay::unmatched]) stops the replay, so that a typo is not mistaken for
+/// a run without stimulus.  Signals that are not meant to drive the circuit can be left out
+/// with [VCDReplay::ignore].  Timestamps are converted to picoseconds using the timescale of
+/// the file, and unknown (`x`) or undriven (`z`) bits are replayed as `0`, with a warning
+/// (see [VCDReplay::unknowns]).
+///
+/// ```rust
+/// # use rust_hdl_core::prelude::*;
+/// let vcd = "$timescale 1 ns $end
+/// $scope module top $end
+/// $var wire 1 ! enable $end
+/// $upscope $end
+/// $enddefinitions $end
+/// #0
+/// 0!
+/// #10
+/// 1!
+/// ";
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Foo {
+///    pub enable: Signal<In, Bit>,
+///    pub active: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Foo {
+///   #[hdl_gen]
+///   fn update(&mut self) {
+///      self.active.next = self.enable.val();
+///   }
+/// }
+///
+/// let mut sim = Simulation::new();
+/// sim.add_vcd_replay(VCDReplay::parse(vcd.as_bytes()).unwrap());
+/// sim.add_testbench(|mut ep: Sim<Foo>| {
+///     let mut x = ep.init()?;
+///     x = ep.wait(10_001, x)?;
+///     sim_assert!(ep, x.active.val(), x);
+///     ep.done(x)
+/// });
+/// let mut uut = Foo::default();
+/// uut.enable.connect();
+/// sim.run(Box::new(uut), 20_000).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct VCDReplay {
+    signals: Vec<ReplaySignal>,
+    root: Option<String>,
+    renames: Vec<(String, String)>,
+    ignored: Vec<String>,
+}
+
+impl VCDReplay {
+    /// Read the VCD file at `path`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> std::result::Result<Self, ReplayError> {
+        Self::parse(std::fs::File::open(path)?)
+    }
+    /// Read a VCD file from `reader`
+    pub fn parse<R: Read>(reader: R) -> std::result::Result<Self, ReplayError> {
+        let mut parser = vcd::Parser::new(reader);
+        let header = parser.parse_header()?;
+        let mut signals = vec![];
+        let mut codes = HashMap::new();
+        collect_vars(
+            &header.items,
+            &mut NamedPath::default(),
+            &mut signals,
+            &mut codes,
+        );
+        if signals.is_empty() {
+            return Err(ReplayError::NoSignals);
+        }
+        let (count, unit) = header.timescale.unwrap_or((1, vcd::TimescaleUnit::PS));
+        let to_ps = |t: u64| {
+            (t as u128 * count as u128 * 1_000_000_000_000 / unit.divisor() as u128) as u64
+        };
+        let mut time = 0;
+        let unknown = |values: &[Value]| values.iter().any(|x| !matches!(x, Value::V0 | Value::V1));
+        for command in parser {
+            let (code, value, undefined) = match command? {
+                Command::Timestamp(t) => {
+                    time = to_ps(t);
+                    continue;
+                }
+                Command::ChangeScalar(code, value) => (
+                    code,
+                    ReplayValue::Bits(value_bits(&[value])),
+                    unknown(&[value]),
+                ),
+                Command::ChangeVector(code, values) => (
+                    code,
+                    ReplayValue::Bits(value_bits(&values)),
+                    unknown(&values),
+                ),
+                Command::ChangeString(code, label) => (code, ReplayValue::Label(label), false),
+                _ => continue,
+            };
+            for ndx in codes.get(&code).into_iter().flatten() {
+                signals[*ndx].changes.push((time, value.clone()));
+                signals[*ndx].unknown |= undefined;
+            }
+        }
+        Ok(Self {
+            root: first_scope(&header),
+            signals,
+            renames: vec![],
+            ignored: vec![],
+        })
+    }
+    /// Use the scope named `scope` (a path, like `top.fpga`) of the file as the top of the circuit,
+    /// in place of the first top level scope.
+    pub fn root(self, scope: &str) -> Self {
+        Self {
+            root: Some(scope.to_string()),
+            ..self
+        }
+    }
+    /// Drive the signal at `path` in the circuit (which starts with `uut`, as in `uut.fifo.data_in`)
+    /// from the signal `signal` (its full path in the file).  The target does not have to be a top
+    /// level input.
+    pub fn rename(mut self, signal: &str, path: &str) -> Self {
+        self.renames.push((signal.to_string(), path.to_string()));
+        self
+    }
+    /// Do not drive the circuit from the signal `signal` (its full path in the file), and do not
+    /// report it as unmatched.
+    pub fn ignore(mut self, signal: &str) -> Self {
+        self.ignored.push(signal.to_string());
+        self
+    }
+    /// The full paths of the signals in the file
+    pub fn signals(&self) -> Vec<String> {
+        self.signals.iter().map(|x| x.path.clone()).collect()
+    }
+    /// The full paths of the signals in the file that have unknown (`x`) or undriven (`z`)
+    /// bits, which are replayed as `0`.
+    pub fn unknowns(&self) -> Vec<String> {
+        self.signals
+            .iter()
+            .filter(|x| x.unknown)
+            .map(|x| x.path.clone())
+            .collect()
+    }
+    // The path in the circuit that a signal in the file would drive by default
+    fn default_target(&self, path: &str) -> Option<String> {
+        let root = self.root.as_deref()?;
+        path.strip_prefix(root)
+            .and_then(|x| x.strip_prefix('.'))
+            .map(|x| format!("uut.{}", x))
+    }
+    // Pair each signal of the file with the target in the circuit it drives (if any), and
+    // whether that target exists at all
+    fn targets(&self, uut: &dyn Block) -> Vec<(&ReplaySignal, Option<Target>, bool)> {
+        let mut all = HashMap::new();
+        let mut inputs = HashSet::new();
+        visit_signals(uut, |path, depth, signal| {
+            let mut targets = vec![];
+            collect_targets(path, path, &signal.descriptor(), 0, &mut targets);
+            for target in targets {
+                if depth == 1 && signal.kind() == AtomKind::InputParameter {
+                    inputs.insert(target.name.clone());
+                }
+                all.insert(target.name.clone(), target);
+            }
+        });
+        self.signals
+            .iter()
+            .filter(|signal| !self.ignored.contains(&signal.path))
+            .map(|signal| {
+                let renamed = self
+                    .renames
+                    .iter()
+                    .find(|x| x.0 == signal.path)
+                    .map(|x| x.1.clone());
+                match renamed {
+                    Some(target) => {
+                        let target = all.get(&target).cloned();
+                        let exists = target.is_some();
+                        (signal, target, exists)
+                    }
+                    None => {
+                        let name = self.default_target(&signal.path);
+                        let exists = name.iter().any(|x| all.contains_key(x));
+                        let target = name
+                            .filter(|x| inputs.contains(x))
+                            .and_then(|x| all.get(&x).cloned());
+                        (signal, target, exists)
+                    }
+                }
+            })
+            .collect()
+    }
+    /// The signals of the file that drive the circuit, as pairs of the path in the file and
+    /// the path in the circuit (with the field, for a part of a struct valued signal, as in
+    /// `uut.cmd$mode`).
+    pub fn matches(&self, uut: &dyn Block) -> Vec<(String, String)> {
+        self.targets(uut)
+            .into_iter()
+            .filter_map(|(signal, target, _)| Some((signal.path.clone(), target?.name)))
+            .collect()
+    }
+    /// The signals of the file that do not name a signal in the circuit (after renaming),
+    /// and are not ignored.  These are usually typos, or signals that need a
+    /// [VCDReplay::rename].  Signals that name an internal signal or an output of the circuit
+    /// are not driven, but are not reported either, so that a full trace can be replayed.
+    pub fn unmatched(&self, uut: &dyn Block) -> Vec<String> {
+        self.targets(uut)
+            .into_iter()
+            .filter(|x| !x.2)
+            .map(|x| x.0.path.clone())
+            .collect()
+    }
+    // The changes to apply to the circuit, grouped by time
+    fn schedule(&self, uut: &dyn Block) -> BTreeMap<u64, Vec<(Target, ReplayValue)>> {
+        let mut schedule: BTreeMap<u64, Vec<(Target, ReplayValue)>> = BTreeMap::new();
+        for (signal, target, _) in self.targets(uut) {
+            let target = match target {
+                Some(target) => target,
+                None => continue,
+            };
+            for (time, value) in &signal.changes {
+                schedule
+                    .entry(*time)
+                    .or_default()
+                    .push((target.clone(), value.clone()));
+            }
+        }
+        schedule
+    }
+    /// Drive the circuit from the file, as a testbench (see
+    /// [Simulation::add_vcd_replay](crate::simulate::Simulation::add_vcd_replay)).  Fails if
+    /// any of the signals in the file do not match the circuit (see [VCDReplay::unmatched]), or
+    /// none of them drive it.
+    pub fn drive<T: Block>(&self, mut ep: Sim<T>) -> Result<()> {
+        let mut x = ep.init()?;
+        let unmatched = self.unmatched(x.as_ref());
+        if !unmatched.is_empty() {
+            return ep.halt_with(
+                SimFailure::new(format!(
+                    "Signals in the VCD file do not match the circuit: {}",
+                    unmatched.join(", ")
+                )),
+                x,
+            );
+        }
+        let unknowns = self.unknowns();
+        if !unknowns.is_empty() {
+            eprintln!(
+                "Warning: unknown or undriven bits are replayed as 0 for {}",
+                unknowns.join(", ")
+            );
+        }
+        let schedule = self.schedule(x.as_ref());
+        if schedule.is_empty() {
+            return ep.halt_with(
+                SimFailure::new("No signal in the VCD file matches an input of the circuit"),
+                x,
+            );
+        }
+        for (time, changes) in schedule {
+            if time > ep.time() {
+                x = ep.wait(time - ep.time(), x)?;
+            }
+            let mut by_path: HashMap<String, Vec<(Target, ReplayValue)>> = HashMap::new();
+            for (target, value) in changes {
+                by_path
+                    .entry(target.path.clone())
+                    .or_default()
+                    .push((target, value));
+            }
+            visit_signals_mut(x.as_mut(), |path, _, signal| {
+                if let Some(changes) = by_path.get(path) {
+                    let bits = changes
+                        .iter()
+                        .try_fold(signal.next_bits(), |bits, (target, value)| {
+                            target.apply(&bits, value)
+                        });
+                    if let Some(bits) = bits {
+                        signal.set_next_bits(&bits);
+                    }
+                }
+            });
+        }
+        ep.done(x)
+    }
+}
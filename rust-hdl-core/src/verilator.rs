@@ -0,0 +1,449 @@
+use crate::atom::{Atom, AtomKind, AtomMut};
+use crate::block::Block;
+use crate::code_writer::CodeWriter;
+use crate::module_defines::generate_verilog;
+use crate::named_path::NamedPath;
+use crate::probe::{Probe, ProbeMut};
+use crate::simulate::SimBackend;
+use num_bigint::BigUint;
+use std::env::temp_dir;
+use std::ffi::c_void;
+use std::fs::{create_dir_all, remove_dir_all, File};
+use std::io::{Error, Write};
+use std::process::Command;
+
+#[derive(Debug)]
+pub enum VerilatorError {
+    VerilatorFailed { stdout: String, stderr: String },
+    UnsupportedPort(Vec<String>),
+    LoadFailed(String),
+    IOError(std::io::Error),
+}
+
+impl From<std::io::Error> for VerilatorError {
+    fn from(x: Error) -> Self {
+        VerilatorError::IOError(x)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Port {
+    pub(crate) name: String,
+    pub(crate) bits: usize,
+    pub(crate) kind: AtomKind,
+}
+
+impl Port {
+    fn words(&self) -> usize {
+        self.bits.div_ceil(32)
+    }
+    // Verilator escapes the `$` used to flatten interfaces in the port names
+    fn cpp_name(&self) -> String {
+        self.name.replace('$', "__024")
+    }
+}
+
+// Tracks the path to an atom, so that the top level ports of the circuit can be
+// found (in a consistent order) by both the immutable and mutable visitors.
+#[derive(Default)]
+struct PortPath {
+    depth: usize,
+    namespace: NamedPath,
+}
+
+impl PortPath {
+    fn port_name(&self, name: &str, kind: AtomKind) -> Option<String> {
+        if self.depth != 1 || !kind.is_parameter() {
+            return None;
+        }
+        if self.namespace.is_empty() {
+            Some(name.to_string())
+        } else {
+            Some(format!("{}${}", self.namespace.flat("$"), name))
+        }
+    }
+}
+
+#[derive(Default)]
+struct PortList {
+    path: PortPath,
+    ports: Vec<Port>,
+    values: Vec<BigUint>,
+}
+
+impl Probe for PortList {
+    fn visit_start_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str, _node: &dyn Block) {
+        self.path.namespace.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &dyn Atom) {
+        if let Some(name) = self.path.port_name(name, signal.kind()) {
+            self.ports.push(Port {
+                name,
+                bits: signal.bits(),
+                kind: signal.kind(),
+            });
+            self.values.push(signal.verilog().as_biguint());
+        }
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.namespace.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str, _node: &dyn Block) {
+        self.path.depth -= 1;
+    }
+}
+
+pub(crate) fn top_level_ports(uut: &dyn Block) -> Vec<Port> {
+    let mut list = PortList::default();
+    uut.accept("top", &mut list);
+    list.ports
+}
+
+pub(crate) fn top_level_values(uut: &dyn Block) -> Vec<BigUint> {
+    let mut list = PortList::default();
+    uut.accept("top", &mut list);
+    list.values
+}
+
+fn write_harness(ports: &[Port]) -> String {
+    let mut io = CodeWriter::default();
+    io.writeln("#include <cstdint>");
+    io.writeln("#include \"verilated.h\"");
+    io.writeln("#include \"Vtop.h\"");
+    io.add_line("");
+    io.writeln("struct Cosim {");
+    io.push();
+    io.writeln("VerilatedContext *context;");
+    io.writeln("Vtop *top;");
+    io.pop();
+    io.writeln("};");
+    io.add_line("");
+    io.writeln("extern \"C\" {");
+    io.writeln("void *rust_hdl_cosim_new() {");
+    io.push();
+    io.writeln("Cosim *cosim = new Cosim;");
+    io.writeln("cosim->context = new VerilatedContext;");
+    io.writeln("cosim->top = new Vtop{cosim->context};");
+    io.writeln("return cosim;");
+    io.pop();
+    io.writeln("}");
+    io.writeln("void rust_hdl_cosim_delete(void *handle) {");
+    io.push();
+    io.writeln("Cosim *cosim = (Cosim *) handle;");
+    io.writeln("cosim->top->final();");
+    io.writeln("delete cosim->top;");
+    io.writeln("delete cosim->context;");
+    io.writeln("delete cosim;");
+    io.pop();
+    io.writeln("}");
+    io.writeln("void rust_hdl_cosim_eval(void *handle) {");
+    io.push();
+    io.writeln("((Cosim *) handle)->top->eval();");
+    io.pop();
+    io.writeln("}");
+    io.writeln("void rust_hdl_cosim_set(void *handle, uint32_t port, const uint32_t *words) {");
+    io.push();
+    io.writeln("Vtop *top = ((Cosim *) handle)->top;");
+    io.writeln("switch (port) {");
+    for (index, port) in ports.iter().enumerate() {
+        if port.kind != AtomKind::InputParameter {
+            continue;
+        }
+        let name = port.cpp_name();
+        match port.words() {
+            1 => io.writeln(format!("case {index}: top->{name} = words[0]; break;")),
+            2 => io.writeln(format!(
+                "case {index}: top->{name} = (uint64_t) words[0] | ((uint64_t) words[1] << 32); break;"
+            )),
+            n => io.writeln(format!(
+                "case {index}: for (int i = 0; i < {n}; i++) top->{name}[i] = words[i]; break;"
+            )),
+        }
+    }
+    io.writeln("}");
+    io.pop();
+    io.writeln("}");
+    io.writeln("void rust_hdl_cosim_get(void *handle, uint32_t port, uint32_t *words) {");
+    io.push();
+    io.writeln("Vtop *top = ((Cosim *) handle)->top;");
+    io.writeln("switch (port) {");
+    for (index, port) in ports.iter().enumerate() {
+        let name = port.cpp_name();
+        match port.words() {
+            1 => io.writeln(format!("case {index}: words[0] = top->{name}; break;")),
+            2 => io.writeln(format!(
+                "case {index}: words[0] = (uint32_t) top->{name}; words[1] = (uint32_t) (top->{name} >> 32); break;"
+            )),
+            n => io.writeln(format!(
+                "case {index}: for (int i = 0; i < {n}; i++) words[i] = top->{name}[i]; break;"
+            )),
+        }
+    }
+    io.writeln("}");
+    io.pop();
+    io.writeln("}");
+    io.writeln("}");
+    io.to_string()
+}
+
+/// Generate the C++ harness used to drive the Verilated version of `uut`.  The
+/// harness exposes the model through a small C interface, in which the top level
+/// ports are numbered in the order in which they are visited by a [Probe].
+pub fn verilator_harness<U: Block>(uut: &U) -> String {
+    write_harness(&top_level_ports(uut))
+}
+
+type NewFn = unsafe extern "C" fn() -> *mut c_void;
+type DeleteFn = unsafe extern "C" fn(*mut c_void);
+type EvalFn = unsafe extern "C" fn(*mut c_void);
+type SetFn = unsafe extern "C" fn(*mut c_void, u32, *const u32);
+type GetFn = unsafe extern "C" fn(*mut c_void, u32, *mut u32);
+
+/// A [VerilatorModel] is a [SimBackend] that replaces the Rust model of a circuit
+/// with the Verilog generated for it (by [generate_verilog]), compiled by Verilator.
+/// The testbenches are unchanged - the top level inputs of the circuit are fed to
+/// the Verilated model, and its outputs are written back into the top level outputs
+/// of the circuit.  Only the top level signals are updated - the internal state of
+/// the circuit is not.  This lets you check that the Verilog you synthesize behaves
+/// like the circuit you simulate.
+///
+/// The model must be used with the same type of circuit it was built from.
+///
+/// ```rust,no_run
+/// # use rust_hdl_core::prelude::*;
+/// # use rust_hdl_core::verilator::VerilatorModel;
+///
+/// #[derive(LogicBlock, Default)]
+/// struct Inverter {
+///     pub sig_in: Signal<In, Bit>,
+///     pub sig_out: Signal<Out, Bit>,
+/// }
+///
+/// impl Logic for Inverter {
+///     #[hdl_gen]
+///     fn update(&mut self) {
+///         self.sig_out.next = !self.sig_in.val();
+///     }
+/// }
+///
+/// let mut uut = Inverter::default();
+/// uut.connect_all();
+/// let mut sim = Simulation::new();
+/// sim.set_backend(VerilatorModel::new("inverter", &uut).unwrap());
+/// sim.add_testbench(|mut ep: Sim<Inverter>| {
+///     let mut x = ep.init()?;
+///     x.sig_in.next = true;
+///     x = ep.wait(1, x)?;
+///     sim_assert!(ep, !x.sig_out.val(), x);
+///     ep.done(x)
+/// });
+/// sim.run(Box::new(uut), 100).unwrap();
+/// ```
+pub struct VerilatorModel {
+    ports: Vec<Port>,
+    handle: *mut c_void,
+    delete: DeleteFn,
+    eval: EvalFn,
+    set: SetFn,
+    get: GetFn,
+    // Must outlive the handle and function pointers above
+    _library: libloading::Library,
+}
+
+impl VerilatorModel {
+    /// Generate the Verilog for `uut`, Verilate it (in a temporary directory named
+    /// `prefix`), and load the resulting model.  Requires `verilator` (and a C++
+    /// compiler) to be in the path.
+    pub fn new<U: Block>(prefix: &str, uut: &U) -> Result<VerilatorModel, VerilatorError> {
+        Self::build(prefix, top_level_ports(uut), &generate_verilog(uut))
+    }
+
+    pub(crate) fn build(
+        prefix: &str,
+        ports: Vec<Port>,
+        verilog: &str,
+    ) -> Result<VerilatorModel, VerilatorError> {
+        let inouts = ports
+            .iter()
+            .filter(|x| x.kind == AtomKind::InOutParameter)
+            .map(|x| x.name.clone())
+            .collect::<Vec<_>>();
+        if !inouts.is_empty() {
+            return Err(VerilatorError::UnsupportedPort(inouts));
+        }
+        let dir = temp_dir().as_path().join(prefix);
+        let _ = remove_dir_all(&dir);
+        create_dir_all(&dir)?;
+        let mut v_file = File::create(dir.join("top.v"))?;
+        write!(v_file, "{}", verilog)?;
+        let mut cpp_file = File::create(dir.join("harness.cpp"))?;
+        write!(cpp_file, "{}", write_harness(&ports))?;
+        let library = dir.join("libcosim.so");
+        let output = Command::new("verilator")
+            .current_dir(&dir)
+            .args([
+                "--cc",
+                "--exe",
+                "--build",
+                "-Wno-fatal",
+                "--top-module",
+                "top",
+                "-CFLAGS",
+                "-fPIC",
+                "-LDFLAGS",
+                "-shared",
+                "-o",
+            ])
+            .arg(&library)
+            .args(["top.v", "harness.cpp"])
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        {
+            let mut debug = File::create(dir.join("verilator.log"))?;
+            write!(debug, "{}", stdout)?;
+            write!(debug, "{}", stderr)?;
+        }
+        if !output.status.success() {
+            return Err(VerilatorError::VerilatorFailed { stdout, stderr });
+        }
+        Self::load(ports, &library)
+    }
+
+    fn load(ports: Vec<Port>, path: &std::path::Path) -> Result<VerilatorModel, VerilatorError> {
+        let failed = |e: libloading::Error| VerilatorError::LoadFailed(e.to_string());
+        // Safety - the library is the harness we just built, and the symbols
+        // have the signatures written by [write_harness].
+        unsafe {
+            let library = libloading::Library::new(path).map_err(failed)?;
+            let new = *library
+                .get::<NewFn>(b"rust_hdl_cosim_new")
+                .map_err(failed)?;
+            let delete = *library
+                .get::<DeleteFn>(b"rust_hdl_cosim_delete")
+                .map_err(failed)?;
+            let eval = *library
+                .get::<EvalFn>(b"rust_hdl_cosim_eval")
+                .map_err(failed)?;
+            let set = *library
+                .get::<SetFn>(b"rust_hdl_cosim_set")
+                .map_err(failed)?;
+            let get = *library
+                .get::<GetFn>(b"rust_hdl_cosim_get")
+                .map_err(failed)?;
+            Ok(VerilatorModel {
+                ports,
+                handle: new(),
+                delete,
+                eval,
+                set,
+                get,
+                _library: library,
+            })
+        }
+    }
+
+    pub(crate) fn set_port(&mut self, index: usize, bits: &BigUint) {
+        let mut words = bits.to_u32_digits();
+        words.resize(self.ports[index].words(), 0);
+        unsafe { (self.set)(self.handle, index as u32, words.as_ptr()) }
+    }
+
+    pub(crate) fn eval(&mut self) {
+        unsafe { (self.eval)(self.handle) }
+    }
+
+    pub(crate) fn get_port(&mut self, index: usize) -> BigUint {
+        let port = &self.ports[index];
+        let mut words = vec![0_u32; port.words()];
+        unsafe { (self.get)(self.handle, index as u32, words.as_mut_ptr()) }
+        let mask = (BigUint::from(1_u32) << port.bits) - 1_u32;
+        BigUint::from_slice(&words) & mask
+    }
+}
+
+impl Drop for VerilatorModel {
+    fn drop(&mut self) {
+        unsafe { (self.delete)(self.handle) }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Phase {
+    Drive,
+    Sample,
+}
+
+struct PortExchange<'a> {
+    model: &'a mut VerilatorModel,
+    phase: Phase,
+    path: PortPath,
+    index: usize,
+    changed: bool,
+}
+
+impl<'a> ProbeMut for PortExchange<'a> {
+    fn visit_start_scope(&mut self, _name: &str) {
+        self.path.depth += 1;
+    }
+
+    fn visit_start_namespace(&mut self, name: &str) {
+        self.path.namespace.push(name);
+    }
+
+    fn visit_atom(&mut self, name: &str, signal: &mut dyn AtomMut) {
+        if self.path.port_name(name, signal.kind()).is_none() {
+            return;
+        }
+        let index = self.index;
+        self.index += 1;
+        match (self.phase, signal.kind()) {
+            (Phase::Drive, AtomKind::InputParameter) => {
+                let bits = signal.next_bits();
+                self.changed |= signal.set_bits(&bits);
+                self.model.set_port(index, &bits);
+            }
+            (Phase::Sample, AtomKind::OutputParameter | AtomKind::OutputPassthrough) => {
+                let bits = self.model.get_port(index);
+                self.changed |= signal.set_bits(&bits);
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_end_namespace(&mut self, _name: &str) {
+        self.path.namespace.pop();
+    }
+
+    fn visit_end_scope(&mut self, _name: &str) {
+        self.path.depth -= 1;
+    }
+}
+
+impl<T: Block> SimBackend<T> for VerilatorModel {
+    fn evaluate(&mut self, x: &mut T) -> bool {
+        let mut changed = false;
+        for phase in [Phase::Drive, Phase::Sample] {
+            if phase == Phase::Sample {
+                self.eval();
+            }
+            let mut exchange = PortExchange {
+                model: self,
+                phase,
+                path: Default::default(),
+                index: 0,
+                changed: false,
+            };
+            x.accept_mut("top", &mut exchange);
+            changed |= exchange.changed;
+        }
+        changed
+    }
+}
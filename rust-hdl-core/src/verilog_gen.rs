@@ -237,10 +237,10 @@ impl VerilogVisitor for VerilogCodeGenerator {
     }
 
     fn visit_cast(&mut self, e: &VerilogExpression, bits: &usize) {
-        self.io.write("(");
+        self.io.write("((");
         self.visit_expression(e);
         let mask = (BigUint::from(1_u32) << bits) - 1_u32;
-        self.io.write(format!(") & {}'h{:x}", bits, mask))
+        self.io.write(format!(") & {}'h{:x})", bits, mask))
     }
 
     fn visit_signed(&mut self, a: &VerilogExpression) {
@@ -285,6 +285,21 @@ impl VerilogVisitor for VerilogCodeGenerator {
         self.visit_expression(ndx);
         self.io.write(")))");
     }
+
+    fn visit_ternary(
+        &mut self,
+        sel: &VerilogExpression,
+        on_true: &VerilogExpression,
+        on_false: &VerilogExpression,
+    ) {
+        self.io.write("((");
+        self.visit_expression(sel);
+        self.io.write(") ? (");
+        self.visit_expression(on_true);
+        self.io.write(") : (");
+        self.visit_expression(on_false);
+        self.io.write("))");
+    }
 }
 
 #[test]
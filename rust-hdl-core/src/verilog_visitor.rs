@@ -125,6 +125,26 @@ pub trait VerilogVisitor {
     ) {
         walk_index_replacement(self, a, b, c);
     }
+
+    fn visit_ternary(
+        &mut self,
+        a: &VerilogExpression,
+        b: &VerilogExpression,
+        c: &VerilogExpression,
+    ) {
+        walk_ternary(self, a, b, c);
+    }
+}
+
+pub fn walk_ternary<V: VerilogVisitor + ?Sized>(
+    visitor: &mut V,
+    a: &VerilogExpression,
+    b: &VerilogExpression,
+    c: &VerilogExpression,
+) {
+    visitor.visit_expression(a);
+    visitor.visit_expression(b);
+    visitor.visit_expression(c);
 }
 
 pub fn walk_index_replacement<V: VerilogVisitor + ?Sized>(
@@ -348,6 +368,9 @@ pub fn walk_expression<V: VerilogVisitor + ?Sized>(visitor: &mut V, e: &VerilogE
         VerilogExpression::IndexReplace(a, b, c) => {
             visitor.visit_index_replace(a, b, c);
         }
+        VerilogExpression::Ternary(a, b, c) => {
+            visitor.visit_ternary(a, b, c);
+        }
         VerilogExpression::Signed(a) => {
             visitor.visit_signed(a);
         }
@@ -1,7 +1,7 @@
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct EdgeTristateBufferDelayed<T: Synth> {
     pub to_pin: Signal<In, T>,
     pub from_pin: Signal<Out, T>,
@@ -131,7 +131,7 @@ fn test_pll_gen() {
     assert!((x.fout - 33.3333).abs() < 1e-3);
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct ICE40PLLBlock<const FIN_FREQ: u64, const FOUT_FREQ: u64> {
     pub clock_in: Signal<In, Clock>,
     pub clock_out: Signal<Out, Clock>,
@@ -191,7 +191,7 @@ SB_PLL40_CORE #(
     }
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct ICEPLL40Core {}
 
 impl ICEPLL40Core {
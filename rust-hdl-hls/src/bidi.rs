@@ -24,7 +24,7 @@ pub struct BidiBusD<T: Synth> {
     pub sig_master: Signal<In, Bit>,
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BidiMaster<T: Synth> {
     pub bus: BidiBusM<T>,
     pub clock: Signal<In, Clock>,
@@ -116,7 +116,7 @@ fn test_bidi_master2_synthesizable() {
     yosys_validate("bidi_master2", &vlog).unwrap();
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BidiSimulatedDevice<T: Synth> {
     pub bus: BidiBusD<T>,
     pub clock: Signal<In, Clock>,
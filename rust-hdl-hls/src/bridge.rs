@@ -9,7 +9,7 @@ use rust_hdl_widgets::prelude::*;
 // by the bridge.  This means different "devices" will come out of reset
 // at different times as the reset de-assert propagates through the network.
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct Bridge<const D: usize, const A: usize, const N: usize> {
     pub upstream: SoCBusResponder<D, A>,
     pub nodes: [SoCPortController<D>; N],
@@ -28,7 +28,7 @@ enum BaseControllerState {
 // and communicates with a 16 bit bus.  Other designs are possible,
 // but the internal logic needs to handle the differences in address
 // space bits, data widths, etc.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BaseController<const A: usize> {
     pub from_cpu: FIFOReadController<Bits<16>>, // Word-stream from the CPU
     pub to_cpu: FIFOWriteController<Bits<16>>,  // Word-stream to the CPU
@@ -2,7 +2,7 @@ use crate::bus::{FIFOReadResponder, FIFOWriteResponder};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct CrossWiden<
     const DN: usize,
     const NN: usize,
@@ -72,7 +72,7 @@ fn test_hsl_cross_fifo_synthesizes() {
     yosys_validate("hsl_cross_fifo", &vlog).unwrap();
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct CrossNarrow<
     const DW: usize,
     const WN: usize,
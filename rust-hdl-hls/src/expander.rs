@@ -2,7 +2,7 @@ use crate::bus::{FIFOReadController, FIFOWriteController};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct Expander<const DN: usize, const DW: usize> {
     pub bus_read: FIFOReadController<Bits<DN>>,
     pub bus_write: FIFOWriteController<Bits<DW>>,
@@ -2,7 +2,7 @@ use crate::bus::{FIFOReadResponder, FIFOWriteResponder};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SyncFIFO<T: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     pub bus_write: FIFOWriteResponder<T>,
     pub bus_read: FIFOReadResponder<T>,
@@ -29,7 +29,7 @@ impl<T: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> Logic
     }
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct AsyncFIFO<T: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     pub bus_write: FIFOWriteResponder<T>,
     pub write_clock: Signal<In, Clock>,
@@ -1,7 +1,7 @@
 use crate::bus::{FIFOReadController, FIFOWriteController};
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct FIFOLink<T: Synth> {
     pub read: FIFOReadController<T>,
     pub write: FIFOWriteController<T>,
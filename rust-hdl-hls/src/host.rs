@@ -8,7 +8,7 @@ use rust_hdl_widgets::prelude::*;
 
 // Creates a Host object that connects a bidirectional 8-bit
 // bus to a Controller with the appropriate intermediate pieces.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct Host<const A: usize> {
     pub bidi_bus: BidiBusM<Bits<8>>,
     pub bus: SoCBusController<16, A>,
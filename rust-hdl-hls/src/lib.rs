@@ -6,6 +6,7 @@ pub mod cross_fifo;
 pub mod expander;
 pub mod fifo;
 pub mod fifo_linker;
+pub mod monitor;
 pub mod host;
 pub mod miso_fifo_port;
 pub mod miso_port;
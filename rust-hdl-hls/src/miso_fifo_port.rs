@@ -3,7 +3,7 @@ use crate::fifo::SyncFIFO;
 use crate::miso_port::MISOPort;
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct MISOFIFOPort<const W: usize, const N: usize, const NP1: usize, const BLOCK: u32> {
     pub bus: SoCPortResponder<W>,
     port: MISOPort<W>,
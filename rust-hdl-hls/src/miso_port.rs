@@ -6,7 +6,7 @@ use rust_hdl_widgets::prelude::*;
 // An input port simply stores the value written to it's input back to
 // the master.  The address comparison logic is registered to improve the
 // timing analysis of the bus.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct MISOPort<const D: usize> {
     pub bus: SoCPortResponder<D>,
     pub port_in: Signal<In, Bits<D>>,
@@ -2,7 +2,7 @@ use crate::bus::SoCPortResponder;
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MISOWidePort<const W: usize, const D: usize> {
     pub bus: SoCPortResponder<D>,
     pub port_in: Signal<In, Bits<W>>,
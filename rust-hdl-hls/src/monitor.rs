@@ -0,0 +1,345 @@
+use crate::bus::{FIFOReadController, FIFOWriteController, SoCBusController};
+use rust_hdl_core::prelude::*;
+use std::collections::VecDeque;
+use std::fmt::{Debug, Display, Formatter};
+
+/// A transaction seen by a monitor, and the time (in picoseconds) of the clock
+/// edge on which it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transaction<D> {
+    pub time: u64,
+    pub item: D,
+}
+
+/// A disagreement between the transactions seen by a monitor, and those expected by
+/// its [Scoreboard].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScoreboardError<D> {
+    /// The transaction did not match the next expected one
+    Mismatch { time: u64, expected: D, actual: D },
+    /// A transaction happened after all of the expected ones had been seen
+    Unexpected { time: u64, actual: D },
+}
+
+impl<D: Debug> Display for ScoreboardError<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScoreboardError::Mismatch {
+                time,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "At time {} ps: expected {:?}, got {:?}",
+                time, expected, actual
+            ),
+            ScoreboardError::Unexpected { time, actual } => {
+                write!(f, "At time {} ps: unexpected {:?}", time, actual)
+            }
+        }
+    }
+}
+
+/// A [Scoreboard] compares the transactions reconstructed by a monitor against a queue
+/// of expected transactions (i.e., the output of a reference model), in order.
+#[derive(Clone, Debug)]
+pub struct Scoreboard<D> {
+    expected: VecDeque<D>,
+    matched: usize,
+    errors: Vec<ScoreboardError<D>>,
+}
+
+impl<D> Default for Scoreboard<D> {
+    fn default() -> Self {
+        Self {
+            expected: Default::default(),
+            matched: 0,
+            errors: vec![],
+        }
+    }
+}
+
+impl<D: Clone + PartialEq + Debug> Scoreboard<D> {
+    /// Add a transaction to the end of the expected queue
+    pub fn expect(&mut self, item: D) {
+        self.expected.push_back(item);
+    }
+    /// Check a transaction against the head of the expected queue
+    pub fn check(&mut self, time: u64, actual: D) {
+        match self.expected.pop_front() {
+            Some(expected) if expected == actual => self.matched += 1,
+            Some(expected) => self.errors.push(ScoreboardError::Mismatch {
+                time,
+                expected,
+                actual,
+            }),
+            None => self
+                .errors
+                .push(ScoreboardError::Unexpected { time, actual }),
+        }
+    }
+    /// The number of transactions that matched
+    pub fn matched(&self) -> usize {
+        self.matched
+    }
+    /// The expected transactions that have not been seen (yet)
+    pub fn missing(&self) -> &VecDeque<D> {
+        &self.expected
+    }
+    pub fn errors(&self) -> &[ScoreboardError<D>] {
+        &self.errors
+    }
+    /// Returns `true` if every expected transaction was seen, and nothing else was
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.expected.is_empty()
+    }
+}
+
+impl<D: Debug> Display for Scoreboard<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} matched, {} errors, {} missing",
+            self.matched,
+            self.errors.len(),
+            self.expected.len()
+        )?;
+        for error in &self.errors {
+            writeln!(f, "  {}", error)?;
+        }
+        for missing in &self.expected {
+            writeln!(f, "  Never saw {:?}", missing)?;
+        }
+        Ok(())
+    }
+}
+
+// The part of a monitor shared by all of the bus types.  Transactions are decoded
+// from the state of the bus just before each rising edge of the clock.
+struct Sampler<U, S> {
+    clock: Box<dyn Fn(&U) -> Clock>,
+    snapshot: Box<dyn Fn(&U) -> S>,
+    last: Option<(Clock, S)>,
+}
+
+impl<U, S> Sampler<U, S> {
+    fn new<C, F>(clock: C, snapshot: F) -> Self
+    where
+        C: Fn(&U) -> Clock + 'static,
+        F: Fn(&U) -> S + 'static,
+    {
+        Self {
+            clock: Box::new(clock),
+            snapshot: Box::new(snapshot),
+            last: None,
+        }
+    }
+    // Returns the state of the bus before the clock edge, if the clock just rose
+    fn sample(&mut self, x: &U) -> Option<S> {
+        let clock = (self.clock)(x);
+        let previous = self.last.replace((clock, (self.snapshot)(x)));
+        match previous {
+            Some((last_clock, state)) if !last_clock.clk && clock.clk => Some(state),
+            _ => None,
+        }
+    }
+}
+
+// The record of transactions common to all of the monitors
+struct Log<D> {
+    transactions: Vec<Transaction<D>>,
+    scoreboard: Scoreboard<D>,
+    checking: bool,
+}
+
+impl<D: Clone + PartialEq + Debug> Log<D> {
+    fn new() -> Self {
+        Self {
+            transactions: vec![],
+            scoreboard: Default::default(),
+            checking: false,
+        }
+    }
+    fn record(&mut self, time: u64, item: D) {
+        if self.checking {
+            self.scoreboard.check(time, item.clone());
+        }
+        self.transactions.push(Transaction { time, item });
+    }
+}
+
+macro_rules! monitor_accessors {
+    ($item: ty) => {
+        /// Load the scoreboard with the transactions the monitor is expected to see.  Without
+        /// this, the monitor only records the transactions.
+        pub fn expecting<I: IntoIterator<Item = $item>>(mut self, items: I) -> Self {
+            self.log.checking = true;
+            for item in items {
+                self.log.scoreboard.expect(item);
+            }
+            self
+        }
+        /// The transactions seen by the monitor, in order
+        pub fn transactions(&self) -> &[Transaction<$item>] {
+            &self.log.transactions
+        }
+        pub fn scoreboard(&self) -> &Scoreboard<$item> {
+            &self.log.scoreboard
+        }
+    };
+}
+
+/// A passive monitor for a [FIFOWriteController].  It records the data of every write that
+/// is accepted by the FIFO (i.e., when `write` is asserted and the FIFO is not `full`),
+/// and checks it against its [Scoreboard].  Attach it to a simulation with
+/// [Simulation::add_monitor].
+pub struct FIFOWriteMonitor<U, T: Synth> {
+    sampler: Sampler<U, (bool, bool, T)>,
+    log: Log<T>,
+}
+
+impl<U: 'static, T: Synth> FIFOWriteMonitor<U, T> {
+    /// Create a monitor for the FIFO interface returned by `bus`, that is clocked by `clock`.
+    pub fn new<C, B>(clock: C, bus: B) -> Self
+    where
+        C: Fn(&U) -> Clock + 'static,
+        B: Fn(&U) -> &FIFOWriteController<T> + 'static,
+    {
+        Self {
+            sampler: Sampler::new(clock, move |x| {
+                let bus = bus(x);
+                (bus.write.val(), bus.full.val(), bus.data.val())
+            }),
+            log: Log::new(),
+        }
+    }
+    monitor_accessors!(T);
+}
+
+impl<U, T: Synth> Monitor<U> for FIFOWriteMonitor<U, T> {
+    fn sample(&mut self, time: u64, x: &U) {
+        if let Some((write, full, data)) = self.sampler.sample(x) {
+            if write && !full {
+                self.log.record(time, data);
+            }
+        }
+    }
+}
+
+/// A passive monitor for a [FIFOReadController].  It records the data of every read from the
+/// FIFO (i.e., when `read` is asserted and the FIFO is not `empty`), and checks it against
+/// its [Scoreboard].  See [FIFOWriteMonitor] for an example.
+pub struct FIFOReadMonitor<U, T: Synth> {
+    sampler: Sampler<U, (bool, bool, T)>,
+    log: Log<T>,
+}
+
+impl<U: 'static, T: Synth> FIFOReadMonitor<U, T> {
+    /// Create a monitor for the FIFO interface returned by `bus`, that is clocked by `clock`.
+    pub fn new<C, B>(clock: C, bus: B) -> Self
+    where
+        C: Fn(&U) -> Clock + 'static,
+        B: Fn(&U) -> &FIFOReadController<T> + 'static,
+    {
+        Self {
+            sampler: Sampler::new(clock, move |x| {
+                let bus = bus(x);
+                (bus.read.val(), bus.empty.val(), bus.data.val())
+            }),
+            log: Log::new(),
+        }
+    }
+    monitor_accessors!(T);
+}
+
+impl<U, T: Synth> Monitor<U> for FIFOReadMonitor<U, T> {
+    fn sample(&mut self, time: u64, x: &U) {
+        if let Some((read, empty, data)) = self.sampler.sample(x) {
+            if read && !empty {
+                self.log.record(time, data);
+            }
+        }
+    }
+}
+
+/// The transactions on a [SoCBusController]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SoCBusTransaction<const D: usize, const A: usize> {
+    /// The controller selected the port at `address`
+    Address(Bits<A>),
+    /// The controller strobed the selected port.  Both directions of the data bus are
+    /// captured, since the direction of the transfer depends on the port.
+    Strobe {
+        address: Bits<A>,
+        from_controller: Bits<D>,
+        to_controller: Bits<D>,
+    },
+}
+
+#[derive(Copy, Clone)]
+struct SoCBusState<const D: usize, const A: usize> {
+    address: Bits<A>,
+    address_strobe: bool,
+    strobe: bool,
+    from_controller: Bits<D>,
+    to_controller: Bits<D>,
+}
+
+/// A passive monitor for a [SoCBusController].  It records every address strobe, and every
+/// data strobe (along with the address selected at the time) on the bus, and checks them
+/// against its [Scoreboard].  The bus carries its own clock.
+pub struct SoCBusMonitor<U, const D: usize, const A: usize> {
+    sampler: Sampler<U, SoCBusState<D, A>>,
+    address: Bits<A>,
+    log: Log<SoCBusTransaction<D, A>>,
+}
+
+impl<U: 'static, const D: usize, const A: usize> SoCBusMonitor<U, D, A> {
+    /// Create a monitor for the bus returned by `bus`.
+    pub fn new<B>(bus: B) -> Self
+    where
+        B: Fn(&U) -> &SoCBusController<D, A> + Clone + 'static,
+    {
+        let clock = bus.clone();
+        Self {
+            sampler: Sampler::new(
+                move |x| clock(x).clock.val(),
+                move |x| {
+                    let bus = bus(x);
+                    SoCBusState {
+                        address: bus.address.val(),
+                        address_strobe: bus.address_strobe.val(),
+                        strobe: bus.strobe.val(),
+                        from_controller: bus.from_controller.val(),
+                        to_controller: bus.to_controller.val(),
+                    }
+                },
+            ),
+            address: Default::default(),
+            log: Log::new(),
+        }
+    }
+    monitor_accessors!(SoCBusTransaction<D, A>);
+}
+
+impl<U, const D: usize, const A: usize> Monitor<U> for SoCBusMonitor<U, D, A> {
+    fn sample(&mut self, time: u64, x: &U) {
+        if let Some(state) = self.sampler.sample(x) {
+            if state.address_strobe {
+                self.address = state.address;
+                self.log
+                    .record(time, SoCBusTransaction::Address(state.address));
+            }
+            if state.strobe {
+                self.log.record(
+                    time,
+                    SoCBusTransaction::Strobe {
+                        address: self.address,
+                        from_controller: state.from_controller,
+                        to_controller: state.to_controller,
+                    },
+                );
+            }
+        }
+    }
+}
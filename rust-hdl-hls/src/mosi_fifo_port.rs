@@ -3,7 +3,7 @@ use crate::fifo::SyncFIFO;
 use crate::mosi_port::MOSIPort;
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct MOSIFIFOPort<const W: usize, const N: usize, const NP1: usize, const BLOCK: u32> {
     pub bus: SoCPortResponder<W>,
     port: MOSIPort<W>,
@@ -7,7 +7,7 @@ use rust_hdl_widgets::prelude::*;
 // The strobe from the master is also forwarded.  This allows you to
 // build logic that knows when the value was changed, or treat the
 // strobe like a trigger.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct MOSIPort<const D: usize> {
     pub bus: SoCPortResponder<D>,
     pub port_out: Signal<Out, Bits<D>>,
@@ -27,6 +27,10 @@ pub use crate::host::Host;
 pub use crate::miso_fifo_port::MISOFIFOPort;
 pub use crate::miso_port::MISOPort;
 pub use crate::miso_wide_port::MISOWidePort;
+pub use crate::monitor::{
+    FIFOReadMonitor, FIFOWriteMonitor, Scoreboard, ScoreboardError, SoCBusMonitor,
+    SoCBusTransaction, Transaction,
+};
 pub use crate::mosi_fifo_port::MOSIFIFOPort;
 pub use crate::mosi_port::MOSIPort;
 pub use crate::mosi_wide_port::MOSIWidePort;
@@ -2,7 +2,7 @@ use crate::bus::{FIFOReadController, FIFOWriteController};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::{FIFOReducerN, WordOrder};
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct Reducer<const DW: usize, const DN: usize> {
     pub bus_read: FIFOReadController<Bits<DW>>,
     pub bus_write: FIFOWriteController<Bits<DN>>,
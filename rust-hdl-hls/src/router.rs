@@ -8,7 +8,7 @@ use rust_hdl_widgets::prelude::*;
 // The master then sees each port on the bridge mapped to the offset
 // of it's base address.  Note that you can stack routers if needed.
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct Router<const D: usize, const A: usize, const N: usize> {
     pub upstream: SoCBusResponder<D, A>,
     pub nodes: [SoCBusController<D, A>; N],
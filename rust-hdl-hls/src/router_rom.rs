@@ -9,7 +9,7 @@ use std::collections::BTreeMap;
 // The master then sees each port on the bridge mapped to the offset
 // of it's base address.  Note that you can stack RouterROMs if needed.
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct RouterROM<const D: usize, const A: usize, const N: usize> {
     pub upstream: SoCBusResponder<D, A>,
     pub nodes: [SoCBusController<D, A>; N],
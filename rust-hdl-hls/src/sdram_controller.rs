@@ -7,7 +7,7 @@ use crate::HLSNamedPorts;
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMController<const R: usize, const C: usize> {
     pub dram: SDRAMDriver<16>,
     pub upstream: SoCBusResponder<16, 8>,
@@ -14,7 +14,7 @@ enum State {
     Reading,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMControllerTester<const R: usize, const C: usize> {
     pub dram: SDRAMDriver<16>,
     pub upstream: SoCBusResponder<16, 8>,
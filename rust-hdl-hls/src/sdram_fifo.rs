@@ -2,7 +2,7 @@ use crate::bus::{FIFOReadResponder, FIFOWriteResponder};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMFIFO<const R: usize, const C: usize, const P: u32, const D: usize, const A: usize> {
     pub clock: Signal<In, Clock>,
     pub sdram: SDRAMDriver<D>,
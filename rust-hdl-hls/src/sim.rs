@@ -1,3 +1,7 @@
+// The exported macros draw their random delays from the simulation generator
+#[doc(hidden)]
+pub use rust_hdl_core::regression::sim_rng;
+
 #[macro_export]
 macro_rules! hls_fifo_write_lazy {
     ($sim: ident, $($clock: ident).+, $uut: ident, $($fifo:ident).+, $data: expr) => {
@@ -10,8 +14,8 @@ macro_rules! hls_fifo_write_lazy {
             $uut.$($fifo).+.write.next = true;
             wait_clock_cycle!($sim, $($clock).+, $uut);
             $uut.$($fifo).+.write.next = false;
-            if rand::thread_rng().gen::<f64>() < 0.2 {
-                for _ in 0..(rand::thread_rng().gen::<u8>() % 40) {
+            if $crate::sim::sim_rng().gen::<f64>() < 0.2 {
+                for _ in 0..($crate::sim::sim_rng().gen::<u8>() % 40) {
                     wait_clock_cycle!($sim, $($clock).+, $uut);
                 }
             }
@@ -59,8 +63,8 @@ macro_rules! hls_fifo_read_lazy {
             $uut.$($fifo).+.read.next = true;
             wait_clock_cycle!($sim, $($clock).+, $uut);
             $uut.$($fifo).+.read.next = false;
-            if rand::thread_rng().gen::<f64>() < 0.2 {
-                for _ in 0..(rand::thread_rng().gen::<u8>() % 40) {
+            if $crate::sim::sim_rng().gen::<f64>() < 0.2 {
+                for _ in 0..($crate::sim::sim_rng().gen::<u8>() % 40) {
                     wait_clock_cycle!($sim, $($clock).+, $uut);
                 }
             }
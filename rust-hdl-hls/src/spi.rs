@@ -12,7 +12,7 @@ use rust_hdl_widgets::prelude::*;
 // 1 - data out
 // 2 - width in
 // 3 - start/type
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct HLSSPIMaster<const D: usize, const A: usize, const W: usize> {
     pub spi: SPIWiresMaster,
     pub upstream: SoCBusResponder<D, A>,
@@ -84,7 +84,7 @@ fn test_hls_spi_master_is_synthesizable() {
     yosys_validate("hls_spi", &vlog).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct HLSSPIMasterDynamicMode<const D: usize, const A: usize, const W: usize> {
     pub spi: SPIWiresMaster,
     pub upstream: SoCBusResponder<D, A>,
@@ -163,7 +163,7 @@ fn test_hls_spi_master_dynamic_mode_is_synthesizable() {
     yosys_validate("hsl_spi_dm", &vlog).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct HLSSPIMuxSlaves<const D: usize, const A: usize, const N: usize> {
     pub to_slaves: [SPIWiresMaster; N],
     pub from_bus: SPIWiresSlave,
@@ -214,7 +214,7 @@ fn test_hls_spi_mux_slaves_is_synthesizable() {
     yosys_validate("hls_spi_mux_slaves", &generate_verilog(&uut)).unwrap()
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct HLSSPIMuxMasters<const D: usize, const A: usize, const N: usize> {
     pub from_masters: [SPIWiresSlave; N],
     pub upstream: SoCBusResponder<D, A>,
@@ -19,7 +19,7 @@ pub fn snore<const P: usize>(x: u32) -> Bits<P> {
     amp.to_bits()
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FaderWithSyncROM {
     pub clock: Signal<In, Clock>,
     pub active: Signal<Out, Bit>,
@@ -69,7 +69,7 @@ enum FIFOFeederState {
     Done,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct LazyFIFOFeeder<T: Synth, const N: usize> {
     pub clock: Signal<In, Clock>,
     pub bus: FIFOWriteController<T>,
@@ -150,7 +150,7 @@ impl<T: Synth, const N: usize> Logic for LazyFIFOFeeder<T, N> {
     }
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct LazyFIFOReader<T: Synth, const N: usize> {
     pub clock: Signal<In, Clock>,
     pub bus: FIFOReadController<T>,
@@ -238,10 +238,10 @@ impl<T: Synth, const N: usize> Logic for LazyFIFOReader<T, N> {
 }
 
 pub fn bursty_rand() -> Bits<32> {
-    if rand::thread_rng().gen::<f64>() < 0.9 {
+    if sim_rng().gen::<f64>() < 0.9 {
         Bits::from(0)
     } else {
-        ((rand::thread_rng().gen::<f64>() * 40.0) as u32).to_bits()
+        ((sim_rng().gen::<f64>() * 40.0) as u32).to_bits()
     }
 }
 
@@ -249,7 +249,7 @@ pub fn bursty_vec(len: usize) -> Vec<Bits<32>> {
     (0..len).map(|_| bursty_rand()).collect()
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SoCTestChip {
     pub clock: Signal<In, Clock>,
     pub sys_clock: Signal<In, Clock>,
@@ -0,0 +1,95 @@
+use proc_macro2::Span;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::visit_mut::VisitMut;
+use syn::{parse_quote, Expr};
+
+use crate::common::TS;
+
+// Rewrites the simulated version of an `hdl_gen` kernel so that each branch
+// arm increments a counter in the branch coverage registry.
+struct Instrument {
+    sites: Vec<TS>,
+}
+
+impl Instrument {
+    fn site(&mut self, span: Span, arm: String) -> syn::Stmt {
+        let index = self.sites.len();
+        self.sites.push(quote_spanned! {span=>
+            BranchSite::new(file!(), line!(), #arm)
+        });
+        parse_quote! {
+            __HDL_BRANCH_SITES[#index].hit();
+        }
+    }
+}
+
+impl VisitMut for Instrument {
+    fn visit_expr_if_mut(&mut self, node: &mut syn::ExprIf) {
+        syn::visit_mut::visit_expr_if_mut(self, node);
+        let cond = &node.cond;
+        let cond = quote!(#cond).to_string();
+        let hit = self.site(node.then_branch.span(), format!("if {}", cond));
+        node.then_branch.stmts.insert(0, hit);
+        match &mut node.else_branch {
+            Some((_, else_expr)) => {
+                // An `else if` is instrumented when the nested `if` is visited
+                if let Expr::Block(block) = else_expr.as_mut() {
+                    let hit = self.site(block.span(), format!("else (of if {})", cond));
+                    block.block.stmts.insert(0, hit);
+                }
+            }
+            None => {
+                let hit = self.site(
+                    node.if_token.span(),
+                    format!("implicit else (of if {})", cond),
+                );
+                node.else_branch = Some((
+                    Default::default(),
+                    Box::new(parse_quote! {
+                        {
+                            #hit
+                        }
+                    }),
+                ));
+            }
+        }
+    }
+
+    fn visit_arm_mut(&mut self, node: &mut syn::Arm) {
+        syn::visit_mut::visit_arm_mut(self, node);
+        let pat = &node.pat;
+        let pat = quote!(#pat).to_string().replace(" :: ", "::");
+        let hit = self.site(node.pat.span(), format!("match {}", pat));
+        let body = &node.body;
+        *node.body = parse_quote! {
+            {
+                #hit
+                #body
+            }
+        };
+    }
+}
+
+pub(crate) fn instrument(mut item: syn::ItemFn) -> TS {
+    let mut visitor = Instrument { sites: vec![] };
+    visitor.visit_block_mut(&mut item.block);
+    let sites = visitor.sites;
+    let count = sites.len();
+    let name = item.sig.ident.to_string();
+    let body = &item.block;
+    *item.block = parse_quote! {
+        {
+            // A static in a generic kernel is shared by all of its instantiations, so the
+            // branches are counted per kernel (not per instantiation), and the kernel is named
+            // without its generic parameters.
+            static __HDL_BRANCH_SITES: [BranchSite; #count] = [#(#sites),*];
+            static __HDL_BRANCH_REGISTER: ::std::sync::Once = ::std::sync::Once::new();
+            __HDL_BRANCH_REGISTER.call_once(|| {
+                register_branches(::std::any::type_name::<Self>(), #name, &__HDL_BRANCH_SITES)
+            });
+            #body
+        }
+    };
+    quote!(#item)
+}
@@ -114,6 +114,57 @@ pub fn get_has_changed(fields: Vec<TS>) -> syn::Result<TS> {
     }
 }
 
+// The ports of a block are the interfaces of its fields.  The ports of an interface are
+// the ports of its fields, and its interface is the interface of its fields.
+pub fn get_has_changed_ports(fields: Vec<TS>, is_block: bool) -> syn::Result<TS> {
+    if fields.is_empty() {
+        Ok(quote! {
+            fn has_changed_ports(&self) -> bool {
+                false
+            }
+            fn has_changed_interface(&self) -> bool {
+                false
+            }
+        })
+    } else if is_block {
+        Ok(quote! {
+            fn has_changed_ports(&self) -> bool {
+                #(self.#fields.has_changed_interface())||*
+            }
+            fn has_changed_interface(&self) -> bool {
+                false
+            }
+        })
+    } else {
+        Ok(quote! {
+            fn has_changed_ports(&self) -> bool {
+                #(self.#fields.has_changed_ports())||*
+            }
+            fn has_changed_interface(&self) -> bool {
+                #(self.#fields.has_changed_interface())||*
+            }
+        })
+    }
+}
+
+// A block reads its own ports, and the ports of its fields, so it only needs to be updated
+// if one of them changed.  Its fields are always latched.
+pub fn get_update_pending(fields: Vec<TS>) -> syn::Result<TS> {
+    if fields.is_empty() {
+        return Ok(quote! {
+            fn update_pending(&mut self) {}
+        });
+    }
+    Ok(quote! {
+        fn update_pending(&mut self) {
+            if #(self.#fields.has_changed_ports())||* {
+                self.update();
+            }
+            #(self.#fields.update_pending();)*
+        }
+    })
+}
+
 pub fn squash(x: &str) -> String {
     x.to_string().replace([' ', '\n'], "")
 }
@@ -2,6 +2,7 @@ use std::ops::Index;
 
 use quote::format_ident;
 use quote::quote;
+use quote::quote_spanned;
 use syn::spanned::Spanned;
 use syn::{BinOp, Expr, Pat, PathSegment, Result, Stmt, UnOp};
 
@@ -17,10 +18,20 @@ pub(crate) fn hdl_gen_process(item: syn::ItemFn) -> Result<TS> {
         ));
     }
     let body = hdl_block(&item.block)?;
+    // Use the span of the function name, so that the location points at the kernel
+    let location = quote_spanned!(signature.ident.span() =>
+        ast::SourceLocation {
+            file: file!(),
+            line: line!(),
+        }
+    );
     Ok(quote! {
     fn hdl(&self) -> ast::Verilog {
         ast::Verilog::Combinatorial(#body)
     }
+    fn hdl_location(&self) -> Option<ast::SourceLocation> {
+        Some(#location)
+    }
     })
 }
 
@@ -272,6 +283,7 @@ fn hdl_compute(m: &syn::Expr) -> Result<TS> {
         Expr::Call(call) => hdl_call(call),
         Expr::MethodCall(method) => hdl_method(method),
         Expr::Lit(lit) => hdl_literal(lit),
+        Expr::Macro(x) => hdl_macro_value(x),
         Expr::Index(_ndx) => {
             let ndx_expanded = common::fixup_ident(quote!(#m).to_string());
             Ok(quote!(ast::VerilogExpression::Signal(#ndx_expanded.to_string())))
@@ -370,12 +382,32 @@ fn hdl_join_or_link(call: &syn::ExprCall, name: &str) -> Result<TS> {
     }
 }
 
+// The width of the result of a cast is its first generic argument (i.e., `bit_cast::<M, N>`)
+fn cast_width(call: &syn::ExprCall) -> Option<TS> {
+    if let Expr::Path(p) = call.func.as_ref() {
+        if let syn::PathArguments::AngleBracketed(args) = &p.path.segments.last()?.arguments {
+            return match args.args.first()? {
+                // A braced constant (`bit_cast::<{ N }, M>`) does not need its braces here
+                syn::GenericArgument::Const(Expr::Block(b)) if b.block.stmts.len() == 1 => {
+                    let width = &b.block.stmts[0];
+                    Some(quote!(#width))
+                }
+                width => Some(quote!(#width)),
+            };
+        }
+    }
+    None
+}
+
 fn hdl_call(call: &syn::ExprCall) -> Result<TS> {
     let funcname = quote!(#call).to_string();
-    if funcname.starts_with("bit_cast")
-        || funcname.starts_with("bits")
-        || funcname.starts_with("Bits")
-    {
+    if funcname.starts_with("bit_cast") {
+        let target = hdl_compute(&call.args[0])?;
+        match cast_width(call) {
+            Some(width) => Ok(quote!({ast::VerilogExpression::Cast(Box::new(#target), #width)})),
+            None => Ok(target),
+        }
+    } else if funcname.starts_with("bits") || funcname.starts_with("Bits") {
         hdl_compute(&call.args[0])
     } else if funcname.starts_with("unsigned_cast") {
         let target = hdl_compute(&call.args[0])?;
@@ -383,6 +415,10 @@ fn hdl_call(call: &syn::ExprCall) -> Result<TS> {
     } else if funcname.starts_with("signed_bit_cast") || funcname.starts_with("signed_cast") {
         let target = hdl_compute(&call.args[0])?;
         Ok(quote!({ast::VerilogExpression::Signed(Box::new(#target))}))
+    } else if callee_is(call, "mux") {
+        hdl_mux(call)
+    } else if callee_is(call, "select") {
+        hdl_select(call)
     } else if squash(&funcname).contains("::join") {
         hdl_join_or_link(call, "join")
     } else if squash(&funcname).contains("::link") {
@@ -398,6 +434,76 @@ fn hdl_call(call: &syn::ExprCall) -> Result<TS> {
     }
 }
 
+fn callee_is(call: &syn::ExprCall, name: &str) -> bool {
+    match call.func.as_ref() {
+        Expr::Path(p) => p
+            .path
+            .segments
+            .last()
+            .map(|x| x.ident == name)
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn hdl_ternary(sel: TS, on_true: TS, on_false: TS) -> TS {
+    quote!({
+        ast::VerilogExpression::Ternary(Box::new(#sel), Box::new(#on_true), Box::new(#on_false))
+    })
+}
+
+// mux(sel, on_true, on_false) becomes sel ? on_true : on_false, which merges the
+// candidates for an unknown selector, just like the four-state simulation does
+fn hdl_mux(call: &syn::ExprCall) -> Result<TS> {
+    if call.args.len() != 3 {
+        return Err(syn::Error::new(
+            call.span(),
+            "mux takes a selector and two values",
+        ));
+    }
+    let sel = hdl_compute(&call.args[0])?;
+    let on_true = hdl_compute(&call.args[1])?;
+    let on_false = hdl_compute(&call.args[2])?;
+    Ok(hdl_ternary(sel, on_true, on_false))
+}
+
+// select(sel, &[(p0, v0), (p1, v1)], default) becomes a chain of
+// (sel == p0) ? v0 : (sel == p1) ? v1 : default, so the first arm that matches wins
+fn hdl_select(call: &syn::ExprCall) -> Result<TS> {
+    let unsupported = |span| {
+        syn::Error::new(
+            span,
+            "select takes a selector, a slice of (pattern, value) tuples and a default",
+        )
+    };
+    if call.args.len() != 3 {
+        return Err(unsupported(call.span()));
+    }
+    let sel = hdl_compute(&call.args[0])?;
+    let arms = match &call.args[1] {
+        Expr::Reference(r) => r.expr.as_ref(),
+        x => x,
+    };
+    let arms = match arms {
+        Expr::Array(a) => &a.elems,
+        x => return Err(unsupported(x.span())),
+    };
+    let mut ret = hdl_compute(&call.args[2])?;
+    for arm in arms.iter().rev() {
+        let (pattern, value) = match arm {
+            Expr::Tuple(t) if t.elems.len() == 2 => (&t.elems[0], &t.elems[1]),
+            x => return Err(unsupported(x.span())),
+        };
+        let pattern = hdl_compute(pattern)?;
+        let value = hdl_compute(value)?;
+        let test = quote!({
+            ast::VerilogExpression::Binary(Box::new(#sel), ast::VerilogOp::Eq, Box::new(#pattern))
+        });
+        ret = hdl_ternary(test, value, ret);
+    }
+    Ok(ret)
+}
+
 fn hdl_method_set(method: &syn::ExprMethodCall) -> Result<TS> {
     let method_name = method.method.to_string();
     let field_set_match = regex::Regex::new(r"set_value_([a-zA-Z][a-zA-Z0-9_]*)").unwrap();
@@ -647,9 +753,35 @@ fn hdl_macro(x: &syn::ExprMacro) -> Result<TS> {
                 }
             ))
         }
+        // The clock domain crossing only exists in simulation
+        "cdc_cross" => Ok(quote!(ast::VerilogStatement::Macro(vec![]))),
         _ => Err(syn::Error::new(
             x.span(),
             "Unsupported macro invocation in HDL",
         )),
     }
 }
+
+fn hdl_macro_value(x: &syn::ExprMacro) -> Result<TS> {
+    let ident = &x.mac.path;
+    let macro_name = quote!(#ident).to_string();
+    match macro_name.as_ref() {
+        // In hardware, the crossing is just the input signal
+        "cdc_cross" => {
+            let args: DFFSetupArgs = x.mac.parse_body()?;
+            if args.dffs.len() != 2 {
+                return Err(syn::Error::new(
+                    x.span(),
+                    "Use cdc_cross!(self, clock, crossing, signal)",
+                ));
+            }
+            let signal = &args.dffs[1];
+            let signal = common::fixup_ident(quote!(#signal).to_string());
+            Ok(quote!(ast::VerilogExpression::Signal(#signal.to_string())))
+        }
+        _ => Err(syn::Error::new(
+            x.span(),
+            "Unsupported macro invocation in HDL expression",
+        )),
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "branch-coverage")]
+mod branch_coverage;
 mod common;
 mod connect_gen;
 mod hdl_gen;
@@ -63,6 +65,8 @@ pub fn logic_struct(input: TokenStream) -> TokenStream {
 pub fn hdl_gen(_attr: TokenStream, item: TokenStream) -> TokenStream {
     let orig = TS::from(item.clone());
     let parse = parse_macro_input!(item as syn::ItemFn);
+    #[cfg(feature = "branch-coverage")]
+    let orig = branch_coverage::instrument(parse.clone());
     let connects = match connect_gen(&parse) {
         Err(e) => return e.to_compile_error().into(),
         Ok(t) => t,
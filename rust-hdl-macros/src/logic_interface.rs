@@ -1,4 +1,6 @@
-use crate::common::{get_connect_all, get_has_changed, get_update_all, TS};
+use crate::common::{
+    get_connect_all, get_has_changed, get_has_changed_ports, get_update_all, get_update_pending, TS,
+};
 use crate::common::{get_field_names, get_field_types};
 use quote::quote;
 use std::collections::HashMap;
@@ -12,6 +14,8 @@ pub(crate) fn get_impl_for_logic_interface(input: &syn::DeriveInput) -> Result<T
     let link_hdl = get_link_hdl(fields.clone(), field_types.clone())?;
     let update_all = get_update_all(fields.clone())?;
     let has_changed = get_has_changed(fields.clone())?;
+    let has_changed_ports = get_has_changed_ports(fields.clone(), false)?;
+    let update_pending = get_update_pending(fields.clone())?;
     let connect_all = get_connect_all(fields.clone())?;
     let join_connect = get_join_connect(fields.clone())?;
     let join_hdl = get_join_hdl(fields.clone(), field_types)?;
@@ -39,6 +43,8 @@ pub(crate) fn get_impl_for_logic_interface(input: &syn::DeriveInput) -> Result<T
             #connect_all
             #update_all
             #has_changed
+            #has_changed_ports
+            #update_pending
             #accept
         }
 
@@ -118,6 +124,11 @@ fn get_accept(fields: Vec<TS>) -> Result<TS> {
             #(self.#fields.accept(#fields_as_strings, probe);)*
             probe.visit_end_namespace(name, self);
         }
+        fn accept_mut(&mut self, name: &str, probe: &mut dyn probe::ProbeMut) {
+            probe.visit_start_namespace(name);
+            #(self.#fields.accept_mut(#fields_as_strings, probe);)*
+            probe.visit_end_namespace(name);
+        }
     })
 }
 
@@ -68,6 +68,12 @@ pub fn get_logic_state_impls(input: &syn::DeriveInput) -> Result<TS> {
                     #(#name::#variants => #discriminants.into(),)*
                 }
             }
+            fn from_bits(bits: &synth::BigUint) -> Option<Self> {
+                #(if *bits == synth::BigUint::from(#discriminants) {
+                    return Some(#name::#variants);
+                })*
+                None
+            }
         }
 
         impl Into<Bits<{#name::BITS}>> for #name {
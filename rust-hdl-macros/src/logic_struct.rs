@@ -73,6 +73,16 @@ pub(crate) fn get_impl_for_logic_struct(input: &syn::DeriveInput) -> Result<TS>
                 let t: Bits<{Self::BITS}> = self.into();
                 t.into()
             }
+
+            fn from_bits(bits: &synth::BigUint) -> Option<Self> {
+                let mut ret = Self::default();
+                #(
+                    let mask = (synth::BigUint::from(1_u32) << <#field_types>::BITS) - 1_u32;
+                    let field = (bits >> ret.#get_offset_names()) & mask;
+                    ret.#fields = <#field_types>::from_bits(&field)?;
+                )*
+                Some(ret)
+            }
         }
     })
 }
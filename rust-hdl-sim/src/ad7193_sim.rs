@@ -15,7 +15,7 @@ enum AD7193State {
     SingleConversionCommit,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct AD7193Simulator {
     // Slave SPI bus
     pub wires: SPIWiresSlave,
@@ -215,7 +215,7 @@ fn test_ad7193_synthesizes() {
     yosys_validate("ad7193", &generate_verilog(&uut)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 struct Test7193 {
     clock: Signal<In, Clock>,
     master: SPIMaster<64>,
@@ -15,7 +15,7 @@ enum State {
     DoConvert,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct ADS8688Simulator {
     pub wires: SPIWiresSlave,
     pub clock: Signal<In, Clock>,
@@ -242,7 +242,7 @@ fn test_ads8688_synthesizes() {
     yosys_validate("ads8688", &generate_verilog(&uut)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 struct Test8688 {
     clock: Signal<In, Clock>,
     master: SPIMaster<64>,
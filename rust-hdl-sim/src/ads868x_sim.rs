@@ -15,7 +15,7 @@ enum ADS868XState {
     Nop,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct ADS868XSimulator {
     pub wires: SPIWiresSlave,
     pub clock: Signal<In, Clock>,
@@ -220,7 +220,7 @@ fn test_ads8689_synthesizes() {
     yosys_validate("ads8689", &generate_verilog(&uut)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 struct Test8689 {
     clock: Signal<In, Clock>,
     master: SPIMaster<32>,
@@ -22,7 +22,7 @@ enum DAQState {
     Copy1,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MAX31856Simulator {
     // Slave SPI bus
     pub wires: SPIWiresSlave,
@@ -233,7 +233,7 @@ fn test_max31856_synthesizes() {
     yosys_validate("max31856", &generate_verilog(&uut)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 struct Test31856 {
     clock: Signal<In, Clock>,
     master: SPIMaster<64>,
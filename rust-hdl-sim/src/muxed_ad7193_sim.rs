@@ -2,7 +2,7 @@ use super::ad7193_sim::{AD7193Config, AD7193Simulator};
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MuxedAD7193Simulators {
     // Input SPI bus
     pub wires: SPIWiresSlave,
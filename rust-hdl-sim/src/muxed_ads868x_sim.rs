@@ -2,7 +2,7 @@ use super::ads868x_sim::ADS868XSimulator;
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MuxedADS868XSimulators<const N: usize> {
     // Input SPI bus
     pub wires: SPIWiresSlave,
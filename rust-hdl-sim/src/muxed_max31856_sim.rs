@@ -2,7 +2,7 @@ use super::max31856_sim::MAX31856Simulator;
 use rust_hdl_core::prelude::*;
 use rust_hdl_widgets::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MuxedMAX31856Simulators {
     // Input SPI bus
     pub wires: SPIWiresSlave,
@@ -19,7 +19,7 @@ pub enum BankState {
 // number of rows to 256, and the number of columns to 32
 // That yields 8 row addresses, and 5 column addresses, for
 // a total of 13 address bits.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MemoryBank<const R: usize, const C: usize, const A: usize, const D: usize> {
     // Constraint - A = R + C
     pub clock: Signal<In, Clock>,
@@ -17,7 +17,7 @@ enum MasterState {
 }
 
 // Clock enable, and DQM are ignored.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMSimulator<
     const R: usize, // Number of rows
     const C: usize, // Number of columns
@@ -46,7 +46,7 @@ pub struct BidiBusD<T: Synth> {
     pub sig_master: Signal<In, Bit>,
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BidiMaster<T: Synth, const N: usize, const NP1: usize> {
     pub bus: BidiBusM<T>,
     pub bus_clock: Signal<In, Clock>,
@@ -157,7 +157,7 @@ fn test_bidi_master2_synthesizable() {
     yosys_validate("bidi_master2", &vlog).unwrap();
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BidiSimulatedDevice<T: Synth, const N: usize, const NP1: usize> {
     pub bus: BidiBusD<T>,
     pub clock: Signal<In, Clock>,
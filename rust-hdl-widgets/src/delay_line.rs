@@ -5,7 +5,7 @@ use rust_hdl_core::prelude::*;
 // A configurable delay line.  Given writes at the input,
 // will write those values back to the output N cycles later,
 // where N is an input of max bit width W.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct DelayLine<D: Synth, const N: usize, const W: usize> {
     pub clock: Signal<In, Clock>,
     pub data_in: Signal<In, D>,
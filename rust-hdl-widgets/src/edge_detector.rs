@@ -2,7 +2,7 @@ use rust_hdl_core::prelude::*;
 
 use crate::{dff::DFF, dff_setup};
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct EdgeDetector {
     pub input_signal: Signal<In, Bit>,
     pub edge_signal: Signal<Out, Bit>,
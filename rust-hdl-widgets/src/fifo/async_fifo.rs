@@ -10,7 +10,7 @@ macro_rules! declare_async_fifo {
     };
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct AsynchronousFIFO<D: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     // Read interface
     pub read: Signal<In, Bit>,
@@ -4,7 +4,7 @@ use crate::fifo::sync_fifo::SynchronousFIFO;
 use crate::prelude::{FIFOExpanderN, FIFOReducerN};
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct CrossWidenFIFO<
     const DN: usize,   // Narrow width
     const NN: usize,   // Number of bits on the narrow side address
@@ -111,7 +111,7 @@ fn cross_widen_fifo_is_synthesizable() {
     yosys_validate("cross_wide", &generate_verilog(&dev)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct CrossNarrowFIFO<
     const DW: usize,
     const WN: usize,
@@ -8,7 +8,7 @@ pub enum WordOrder {
     MostSignificantFirst,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FIFOExpanderN<const DN: usize, const DW: usize> {
     // Data comes by reading from the source FIFO
     pub data_in: Signal<In, Bits<DN>>,
@@ -4,7 +4,7 @@ use crate::{dff::DFF, dff_setup};
 
 // The read side of the circuitry for the FIFO.  Manages the read
 // address
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FIFOReadLogic<D: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     // Clock
     pub clock: Signal<In, Clock>,
@@ -116,7 +116,7 @@ fn fifo_read_is_synthesizable() {
     yosys_validate("fifo_read", &generate_verilog(&dev)).unwrap();
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FIFOWriteLogic<D: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     pub write: Signal<In, Bit>,
     pub data_in: Signal<In, D>,
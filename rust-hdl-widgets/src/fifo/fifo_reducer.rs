@@ -2,7 +2,7 @@ use rust_hdl_core::prelude::*;
 
 use crate::{dff::DFF, dff_setup};
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FIFOReducer<const DW: usize, const DN: usize, const REVERSE: bool> {
     // Data comes by reading from the source FIFO
     pub data_in: Signal<In, Bits<DW>>,
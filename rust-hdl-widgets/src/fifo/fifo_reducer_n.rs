@@ -1,7 +1,7 @@
 use crate::{dff::DFF, dff_setup, fifo::fifo_expander_n::WordOrder};
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct FIFOReducerN<const DW: usize, const DN: usize> {
     // Data comes by reading from the source FIFO
     pub data_in: Signal<In, Bits<DW>>,
@@ -3,7 +3,7 @@ use rust_hdl_core::prelude::*;
 use crate::{dff::DFF, dff_setup};
 
 // A synchronous FIFO of depth 1, backed by a pair of registers
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct RegisterFIFO<T: Synth> {
     pub data_in: Signal<In, T>,
     pub data_out: Signal<Out, T>,
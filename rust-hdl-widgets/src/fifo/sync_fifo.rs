@@ -10,7 +10,7 @@ macro_rules! declare_sync_fifo {
     };
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SynchronousFIFO<D: Synth, const N: usize, const NP1: usize, const BLOCK_SIZE: u32> {
     pub clock: Signal<In, Clock>,
     // Read interface
@@ -2,14 +2,14 @@ use rust_hdl_core::prelude::*;
 
 use crate::open_drain::{OpenDrainDriver, OpenDrainReceiver};
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "I2CBusReceiver"]
 pub struct I2CBusDriver {
     pub sda: OpenDrainDriver,
     pub scl: OpenDrainDriver,
 }
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "I2CBusDriver"]
 pub struct I2CBusReceiver {
     pub sda: OpenDrainReceiver,
@@ -29,7 +29,7 @@ enum State {
     WaitDriverIdle,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct I2CController {
     pub i2c: I2CBusDriver,
     pub clock: Signal<In, Clock>,
@@ -237,7 +237,7 @@ fn test_i2c_controller_synthesizes() {
 }
 
 // TODO - this probably needs some clean up
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 struct I2CControllerTest {
     clock: Signal<In, Clock>,
     controller: I2CController,
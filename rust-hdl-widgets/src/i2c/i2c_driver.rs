@@ -36,7 +36,7 @@ enum State {
 }
 
 // Implement the bit-bang I2C interface as reported on Wikipedia
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct I2CDriver {
     pub i2c: I2CBusDriver,
     pub clock: Signal<In, Clock>,
@@ -19,7 +19,7 @@ enum State {
     CollectAck,
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct I2CTarget {
     pub i2c: I2CBusDriver,
     pub clock: Signal<In, Clock>,
@@ -3,7 +3,7 @@ use array_init::array_init;
 use crate::prelude::*;
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct I2CTestBus<const N: usize> {
     pub endpoints: [I2CBusReceiver; N],
     pub sda_state: Signal<Local, Bit>,
@@ -50,7 +50,7 @@ enum State {
 
 // Provides a simple read/write memory on an I2C bus
 // The memory is 16 bits wide, and there are 16 addresses.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct I2CTestTarget {
     // The I2C data lines must have external pullups.
     pub i2c: I2CBusDriver,
@@ -14,7 +14,7 @@ enum MACFIRState {
     Write,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MultiplyAccumulateSymmetricFiniteImpulseResponseFilter<const ADDR_BITS: usize> {
     pub data_in: Signal<In, Signed<16>>,
     pub strobe_in: Signal<In, Bit>,
@@ -1,6 +1,6 @@
 use rust_hdl_core::prelude::*;
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct OpenDrainBuffer {
     pub bus: Signal<InOut, Bit>,
     pub control: OpenDrainReceiver,
@@ -39,14 +39,14 @@ fn test_opendrain_synthesizes() {
     yosys_validate("open_drain", &vlog).unwrap()
 }
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "OpenDrainReceiver"]
 pub struct OpenDrainDriver {
     pub drive_low: Signal<Out, Bit>,
     pub line_state: Signal<In, Bit>,
 }
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "OpenDrainDriver"]
 pub struct OpenDrainReceiver {
     pub drive_low: Signal<In, Bit>,
@@ -3,7 +3,7 @@ use rust_hdl_core::prelude::*;
 
 // Adopted from Alchitry.com Lucid module `pn_gen`
 // This version does not provide seed setting.  It generates a fixed sequence.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct LFSRSimple {
     pub clock: Signal<In, Clock>,
     pub strobe: Signal<In, Bit>,
@@ -3,7 +3,7 @@ use crate::strobe::Strobe;
 use rust_hdl_core::prelude::*;
 use std::time::Duration;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct Pulser {
     pub clock: Signal<In, Clock>,
     pub enable: Signal<In, Bit>,
@@ -2,7 +2,7 @@ use rust_hdl_core::prelude::*;
 
 use crate::dff::DFF;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct PulseWidthModulator<const N: usize> {
     pub enable: Signal<In, Bit>,
     pub threshold: Signal<In, Bits<N>>,
@@ -3,7 +3,7 @@ use rust_hdl_core::prelude::*;
 use rust_hdl_core::timing::TimingInfo;
 use std::collections::BTreeMap;
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 pub struct RAMWrite<D: Synth, const N: usize> {
     pub address: Signal<In, Bits<N>>,
     pub clock: Signal<In, Clock>,
@@ -11,7 +11,7 @@ pub struct RAMWrite<D: Synth, const N: usize> {
     pub enable: Signal<In, bool>,
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct RAM<D: Synth, const N: usize> {
     pub read_address: Signal<In, Bits<N>>,
     pub read_clock: Signal<In, Clock>,
@@ -41,10 +41,11 @@ impl<I: Iterator<Item = D>, D: Synth, const N: usize> From<I> for RAM<D, N> {
 impl<D: Synth, const N: usize> Logic for RAM<D, N> {
     fn update(&mut self) {
         if self.read_clock.pos_edge() {
-            self.read_data.next = *self
+            self.read_data.next = self
                 ._sim
                 .get(&self.read_address.val())
-                .unwrap_or(&D::default());
+                .copied()
+                .unwrap_or_else(uninitialized);
         }
         if self.write_clock.pos_edge() && self.write_enable.val() {
             self._sim
@@ -1,7 +1,7 @@
 use rust_hdl_core::prelude::*;
 use std::collections::BTreeMap;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct ROM<D: Synth, const N: usize> {
     pub address: Signal<In, Bits<N>>,
     pub data: Signal<Out, D>,
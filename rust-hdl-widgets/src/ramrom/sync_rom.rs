@@ -3,7 +3,7 @@ use rust_hdl_core::prelude::*;
 use rust_hdl_core::timing::TimingInfo;
 use std::collections::BTreeMap;
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SyncROM<D: Synth, const N: usize> {
     pub address: Signal<In, Bits<N>>,
     pub clock: Signal<In, Clock>,
@@ -2,7 +2,7 @@ use rust_hdl_core::prelude::*;
 
 use crate::{dff::DFF, dff_setup};
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct RegisteredEdgeTristate<const W: usize> {
     pub bus: Signal<InOut, Bits<W>>,
     pub write_enable: Signal<In, Bit>,
@@ -34,7 +34,7 @@ enum State {
 //  C - Col bits in the address
 //  D - Data bus width
 //  L - Line width (multiple of D)
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMBaseController<const R: usize, const C: usize, const L: usize, const D: usize> {
     pub clock: Signal<In, Clock>,
     pub sdram: SDRAMDriver<D>,
@@ -37,7 +37,7 @@ enum State {
 //  C - Col bits in the address
 //  D - Data bus width
 //  L - Burst size (< 32)
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMBurstController<const R: usize, const C: usize, const L: u32, const D: usize> {
     pub clock: Signal<In, Clock>,
     pub sdram: SDRAMDriver<D>,
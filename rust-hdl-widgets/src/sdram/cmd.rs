@@ -12,7 +12,7 @@ pub enum SDRAMCommand {
     NOP,              // HHH
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SDRAMCommandEncoder {
     pub ras_not: Signal<Out, Bit>,
     pub cas_not: Signal<Out, Bit>,
@@ -71,7 +71,7 @@ impl Logic for SDRAMCommandEncoder {
     }
 }
 
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SDRAMCommandDecoder {
     pub ras_not: Signal<In, Bit>,
     pub cas_not: Signal<In, Bit>,
@@ -11,7 +11,7 @@ enum State {
     Busy,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SDRAMFIFOController<
     const R: usize, // Number of rows in the SDRAM
     const C: usize, // Number of columns in the SDRAM
@@ -22,7 +22,7 @@ pub struct SPIConfig {
     pub cpol: bool,
 }
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "SPIWiresSlave"]
 pub struct SPIWiresMaster {
     pub mosi: Signal<Out, Bit>,
@@ -31,7 +31,7 @@ pub struct SPIWiresMaster {
     pub mclk: Signal<Out, Bit>,
 }
 
-#[derive(LogicInterface, Default)]
+#[derive(LogicInterface, Clone, Default)]
 #[join = "SPIWiresMaster"]
 pub struct SPIWiresSlave {
     pub mosi: Signal<In, Bit>,
@@ -40,7 +40,7 @@ pub struct SPIWiresSlave {
     pub mclk: Signal<In, Bit>,
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SPIMaster<const N: usize> {
     pub clock: Signal<In, Clock>,
     pub bits_outbound: Signal<In, Bits<16>>,
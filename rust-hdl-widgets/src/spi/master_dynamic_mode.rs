@@ -49,7 +49,7 @@ impl Into<SPIConfig> for SPIConfigDynamicMode {
     }
 }
 
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SPIMasterDynamicMode<const N: usize> {
     pub clock: Signal<In, Clock>,
     pub bits_outbound: Signal<In, Bits<16>>,
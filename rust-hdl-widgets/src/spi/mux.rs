@@ -3,7 +3,7 @@ use rust_hdl_core::prelude::*;
 use crate::prelude::{SPIWiresMaster, SPIWiresSlave};
 
 // Mux N SPI slaves onto a bus
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MuxSlaves<const N: usize, const A: usize> {
     pub from_master: SPIWiresSlave,
     pub to_slaves: [SPIWiresMaster; N],
@@ -48,7 +48,7 @@ fn test_spi_mux_slaves_is_synthesizable() {
 }
 
 // Mux N SPI masters onto a bus
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct MuxMasters<const N: usize, const A: usize> {
     pub to_bus: SPIWiresMaster,
     pub from_masters: [SPIWiresSlave; N],
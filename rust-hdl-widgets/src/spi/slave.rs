@@ -22,7 +22,7 @@ enum SPISlaveState {
 /// use it to implement a SPI endpoint in the FPGA if you want to.  This [SPISlave]
 /// is not very robust, so be cautious with using it.  In particular, with a very
 /// badly behaved SPI master, it may not operate as expected.
-#[derive(LogicBlock)]
+#[derive(LogicBlock, Clone)]
 pub struct SPISlave<const N: usize> {
     /// The clock driving the [SPISlave]
     pub clock: Signal<In, Clock>,
@@ -5,7 +5,7 @@ use crate::{dff::DFF, dff_setup};
 /// A [BitSynchronizer] is used to move signals that are asynchronous to a clock into that
 /// clock domain using a pair of back-to-back flip-flops.  While the first flip flop may
 /// become metastable, the second one is likely to be stable.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct BitSynchronizer {
     /// The input signal, which is asynchronous to the clock
     pub sig_in: Signal<In, Bit>,
@@ -13,6 +13,7 @@ pub struct BitSynchronizer {
     pub sig_out: Signal<Out, Bit>,
     /// The clock signal to synchronize the output to
     pub clock: Signal<In, Clock>,
+    cross: ClockCrossing<Bit>,
     dff0: DFF<Bit>,
     dff1: DFF<Bit>,
 }
@@ -21,7 +22,7 @@ impl Logic for BitSynchronizer {
     #[hdl_gen]
     fn update(&mut self) {
         dff_setup!(self, clock, dff0, dff1);
-        self.dff0.d.next = self.sig_in.val();
+        self.dff0.d.next = cdc_cross!(self, clock, cross, sig_in);
         self.dff1.d.next = self.dff0.q.val();
         self.sig_out.next = self.dff1.q.val();
     }
@@ -48,7 +49,7 @@ enum SyncSenderState {
 /// widgets will use a set of handshake signals to move a value from one clock domain to another
 /// safely.  Note that while the state machine is executing, the synchronizer will indicate it
 /// is busy.  Crossing clock domains with greater ease is best done with an [AsynchronousFIFO].
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SyncSender<T: Synth> {
     /// The input signal to synchronize across clock domains
     pub sig_in: Signal<In, T>,
@@ -129,7 +130,7 @@ enum SyncReceiverState {
 /// A [SyncReceiver] works together with a [SyncSender] to transmit data from one clock domain
 /// to another (in one direction).  To use a [SyncReceiver] wire up the [sig_cross], [flag_in]
 /// and [ack_out] signals between the two.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct SyncReceiver<T: Synth> {
     /// The data output synchronized to the receiver's clock
     pub sig_out: Signal<Out, T>,
@@ -147,6 +148,7 @@ pub struct SyncReceiver<T: Synth> {
     update_delay: DFF<Bit>,
     state: DFF<SyncReceiverState>,
     sync: BitSynchronizer,
+    cross: ClockCrossing<T>,
 }
 
 impl<T: Synth> Logic for SyncReceiver<T> {
@@ -154,6 +156,7 @@ impl<T: Synth> Logic for SyncReceiver<T> {
     fn update(&mut self) {
         dff_setup!(self, clock, hold, update_delay, state);
         clock!(self, clock, sync);
+        cdc_cross!(self, clock, cross, sig_cross);
         self.sig_out.next = self.hold.q.val();
         self.ack_out.next = false.into();
         self.sync.sig_in.next = self.flag_in.val();
@@ -170,7 +173,7 @@ impl<T: Synth> Logic for SyncReceiver<T> {
                 if !self.sync.sig_out.val() {
                     self.ack_out.next = false.into();
                     self.update_delay.d.next = true;
-                    self.hold.d.next = self.sig_cross.val().into();
+                    self.hold.d.next = cdc_cross!(self, clock, cross, sig_cross);
                     self.state.d.next = SyncReceiverState::WaitSteady.into();
                 } else {
                     self.ack_out.next = true;
@@ -199,7 +202,7 @@ fn sync_receiver_is_synthesizable() {
 /// Note that the [VectorSynchronizer] can be used to reflect a value/register into a
 /// second clock domain by tying `self.send.next = !self.busy.val()`.  In that case, the output
 /// signal will be always attempting to follow the [sig_in] input as quickly as possible.
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct VectorSynchronizer<T: Synth> {
     /// The input clock interface.  Input data is clocked in using this clock.
     pub clock_in: Signal<In, Clock>,
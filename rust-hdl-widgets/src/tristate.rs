@@ -22,7 +22,7 @@ use rust_hdl_core::prelude::*;
 ///    bus: Signal<InOut, Bits<8>>,
 /// }
 /// ```
-#[derive(LogicBlock, Default)]
+#[derive(LogicBlock, Clone, Default)]
 pub struct TristateBuffer<D: Synth> {
     /// The tristated signals come out of this pin.  This should be a top level signal in your design.
     pub bus: Signal<InOut, D>,
@@ -0,0 +1,148 @@
+// Run with `cargo test -p rust-hdl --features branch-coverage --test core_branch_coverage`
+#![cfg(feature = "branch-coverage")]
+use rust_hdl::prelude::*;
+use std::sync::Mutex;
+
+// The branch counters are global, and one of the tests resets them
+static COUNTERS: Mutex<()> = Mutex::new(());
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Mode {
+    Idle,
+    Run,
+    Stop,
+}
+
+#[derive(LogicBlock, Default)]
+struct Branchy {
+    pub clock: Signal<In, Clock>,
+    pub start: Signal<In, Bit>,
+    pub abort: Signal<In, Bit>,
+    mode: DFF<Mode>,
+}
+
+impl Logic for Branchy {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, mode);
+        match self.mode.q.val() {
+            Mode::Idle => {
+                if self.start.val() {
+                    self.mode.d.next = Mode::Run;
+                }
+            }
+            Mode::Run => {
+                if self.abort.val() {
+                    self.mode.d.next = Mode::Stop;
+                } else {
+                    self.mode.d.next = Mode::Run;
+                }
+            }
+            Mode::Stop => {
+                self.mode.d.next = Mode::Idle;
+            }
+        }
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Gate<const N: usize> {
+    pub enable: Signal<In, Bit>,
+    pub data: Signal<In, Bits<N>>,
+    pub out: Signal<Out, Bits<N>>,
+}
+
+impl<const N: usize> Logic for Gate<N> {
+    #[hdl_gen]
+    fn update(&mut self) {
+        if self.enable.val() {
+            self.out.next = self.data.val();
+        } else {
+            self.out.next = 0.into();
+        }
+    }
+}
+
+#[test]
+fn test_branch_coverage_of_hdl_gen_kernel() {
+    let _guard = COUNTERS.lock().unwrap();
+    let mut sim = simple_sim!(Branchy, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 2);
+        x.start.next = true;
+        wait_clock_cycles!(ep, clock, x, 4);
+        ep.done(x)
+    });
+    let mut uut = Branchy::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let coverage = BranchCoverage::collect();
+    let branches = coverage.kernel("Branchy::update");
+    // 3 match arms, and 2 arms for each of the 2 ifs
+    assert_eq!(branches.len(), 7);
+    assert!(branches
+        .iter()
+        .all(|x| x.file.ends_with("core_branch_coverage.rs")));
+    let untaken = coverage
+        .untaken()
+        .into_iter()
+        .filter(|x| x.kernel == "Branchy::update")
+        .map(|x| x.arm.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(untaken, vec!["if self.abort.val()", "match Mode::Stop"]);
+    let stop = branches
+        .iter()
+        .find(|x| x.arm == "match Mode::Stop")
+        .unwrap();
+    // The arm is reported at the line of its pattern
+    let line = include_str!("core_branch_coverage.rs")
+        .lines()
+        .position(|x| x.trim() == "Mode::Stop => {")
+        .unwrap();
+    assert_eq!(stop.line as usize, line + 1);
+    assert!(coverage.to_string().contains("never took match Mode::Stop"));
+    BranchCoverage::reset();
+    assert!(BranchCoverage::collect()
+        .kernel("Branchy::update")
+        .iter()
+        .all(|x| x.hits == 0));
+}
+
+#[derive(LogicBlock, Default)]
+struct Gates {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    narrow: Gate<4>,
+    wide: Gate<8>,
+}
+
+impl Logic for Gates {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.narrow.enable.next = self.enable.val();
+        self.narrow.data.next = 1.into();
+        self.wide.enable.next = !self.enable.val();
+        self.wide.data.next = 1.into();
+    }
+}
+
+#[test]
+fn test_branch_coverage_of_generic_kernel() {
+    let _guard = COUNTERS.lock().unwrap();
+    let mut sim = simple_sim!(Gates, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.enable.next = true;
+        wait_clock_cycles!(ep, clock, x, 2);
+        ep.done(x)
+    });
+    let mut uut = Gates::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let coverage = BranchCoverage::collect();
+    let branches = coverage.kernel("Gate::update");
+    // Both instantiations count towards the same kernel
+    assert_eq!(branches.len(), 2);
+    assert!(branches.iter().all(|x| x.hits != 0));
+}
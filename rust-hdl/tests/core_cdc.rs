@@ -0,0 +1,168 @@
+use rust_hdl::core::check_error::CheckError;
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct TwoDomains {
+    pub fast_clock: Signal<In, Clock>,
+    pub slow_clock: Signal<In, Clock>,
+    pub data_in: Signal<In, Bit>,
+    pub data_out: Signal<Out, Bit>,
+    pub synced_out: Signal<Out, Bit>,
+    launch: DFF<Bit>,
+    capture: DFF<Bit>,
+    sync: BitSynchronizer,
+}
+
+impl Logic for TwoDomains {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, fast_clock, launch);
+        dff_setup!(self, slow_clock, capture);
+        clock!(self, slow_clock, sync);
+        self.launch.d.next = self.data_in.val();
+        // Sampled directly in the slow domain - this is the crossing to catch
+        self.capture.d.next = self.launch.q.val();
+        self.data_out.next = self.capture.q.val();
+        self.sync.sig_in.next = self.launch.q.val();
+        self.synced_out.next = self.sync.sig_out.val();
+    }
+}
+
+#[test]
+fn test_cdc_finds_unsynchronized_crossing() {
+    let mut uut = TwoDomains::default();
+    uut.connect_all();
+    match check_cdc(&uut) {
+        Err(CheckError::ClockDomainCrossings(paths)) => {
+            assert_eq!(paths.len(), 1);
+            assert_eq!(paths[0].from, "uut.launch.dff");
+            assert_eq!(paths[0].to, "uut.capture.dff");
+            assert_eq!(paths[0].launch_clock, "uut.fast_clock");
+            assert_eq!(paths[0].capture_clock, "uut.slow_clock");
+        }
+        x => panic!("Expected a clock domain crossing, got {:?}", x),
+    }
+}
+
+// A block with custom Verilog that is just a wire - it does not synchronize anything
+#[derive(LogicBlock, Default)]
+struct PassThrough {
+    pub sig_in: Signal<In, Bit>,
+    pub sig_out: Signal<Out, Bit>,
+}
+
+impl Logic for PassThrough {
+    fn update(&mut self) {
+        self.sig_out.next = self.sig_in.val();
+    }
+    fn connect(&mut self) {
+        self.sig_out.connect();
+    }
+    fn hdl(&self) -> Verilog {
+        Verilog::Custom("always @(*) sig_out = sig_in;".into())
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct CustomCrossing {
+    pub fast_clock: Signal<In, Clock>,
+    pub slow_clock: Signal<In, Clock>,
+    pub data_in: Signal<In, Bit>,
+    pub data_out: Signal<Out, Bit>,
+    launch: DFF<Bit>,
+    capture: DFF<Bit>,
+    pass: PassThrough,
+}
+
+impl Logic for CustomCrossing {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, fast_clock, launch);
+        dff_setup!(self, slow_clock, capture);
+        self.launch.d.next = self.data_in.val();
+        self.pass.sig_in.next = self.launch.q.val();
+        self.capture.d.next = self.pass.sig_out.val();
+        self.data_out.next = self.capture.q.val();
+    }
+}
+
+#[test]
+fn test_cdc_finds_crossing_through_custom_verilog() {
+    let mut uut = CustomCrossing::default();
+    uut.connect_all();
+    match check_cdc(&uut) {
+        Err(CheckError::ClockDomainCrossings(paths)) => {
+            assert_eq!(paths.len(), 1);
+            assert_eq!(paths[0].from, "uut.launch.dff");
+            assert_eq!(paths[0].to, "uut.capture.dff");
+        }
+        x => panic!("Expected a clock domain crossing, got {:?}", x),
+    }
+}
+
+#[test]
+fn test_cdc_accepts_synchronizers() {
+    let mut uut: AsynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_cdc(&uut).is_ok());
+    let mut uut: VectorSynchronizer<Bits<8>> = Default::default();
+    uut.connect_all();
+    assert!(check_cdc(&uut).is_ok());
+    let mut uut: CrossWidenFIFO<4, 5, 6, 16, 3, 4> = Default::default();
+    uut.connect_all();
+    assert!(check_cdc(&uut).is_ok());
+    let mut uut: CrossNarrowFIFO<16, 3, 4, 4, 5, 6> = Default::default();
+    uut.connect_all();
+    assert!(check_cdc(&uut).is_ok());
+}
+
+#[derive(LogicBlock, Default)]
+struct WrongDomainFIFO {
+    pub read_clock: Signal<In, Clock>,
+    pub write_clock: Signal<In, Clock>,
+    pub data_in: Signal<In, Bits<8>>,
+    pub write: Signal<In, Bit>,
+    launch_data: DFF<Bits<8>>,
+    launch_write: DFF<Bit>,
+    fifo: AsynchronousFIFO<Bits<8>, 4, 5, 1>,
+}
+
+impl Logic for WrongDomainFIFO {
+    #[hdl_gen]
+    fn update(&mut self) {
+        // The write side of the FIFO is driven from the read clock domain
+        dff_setup!(self, read_clock, launch_data, launch_write);
+        self.launch_data.d.next = self.data_in.val();
+        self.launch_write.d.next = self.write.val();
+        self.fifo.read_clock.next = self.read_clock.val();
+        self.fifo.write_clock.next = self.write_clock.val();
+        self.fifo.data_in.next = self.launch_data.q.val();
+        self.fifo.write.next = self.launch_write.q.val();
+        self.fifo.read.next = false;
+    }
+}
+
+#[test]
+fn test_cdc_finds_fifo_driven_from_the_wrong_domain() {
+    let mut uut = WrongDomainFIFO::default();
+    uut.connect_all();
+    match check_cdc(&uut) {
+        Err(CheckError::ClockDomainCrossings(paths)) => {
+            assert!(!paths.is_empty());
+            for path in &paths {
+                assert_eq!(path.launch_clock, "uut.read_clock");
+                assert_eq!(path.capture_clock, "uut.write_clock");
+            }
+            assert!(paths.iter().any(|x| x.from == "uut.launch_write.dff"));
+        }
+        x => panic!("Expected a clock domain crossing, got {:?}", x),
+    }
+}
+
+#[test]
+fn test_cdc_follows_clocks_through_interfaces() {
+    // The bus clock reaches the port through a joined interface
+    let mut uut: MISOFIFOPort<16, 3, 4, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_cdc(&uut).is_ok());
+}
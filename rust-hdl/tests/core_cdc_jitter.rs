@@ -0,0 +1,236 @@
+use rand::Rng;
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct SyncTest {
+    pub clock: Signal<In, Clock>,
+    pub sync: BitSynchronizer,
+}
+
+impl Logic for SyncTest {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, sync);
+    }
+}
+
+// The time at which a rising input reaches the output of a synchronizer
+fn synchronizer_output_time(jitter: Option<CDCJitter>) -> u64 {
+    let mut sim = Simulation::new();
+    if let Some(jitter) = jitter {
+        sim.set_cdc_jitter(jitter);
+    }
+    sim.add_clock(5, |x: &mut Box<SyncTest>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut sim: Sim<SyncTest>| {
+        let mut x = sim.init()?;
+        wait_clock_cycles!(sim, clock, x, 4);
+        x.sync.sig_in.next = true;
+        wait_clock_cycles!(sim, clock, x, 8);
+        sim.done(x)
+    });
+    let latency = std::sync::Arc::new(std::sync::Mutex::new(0));
+    let record = latency.clone();
+    sim.add_testbench(move |mut sim: Sim<SyncTest>| {
+        let mut x = sim.init()?;
+        x = sim.watch(|x| x.sync.sig_out.val(), x)?;
+        *record.lock().unwrap() = sim.time();
+        sim.done(x)
+    });
+    let mut uut = SyncTest::default();
+    uut.sync.sig_in.connect();
+    sim.run(Box::new(uut), 10_000).unwrap();
+    let time = *latency.lock().unwrap();
+    time
+}
+
+#[test]
+fn test_late_resolution_delays_bit_synchronizer() {
+    let perfect = synchronizer_output_time(None);
+    let late = synchronizer_output_time(Some(CDCJitter {
+        late: 1.0,
+        skew: 0.0,
+    }));
+    // The change is resolved one clock (10 ps) late
+    assert_eq!(late, perfect + 10);
+}
+
+#[derive(LogicBlock, Default)]
+struct CrossingTest {
+    pub clock: Signal<In, Clock>,
+    pub sig_in: Signal<In, Bits<8>>,
+    pub sig_out: Signal<Out, Bits<8>>,
+    cross: ClockCrossing<Bits<8>>,
+}
+
+impl Logic for CrossingTest {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.sig_out.next = cdc_cross!(self, clock, cross, sig_in);
+    }
+}
+
+#[test]
+fn test_skew_on_a_bus_crossing() {
+    let mut sim = Simulation::new();
+    sim.set_cdc_jitter(CDCJitter {
+        late: 0.0,
+        skew: 1.0,
+    });
+    sim.add_clock(5, |x: &mut Box<CrossingTest>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut sim: Sim<CrossingTest>| {
+        let mut x = sim.init()?;
+        wait_clock_cycle!(sim, clock, x);
+        x.sig_in.next = 0x5A.into();
+        x = sim.wait(1, x)?;
+        // Every changed bit is sampled late, so the edge after the change sees the old value
+        sim_assert_eq!(sim, x.sig_out.val(), 0, x);
+        wait_clock_cycle!(sim, clock, x);
+        sim_assert_eq!(sim, x.sig_out.val(), 0x5A, x);
+        sim.done(x)
+    });
+    let mut uut = CrossingTest::default();
+    uut.sig_in.connect();
+    sim.run(Box::new(uut), 1_000).unwrap();
+}
+
+#[test]
+fn test_crossing_is_a_wire_in_verilog() {
+    let mut uut = CrossingTest::default();
+    uut.connect_all();
+    let vlog = generate_verilog(&uut);
+    assert!(vlog.contains("sig_out = sig_in;"));
+    assert!(!vlog.contains("cross"));
+}
+
+// The jitter is only simulated, so the synchronizer is the same two flops in Verilog
+const BIT_SYNCHRONIZER_VERILOG: &str = r#"
+module top(sig_in,sig_out,clock);
+
+    // Module arguments
+    input wire  sig_in;
+    output reg  sig_out;
+    input wire  clock;
+
+    // Stub signals
+    reg  dff0$d;
+    wire  dff0$q;
+    reg  dff0$clock;
+    reg  dff1$d;
+    wire  dff1$q;
+    reg  dff1$clock;
+
+    // Sub module instances
+    top$dff0 dff0(
+        .d(dff0$d),
+        .q(dff0$q),
+        .clock(dff0$clock)
+    );
+    top$dff1 dff1(
+        .d(dff1$d),
+        .q(dff1$q),
+        .clock(dff1$clock)
+    );
+
+    // Update code
+    always @(*) begin
+        dff0$clock = clock;
+        dff1$clock = clock;
+        dff0$d = dff0$q;
+        dff1$d = dff1$q;
+        dff0$d = sig_in;
+        dff1$d = dff0$q;
+        sig_out = dff1$q;
+    end
+
+endmodule // top
+"#;
+
+#[test]
+fn test_synchronizer_verilog_is_unchanged() {
+    let mut uut = BitSynchronizer::default();
+    uut.connect_all();
+    let vlog = generate_verilog(&uut);
+    let top = vlog.split("endmodule // top\n").next().unwrap();
+    let lines = |x: &str| {
+        x.trim()
+            .lines()
+            .map(|x| x.trim_end().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        lines(&format!("{}endmodule // top", top)),
+        lines(BIT_SYNCHRONIZER_VERILOG)
+    );
+    assert!(!vlog.contains("cross"));
+    let mut uut: SyncReceiver<Bits<8>> = Default::default();
+    uut.connect_all();
+    let vlog = generate_verilog(&uut);
+    assert!(vlog.contains("hold$d = sig_cross;"));
+    assert!(!vlog.contains("cross$"));
+}
+
+#[derive(LogicBlock, Default)]
+struct AsynchronousFIFOTest {
+    pub read_clock: Signal<In, Clock>,
+    pub write_clock: Signal<In, Clock>,
+    pub fifo: AsynchronousFIFO<Bits<16>, 4, 5, 4>,
+}
+
+impl Logic for AsynchronousFIFOTest {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.fifo.write_clock.next = self.write_clock.val();
+        self.fifo.read_clock.next = self.read_clock.val();
+    }
+}
+
+#[test]
+fn test_asynchronous_fifo_is_robust_to_cdc_jitter() {
+    let regression = Regression::new(200_000, |_seed| {
+        let mut sim = Simulation::new();
+        sim.set_cdc_jitter(CDCJitter::default());
+        let data = (0..256)
+            .map(|_| sim_rng().gen::<u16>().to_bits())
+            .collect::<Vec<Bits<16>>>();
+        let expected = data.clone();
+        sim.add_clock(5, |x: &mut Box<AsynchronousFIFOTest>| {
+            x.read_clock.next = !x.read_clock.val()
+        });
+        sim.add_clock(4, |x: &mut Box<AsynchronousFIFOTest>| {
+            x.write_clock.next = !x.write_clock.val()
+        });
+        sim.add_testbench(move |mut sim: Sim<AsynchronousFIFOTest>| {
+            let mut x = sim.init()?;
+            wait_clock_true!(sim, write_clock, x);
+            for sample in &data {
+                x = sim.watch(|x| !x.fifo.full.val(), x)?;
+                x.fifo.data_in.next = *sample;
+                x.fifo.write.next = true;
+                wait_clock_cycle!(sim, write_clock, x);
+                x.fifo.write.next = false;
+            }
+            sim.done(x)
+        });
+        sim.add_testbench(move |mut sim: Sim<AsynchronousFIFOTest>| {
+            let mut x = sim.init()?;
+            wait_clock_true!(sim, read_clock, x);
+            for sample in &expected {
+                x = sim.watch(|x| !x.fifo.empty.val(), x)?;
+                sim_assert_eq!(sim, x.fifo.data_out.val(), *sample, x);
+                x.fifo.read.next = true;
+                wait_clock_cycle!(sim, read_clock, x);
+                x.fifo.read.next = false;
+            }
+            sim_assert!(sim, !x.fifo.underflow.val(), x);
+            sim_assert!(sim, !x.fifo.overflow.val(), x);
+            sim.done(x)
+        });
+        let mut uut = AsynchronousFIFOTest::default();
+        uut.fifo.read.connect();
+        uut.fifo.data_in.connect();
+        uut.fifo.write.connect();
+        (sim, Box::new(uut))
+    });
+    let report = regression.run_seeds(0..4);
+    assert!(report.is_success(), "{}", report);
+}
@@ -0,0 +1,161 @@
+use rand::Rng;
+use rust_hdl::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[derive(LogicBlock, Clone, Default)]
+struct Booter {
+    pub clock: Signal<In, Clock>,
+    pub data: Signal<In, Bits<16>>,
+    pub ready: Signal<Out, Bit>,
+    pub total: Signal<Out, Bits<16>>,
+    boot: DFF<Bits<16>>,
+    sum: DFF<Bits<16>>,
+}
+
+impl Logic for Booter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, boot, sum);
+        self.ready.next = self.boot.q.val() == 1000;
+        if !self.ready.val() {
+            self.boot.d.next = self.boot.q.val() + 1;
+        } else {
+            self.sum.d.next = self.sum.q.val() + self.data.val();
+        }
+        self.total.next = self.sum.q.val();
+    }
+}
+
+type Log = Arc<Mutex<Vec<(u64, u16)>>>;
+
+fn booter_sim() -> Simulation<Booter> {
+    let mut sim = Simulation::new();
+    sim.add_phased_clock(5000, 1000, |x: &mut Box<Booter>| {
+        x.clock.next = !x.clock.val()
+    });
+    sim
+}
+
+fn scenario(sim: &mut Simulation<Booter>, data: u16, log: Log, warm_up: bool) {
+    sim.add_testbench(move |mut sim: Sim<Booter>| {
+        let mut x = sim.init()?;
+        if warm_up {
+            x = sim.watch(|x| x.ready.val(), x)?;
+        }
+        x.data.next = (data as u64).into();
+        for _ in 0..10 {
+            wait_clock_cycle!(sim, clock, x);
+            log.lock()
+                .unwrap()
+                .push((sim.time(), x.total.val().index() as u16));
+        }
+        sim.done(x)
+    });
+}
+
+fn warm_up(sim: &mut Simulation<Booter>) {
+    sim.add_testbench(|mut sim: Sim<Booter>| {
+        let mut x = sim.init()?;
+        x = sim.watch(|x| x.ready.val(), x)?;
+        sim.done(x)
+    });
+}
+
+fn booter() -> Box<Booter> {
+    let mut uut = Booter::default();
+    uut.connect_all();
+    Box::new(uut)
+}
+
+#[test]
+fn test_fork_matches_full_run() {
+    let full: Log = Default::default();
+    let mut sim = booter_sim();
+    scenario(&mut sim, 3, full.clone(), true);
+    sim.run(booter(), 100 * sim_time::ONE_MICROSECOND).unwrap();
+
+    let mut sim = booter_sim();
+    warm_up(&mut sim);
+    let checkpoint = sim
+        .run_to_checkpoint(booter(), 100 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    assert!(checkpoint.circuit().ready.val());
+    let forked: Log = Default::default();
+    let (mut sim, uut) = checkpoint.fork();
+    scenario(&mut sim, 3, forked.clone(), false);
+    sim.run(uut, 100 * sim_time::ONE_MICROSECOND).unwrap();
+    assert_eq!(*full.lock().unwrap(), *forked.lock().unwrap());
+    assert_eq!(full.lock().unwrap().len(), 10);
+}
+
+#[test]
+fn test_forks_are_independent() {
+    let mut sim = booter_sim();
+    warm_up(&mut sim);
+    let checkpoint = sim
+        .run_to_checkpoint(booter(), 100 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let boot_time = checkpoint.time();
+    let mut totals = vec![];
+    for data in [1, 5] {
+        let log: Log = Default::default();
+        let (mut sim, uut) = checkpoint.fork();
+        scenario(&mut sim, data, log.clone(), false);
+        sim.run(uut, 100 * sim_time::ONE_MICROSECOND).unwrap();
+        let log = log.lock().unwrap();
+        assert!(log.iter().all(|(t, _)| *t > boot_time));
+        totals.push(log.last().unwrap().1);
+    }
+    assert_eq!(totals[1], totals[0] * 5);
+    // The checkpoint itself is untouched by the forks
+    assert_eq!(checkpoint.circuit().total.val(), 0);
+}
+
+#[test]
+fn test_restore_with_cooperative_executor() {
+    let mut sim = booter_sim();
+    sim.set_executor(Executor::Cooperative);
+    warm_up(&mut sim);
+    let checkpoint = sim
+        .run_to_checkpoint(booter(), 100 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let log: Log = Default::default();
+    let (mut sim, uut) = checkpoint.restore();
+    scenario(&mut sim, 2, log.clone(), false);
+    sim.run(uut, 100 * sim_time::ONE_MICROSECOND).unwrap();
+    assert_eq!(log.lock().unwrap().last().unwrap().1, 20);
+}
+
+#[test]
+fn test_checkpoint_max_time() {
+    let mut sim = booter_sim();
+    warm_up(&mut sim);
+    assert!(matches!(
+        sim.run_to_checkpoint(booter(), sim_time::ONE_MICROSECOND),
+        Err(SimError::MaxTimeReached)
+    ));
+}
+
+#[test]
+fn test_forks_keep_the_seed() {
+    let mut sim = booter_sim();
+    sim.set_seed(0xdead_beef);
+    warm_up(&mut sim);
+    let checkpoint = sim
+        .run_to_checkpoint(booter(), 100 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let mut draws = vec![];
+    for _ in 0..2 {
+        let log: Log = Default::default();
+        let (mut sim, uut) = checkpoint.fork();
+        let draw = log.clone();
+        sim.add_testbench(move |sim: Sim<Booter>| {
+            let x = sim.init()?;
+            draw.lock().unwrap().push((0, sim_rng().gen::<u16>()));
+            sim.done(x)
+        });
+        sim.run(uut, 100 * sim_time::ONE_MICROSECOND).unwrap();
+        draws.push(log.lock().unwrap()[0].1);
+    }
+    assert_eq!(draws[0], draws[1]);
+}
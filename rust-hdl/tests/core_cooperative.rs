@@ -0,0 +1,144 @@
+use rust_hdl::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[derive(LogicBlock, Default)]
+struct Accumulator {
+    pub clock: Signal<In, Clock>,
+    pub data: Signal<In, Bits<16>>,
+    pub strobe: Signal<In, Bit>,
+    pub total: Signal<Out, Bits<16>>,
+    sum: DFF<Bits<16>>,
+}
+
+impl Logic for Accumulator {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, sum);
+        if self.strobe.val() {
+            self.sum.d.next = self.sum.q.val() + self.data.val();
+        }
+        self.total.next = self.sum.q.val();
+    }
+}
+
+fn accumulator_sim(
+    executor: Executor,
+    threads: Arc<Mutex<Vec<std::thread::ThreadId>>>,
+) -> Simulation<Accumulator> {
+    let mut sim = Simulation::new();
+    sim.add_clock(5000, |x: &mut Box<Accumulator>| {
+        x.clock.next = !x.clock.val()
+    });
+    let ids = threads.clone();
+    sim.add_testbench(move |mut sim: Sim<Accumulator>| {
+        ids.lock().unwrap().push(std::thread::current().id());
+        let mut x = sim.init()?;
+        wait_clock_cycle!(sim, clock, x);
+        for i in 0..10 {
+            x.data.next = i.into();
+            x.strobe.next = true;
+            wait_clock_cycle!(sim, clock, x);
+        }
+        x.strobe.next = false;
+        sim.done(x)
+    });
+    let ids = threads.clone();
+    sim.add_testbench(move |mut sim: Sim<Accumulator>| {
+        ids.lock().unwrap().push(std::thread::current().id());
+        let mut x = sim.init()?;
+        x = sim.watch(|x| x.total.val() == 45, x)?;
+        wait_clock_cycles!(sim, clock, x, 4);
+        sim_assert_eq!(sim, x.total.val(), 45, x);
+        sim.done(x)
+    });
+    sim.set_executor(executor);
+    sim
+}
+
+fn run_accumulator(executor: Executor) -> (Vec<u8>, Vec<std::thread::ThreadId>) {
+    let threads = Arc::new(Mutex::new(vec![]));
+    let mut uut = Accumulator::default();
+    uut.connect_all();
+    let mut vcd = vec![];
+    accumulator_sim(executor, threads.clone())
+        .run_traced(Box::new(uut), 1_000_000, &mut vcd)
+        .unwrap();
+    let ids = threads.lock().unwrap().clone();
+    (vcd, ids)
+}
+
+#[test]
+fn test_cooperative_matches_threaded() {
+    let (threaded, _) = run_accumulator(Executor::Threaded);
+    let (cooperative, _) = run_accumulator(Executor::Cooperative);
+    assert_eq!(threaded, cooperative);
+}
+
+#[test]
+fn test_cooperative_runs_on_one_thread() {
+    let (_, ids) = run_accumulator(Executor::Cooperative);
+    assert_eq!(ids.len(), 2);
+    assert!(ids.iter().all(|x| *x == std::thread::current().id()));
+    let (_, ids) = run_accumulator(Executor::Threaded);
+    assert!(ids.iter().all(|x| *x != std::thread::current().id()));
+}
+
+#[test]
+fn test_cooperative_is_deterministic() {
+    let (first, _) = run_accumulator(Executor::Cooperative);
+    for _ in 0..5 {
+        assert_eq!(run_accumulator(Executor::Cooperative).0, first);
+    }
+}
+
+#[test]
+fn test_cooperative_halt() {
+    let mut sim = simple_sim!(Accumulator, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 4);
+        sim_assert!(ep, x.total.val() == 1, x);
+        ep.done(x)
+    });
+    sim.set_executor(Executor::Cooperative);
+    let mut uut = Accumulator::default();
+    uut.connect_all();
+    assert!(matches!(
+        sim.run(Box::new(uut), 1_000_000),
+        Err(SimError::SimHalted(_))
+    ));
+}
+
+#[test]
+fn test_cooperative_panic() {
+    let mut sim = simple_sim!(Accumulator, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 4);
+        if x.total.val() == 0 {
+            panic!("Testbench failure");
+        }
+        ep.done(x)
+    });
+    sim.set_executor(Executor::Cooperative);
+    let mut uut = Accumulator::default();
+    uut.connect_all();
+    match sim.run(Box::new(uut), 1_000_000) {
+        Err(SimError::SimPanic(failure)) => assert_eq!(failure.message, "Testbench failure"),
+        x => panic!("Expected a panic, got {:?}", x),
+    }
+}
+
+#[test]
+fn test_cooperative_max_time() {
+    let mut sim = simple_sim!(Accumulator, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 1000);
+        ep.done(x)
+    });
+    sim.set_executor(Executor::Cooperative);
+    let mut uut = Accumulator::default();
+    uut.connect_all();
+    assert_eq!(
+        sim.run(Box::new(uut), 100_000),
+        Err(SimError::MaxTimeReached)
+    );
+}
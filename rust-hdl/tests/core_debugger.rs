@@ -0,0 +1,86 @@
+use rust_hdl::prelude::*;
+use std::io::{Cursor, Write};
+use std::sync::{Arc, Mutex};
+
+#[derive(LogicBlock, Default)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    store: DFF<Bits<8>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        self.store.d.next = self.store.q.val() + 1;
+        self.count.next = self.store.q.val();
+    }
+}
+
+// A writer that keeps what the debugger writes, so the test can inspect it
+#[derive(Clone, Default)]
+struct Transcript(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Transcript {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Transcript {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+fn debug_session(debugger: Debugger<Counter>) -> Result<(), SimError> {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<Counter>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut ep: Sim<Counter>| {
+        let x = ep.init()?;
+        let x = ep.wait(1_000, x)?;
+        ep.done(x)
+    });
+    sim.add_monitor(debugger);
+    sim.run(Box::new(Counter::default()), 10_000)
+}
+
+#[test]
+fn test_debugger_breakpoint_print_and_step() {
+    let transcript = Transcript::default();
+    let script = "p count store.q\nn 2\np uut.count\nd five\nc\n";
+    let debugger = Debugger::with_io(Cursor::new(script), transcript.clone())
+        .breakpoint("five", |x: &Counter| x.count.val() == 5);
+    debug_session(debugger).unwrap();
+    let text = transcript.text();
+    assert!(text.contains("Breakpoint five hit at"), "{}", text);
+    assert!(text.contains("uut.count = 8'h5"), "{}", text);
+    assert!(text.contains("uut.store.q = 8'h5"), "{}", text);
+    assert!(text.contains("uut.count = 8'h7"), "{}", text);
+    // The breakpoint was disabled, so the simulation ran to the end
+    assert_eq!(text.matches("Breakpoint").count(), 1, "{}", text);
+}
+
+#[test]
+fn test_debugger_break_on_start_and_detach() {
+    let transcript = Transcript::default();
+    let script = "t\nl store\nfoo\nn clk\nn count\ns 3\nq\n";
+    let debugger = Debugger::with_io(Cursor::new(script), transcript.clone())
+        .break_on_start()
+        .breakpoint("never", |x: &Counter| x.count.val() == 200);
+    debug_session(debugger).unwrap();
+    let text = transcript.text();
+    assert!(text.contains("(sim @ 0 ps) 0 ps"), "{}", text);
+    assert!(text.contains("uut.store.d\n"), "{}", text);
+    assert!(text.contains("Unknown command foo"), "{}", text);
+    // A clock that does not exist (or is not a clock) is rejected when the command is given
+    assert!(text.contains("No signal named clk"), "{}", text);
+    assert!(text.contains("uut.count is not a clock"), "{}", text);
+    // After 3 steps, the debugger stops again, and then detaches on quit
+    assert_eq!(text.matches("(sim @").count(), 7, "{}", text);
+}
@@ -0,0 +1,193 @@
+use rust_hdl::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(LogicBlock, Default)]
+struct CrossingTest {
+    pub clock1: Signal<In, Clock>,
+    pub clock2: Signal<In, Clock>,
+    pub sender: SyncSender<Bits<8>>,
+    pub recv: SyncReceiver<Bits<8>>,
+    pub counter: DFF<Bits<16>>,
+}
+
+impl Logic for CrossingTest {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock1, sender);
+        clock!(self, clock2, recv);
+        dff_setup!(self, clock2, counter);
+        self.sender.ack_in.next = self.recv.ack_out.val();
+        self.recv.flag_in.next = self.sender.flag_out.val();
+        self.recv.sig_cross.next = self.sender.sig_cross.val();
+        if self.recv.update.val() {
+            self.counter.d.next = self.counter.q.val() + 1;
+        }
+    }
+}
+
+fn run_crossing_test(scheduler: Scheduler) -> Vec<u8> {
+    let mut uut = CrossingTest::default();
+    uut.sender.sig_in.connect();
+    uut.sender.send.connect();
+    uut.connect_all();
+    let mut sim = Simulation::new();
+    sim.set_scheduler(scheduler);
+    sim.add_clock(5, |x: &mut Box<CrossingTest>| {
+        x.clock2.next = !x.clock2.val()
+    });
+    sim.add_clock(9, |x: &mut Box<CrossingTest>| {
+        x.clock1.next = !x.clock1.val()
+    });
+    sim.add_testbench(move |mut sim: Sim<CrossingTest>| {
+        let mut x = sim.init()?;
+        wait_clock_true!(sim, clock1, x);
+        for i in 0..50 {
+            x.sender.sig_in.next = i.into();
+            x.sender.send.next = true;
+            wait_clock_cycle!(sim, clock1, x);
+            x.sender.send.next = false;
+            x = sim.watch(|x| !x.sender.busy.val(), x)?;
+        }
+        sim.done(x)
+    });
+    sim.add_testbench(move |mut sim: Sim<CrossingTest>| {
+        let mut x = sim.init()?;
+        wait_clock_true!(sim, clock2, x);
+        for i in 0..50 {
+            x = sim.watch(|x| x.recv.update.val(), x)?;
+            sim_assert_eq!(sim, x.recv.sig_out.val(), i, x);
+            wait_clock_cycle!(sim, clock2, x);
+        }
+        sim_assert_eq!(sim, x.counter.q.val(), 50, x);
+        sim.done(x)
+    });
+    let mut vcd = vec![];
+    sim.run_traced(Box::new(uut), 100_000, &mut vcd).unwrap();
+    vcd
+}
+
+// Counts the number of times it is evaluated
+#[derive(LogicBlock, Default)]
+struct Counted {
+    pub input: Signal<In, Bits<8>>,
+    pub output: Signal<Out, Bits<8>>,
+    _updates: Arc<AtomicUsize>,
+}
+
+impl Logic for Counted {
+    fn update(&mut self) {
+        self._updates.fetch_add(1, Ordering::Relaxed);
+        self.output.next = self.input.val() + 1;
+    }
+
+    fn connect(&mut self) {
+        self.output.connect();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct MostlyIdle {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    counter: DFF<Bits<8>>,
+    busy: Counted,
+    idle: [Counted; 8],
+}
+
+impl MostlyIdle {
+    fn new(busy: &Arc<AtomicUsize>, idle: &Arc<AtomicUsize>) -> Self {
+        let mut ret = Self::default();
+        ret.busy._updates = busy.clone();
+        for x in &mut ret.idle {
+            x._updates = idle.clone();
+        }
+        ret
+    }
+}
+
+impl Logic for MostlyIdle {
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        self.counter.d.next = self.counter.q.val() + 1;
+        self.busy.input.next = self.counter.q.val();
+        self.count.next = self.busy.output.val();
+        for x in &mut self.idle {
+            x.input.next = 42.into();
+        }
+    }
+
+    fn connect(&mut self) {
+        self.counter.clock.connect();
+        self.counter.d.connect();
+        self.busy.input.connect();
+        self.count.connect();
+        for x in &mut self.idle {
+            x.input.connect();
+        }
+    }
+}
+
+// Returns the final count, and the number of updates of the busy and idle blocks
+fn run_mostly_idle(scheduler: Scheduler) -> (usize, usize, usize) {
+    let busy = Arc::new(AtomicUsize::new(0));
+    let idle = Arc::new(AtomicUsize::new(0));
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut uut = MostlyIdle::new(&busy, &idle);
+    uut.connect_all();
+    let final_count = count.clone();
+    let mut sim = simple_sim!(MostlyIdle, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 100);
+        final_count.store(x.count.val().index(), Ordering::Relaxed);
+        ep.done(x)
+    });
+    sim.set_scheduler(scheduler);
+    sim.run(Box::new(uut), 10_000_000).unwrap();
+    (
+        count.load(Ordering::Relaxed),
+        busy.load(Ordering::Relaxed),
+        idle.load(Ordering::Relaxed),
+    )
+}
+
+#[test]
+fn test_event_driven_skips_quiescent_blocks() {
+    let (sweep_count, sweep_busy, sweep_idle) = run_mostly_idle(Scheduler::Sweep);
+    let (count, busy, idle) = run_mostly_idle(Scheduler::EventDriven);
+    assert_eq!(count, sweep_count);
+    // Every block is evaluated on every delta cycle of the sweep
+    assert_eq!(sweep_idle, sweep_busy * 8);
+    assert!(sweep_busy > 200);
+    // The busy block is only evaluated when the counter changes, and the idle blocks
+    // only until their input settles
+    assert!(busy <= 2 * 100 + 4);
+    assert!(idle <= 8 * 3);
+}
+
+#[test]
+fn test_event_driven_matches_sweep() {
+    let sweep = run_crossing_test(Scheduler::Sweep);
+    let event_driven = run_crossing_test(Scheduler::EventDriven);
+    assert_eq!(sweep, event_driven);
+}
+
+#[test]
+fn test_simulate_pending_matches_simulate() {
+    let mut sweep: Strobe<32> = Strobe::new(1000, 10.0);
+    let mut event_driven: Strobe<32> = Strobe::new(1000, 10.0);
+    sweep.enable.next = true;
+    event_driven.enable.next = true;
+    sweep.connect_all();
+    event_driven.connect_all();
+    // Every block has to be evaluated once before the pending changes can be tracked
+    assert!(simulate(&mut sweep, 10));
+    assert!(simulate(&mut event_driven, 10));
+    for clock in 0..10_000 {
+        sweep.clock.next = (clock % 2 == 0).into();
+        event_driven.clock.next = (clock % 2 == 0).into();
+        assert!(simulate(&mut sweep, 10));
+        assert!(simulate_pending(&mut event_driven, 10));
+        assert_eq!(sweep.strobe.val(), event_driven.strobe.val());
+    }
+}
@@ -0,0 +1,383 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Incrementer {
+    pub clock: Signal<In, Clock>,
+    pub data: Signal<In, FourState<8>>,
+    pub sum: Signal<Out, FourState<8>>,
+    store: DFF<FourState<8>>,
+}
+
+impl Logic for Incrementer {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        self.store.d.next = self.data.val();
+        self.sum.next = self.store.q.val() + 1;
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct TwoState {
+    pub clock: Signal<In, Clock>,
+    pub data: Signal<In, Bits<8>>,
+    pub sum: Signal<Out, Bits<8>>,
+    store: DFF<Bits<8>>,
+}
+
+impl Logic for TwoState {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        self.store.d.next = self.data.val();
+        self.sum.next = self.store.q.val() + 1;
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Gate {
+    pub enable: Signal<In, FourState<1>>,
+    pub data: Signal<In, FourState<8>>,
+    pub out: Signal<Out, FourState<8>>,
+    pub active: Signal<Out, FourState<1>>,
+}
+
+impl Logic for Gate {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.out.next = self.data.val();
+        self.active.next = self.enable.val();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct GatedIncrementer {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, FourState<1>>,
+    pub data: Signal<In, FourState<8>>,
+    pub sum: Signal<Out, FourState<8>>,
+    pub active: Signal<Out, FourState<1>>,
+    gate: Gate,
+}
+
+impl Logic for GatedIncrementer {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.gate.enable.next = self.enable.val();
+        self.gate.data.next = self.data.val();
+        self.sum.next = self.gate.out.val() + 1;
+        self.active.next = self.gate.active.val();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Selector {
+    pub sel: Signal<In, FourState<1>>,
+    pub code: Signal<In, FourState<2>>,
+    pub a: Signal<In, FourState<8>>,
+    pub b: Signal<In, FourState<8>>,
+    pub out: Signal<Out, FourState<8>>,
+    pub picked: Signal<Out, FourState<8>>,
+}
+
+impl Logic for Selector {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.out.next = mux(self.sel.val(), self.a.val(), self.b.val());
+        self.picked.next = select(
+            self.code.val(),
+            &[(0, self.a.val()), (1, self.b.val())],
+            self.a.val() & self.b.val(),
+        );
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Loader {
+    pub clock: Signal<In, Clock>,
+    pub load: Signal<In, FourState<1>>,
+    pub enable: Signal<In, FourState<1>>,
+    pub data: Signal<In, FourState<8>>,
+    pub value: Signal<Out, FourState<8>>,
+    pub count: Signal<Out, FourState<8>>,
+    store: DFF<FourState<8>>,
+    counter: DFF<FourState<8>>,
+}
+
+impl Logic for Loader {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store, counter);
+        self.store.d.next = mux(self.load.val(), self.data.val(), self.store.q.val());
+        if self.enable.val() == 1 {
+            self.counter.d.next = self.counter.q.val() + 1;
+        }
+        self.value.next = self.store.q.val();
+        self.count.next = self.counter.q.val();
+    }
+}
+
+#[derive(LogicBlock)]
+struct UnwrittenRAM {
+    pub clock: Signal<In, Clock>,
+    pub ram: RAM<FourState<8>, 4>,
+}
+
+impl Logic for UnwrittenRAM {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.ram.write_clock.next = self.clock.val();
+        self.ram.read_clock.next = self.clock.val();
+    }
+}
+
+#[test]
+fn test_four_state_operators() {
+    let a = FourState::<4>::from_str("10x1");
+    let b = FourState::<4>::from_str("0z11");
+    assert_eq!(a & b, FourState::from_str("00x1"));
+    assert_eq!(a | b, FourState::from_str("1x11"));
+    assert_eq!(a ^ b, FourState::from_str("1xx0"));
+    assert_eq!(!a, FourState::from_str("01x0"));
+    assert_eq!(a << 1, FourState::from_str("0x10"));
+    assert!((a + b).is_unknown());
+    assert_eq!(a.to_string(), "10x1");
+    let c: FourState<4> = 9.into();
+    assert_eq!(c + 3, 12);
+    assert_eq!(c.known(), Some(9.into()));
+    assert_eq!(a.known(), None);
+    assert_eq!(c.eq4(&9.into()), 1);
+    assert_eq!(c.eq4(&a), FourState::from_str("x"));
+    assert_eq!(c.eq4(&FourState::from_str("11x1")), 0);
+    assert!(a.eq4(&FourState::from_str("1011")).is_unknown());
+    // An unknown select merges the candidates
+    let lo: FourState<4> = 0b0011.into();
+    let hi: FourState<4> = 0b0110.into();
+    assert_eq!(mux(true.into(), hi, lo), hi);
+    assert_eq!(
+        mux(FourState::unknown(), hi, lo),
+        FourState::from_str("0x1x")
+    );
+    let arms = [(0, lo), (1, hi), (2, hi)];
+    assert_eq!(select(FourState::<2>::from(1), &arms, lo), hi);
+    assert_eq!(select(FourState::<2>::from(3), &arms, lo), lo);
+    // Only arms 1 and 3 (the default) can match 'x1'
+    assert_eq!(select(FourState::<2>::from_str("x1"), &arms, hi), hi);
+    assert_eq!(
+        select(FourState::<2>::from_str("1x"), &arms, lo),
+        FourState::from_str("0x1x")
+    );
+}
+
+#[test]
+fn test_four_state_vcd_shows_unknown_bits() {
+    let a = FourState::<4>::from_str("z0x1");
+    match a.vcd() {
+        VCDValue::Vector(v) => assert_eq!(
+            v,
+            vec![vcd::Value::Z, vcd::Value::V0, vcd::Value::X, vcd::Value::V1]
+        ),
+        _ => panic!("Expected a vector"),
+    }
+    // The Verilog treats unknown bits as zero
+    assert_eq!(a.verilog().as_biguint(), 1_u32.into());
+}
+
+#[test]
+fn test_x_checker_flags_uninitialized_register() {
+    let mut uut = Incrementer::default();
+    uut.connect_all();
+    let mut sim = simple_sim!(Incrementer, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.data.next = 41.into();
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.sum.val(), 42, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    let checker = sim.add_observer(XChecker::default());
+    sim.run(Box::new(uut), 100_000).unwrap();
+    let checker = checker.borrow();
+    assert_eq!(checker.violations().len(), 1);
+    let first = checker.first().unwrap();
+    assert_eq!(first.signal, "uut.sum");
+    assert_eq!(first.site, XSite::Output);
+    assert_eq!(first.time, 0);
+    assert_eq!(first.value, "xxxxxxxx");
+}
+
+#[test]
+fn test_two_state_simulation_is_unchanged() {
+    let mut uut = TwoState::default();
+    uut.connect_all();
+    let mut sim = simple_sim!(TwoState, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        // Before the first clock, the register reads as zero
+        sim_assert_eq!(ep, x.sum.val(), 1, x);
+        x.data.next = 41.into();
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.sum.val(), 42, x);
+        ep.done(x)
+    });
+    let checker = sim.add_observer(XChecker::default());
+    sim.run(Box::new(uut), 100_000).unwrap();
+    assert!(checker.borrow().is_clean());
+}
+
+#[test]
+fn test_four_state_is_off_by_default() {
+    let mut uut = Incrementer::default();
+    uut.connect_all();
+    let mut sim = simple_sim!(Incrementer, clock, 100_000_000, ep, {
+        let x = ep.init()?;
+        // Without four-state simulation, the register reads as zero
+        sim_assert_eq!(ep, x.sum.val(), 1, x);
+        ep.done(x)
+    });
+    let checker = sim.add_observer(XChecker::default());
+    sim.run(Box::new(uut), 100_000).unwrap();
+    assert!(checker.borrow().is_clean());
+}
+
+#[test]
+fn test_x_checker_flags_marked_enables() {
+    let mut uut = GatedIncrementer::default();
+    uut.connect_all();
+    let mut sim = simple_sim!(GatedIncrementer, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.data.next = 41.into();
+        wait_clock_cycle!(ep, clock, x);
+        x.enable.next = true.into();
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.sum.val(), 42, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    let checker = sim.add_observer(XChecker::default().enable("uut.gate.enable"));
+    sim.run(Box::new(uut), 100_000).unwrap();
+    let checker = checker.borrow();
+    let sites = checker
+        .violations()
+        .iter()
+        .map(|x| (x.signal.as_str(), x.site))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sites,
+        vec![
+            ("uut.sum", XSite::Output),
+            ("uut.active", XSite::Output),
+            ("uut.gate.enable", XSite::Enable)
+        ]
+    );
+}
+
+#[test]
+fn test_x_selector_gives_x_output_in_kernel() {
+    let mut uut = Selector::default();
+    uut.connect_all();
+    let vlog = generate_verilog(&uut);
+    assert!(vlog.contains("((sel) ? (a) : (b))"));
+    assert!(vlog.contains("((code == 32'h0) ? (a) : (((code == 32'h1) ? (b) : (a & b))))"));
+    let mut sim = Simulation::new();
+    sim.add_testbench(move |mut ep: Sim<Selector>| {
+        let mut x = ep.init()?;
+        x.a.next = 0x0F.into();
+        x.b.next = 0x3F.into();
+        x = ep.wait(1, x)?;
+        // The selectors are never driven, so they are X, and the candidates are merged
+        sim_assert_eq!(ep, x.out.val(), FourState::from_str("00xx1111"), x);
+        sim_assert_eq!(ep, x.picked.val(), FourState::from_str("00xx1111"), x);
+        x.sel.next = true.into();
+        x.code.next = FourState::from_str("x1");
+        x = ep.wait(1, x)?;
+        sim_assert_eq!(ep, x.out.val(), 0x0F, x);
+        // Only arm 1 and the default can match
+        sim_assert_eq!(ep, x.picked.val(), FourState::from_str("00xx1111"), x);
+        x.code.next = 1.into();
+        x = ep.wait(1, x)?;
+        sim_assert_eq!(ep, x.picked.val(), 0x3F, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    sim.run(Box::new(uut), 100).unwrap();
+}
+
+#[test]
+fn test_x_checker_finds_register_enables() {
+    let mut uut = Loader::default();
+    uut.connect_all();
+    let mut sim = simple_sim!(Loader, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.data.next = 42.into();
+        wait_clock_cycle!(ep, clock, x);
+        // An unknown enable leaves the register unknown
+        sim_assert!(ep, x.value.val().is_unknown(), x);
+        x.load.next = true.into();
+        x.enable.next = false.into();
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.value.val(), 42, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    let checker = sim.add_observer(XChecker::default());
+    sim.run(Box::new(uut), 100_000).unwrap();
+    let checker = checker.borrow();
+    let enables = checker
+        .violations()
+        .iter()
+        .filter(|x| x.site == XSite::Enable)
+        .map(|x| x.signal.as_str())
+        .collect::<Vec<_>>();
+    assert!(enables.contains(&"uut.load"));
+    assert!(enables.contains(&"uut.enable"));
+    assert!(!enables.contains(&"uut.data"));
+}
+
+#[test]
+fn test_unwritten_ram_reads_as_x() {
+    let mut uut = UnwrittenRAM {
+        clock: Default::default(),
+        ram: RAM::new(Default::default()),
+    };
+    uut.ram.write_enable.connect();
+    uut.ram.write_data.connect();
+    uut.ram.write_address.connect();
+    uut.ram.read_address.connect();
+    uut.connect_all();
+    let mut sim = simple_sim!(UnwrittenRAM, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.ram.read_address.next = 3.into();
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.ram.read_data.val(), FourState::unknown(), x);
+        x.ram.write_address.next = 3.into();
+        x.ram.write_data.next = 0x55.into();
+        x.ram.write_enable.next = true;
+        wait_clock_cycle!(ep, clock, x);
+        x.ram.write_enable.next = false;
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.ram.read_data.val(), 0x55, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    sim.run(Box::new(uut), 100_000).unwrap();
+}
+
+#[test]
+fn test_undriven_bus_reads_as_z() {
+    let mut uut = TristateBuffer::<FourState<8>>::default();
+    uut.connect_all();
+    let mut sim = Simulation::new();
+    sim.add_testbench(move |mut ep: Sim<TristateBuffer<FourState<8>>>| {
+        let mut x = ep.init()?;
+        x.write_data.next = 0x12.into();
+        x = ep.wait(1, x)?;
+        sim_assert_eq!(ep, x.bus.val(), FourState::undriven(), x);
+        sim_assert!(ep, x.read_data.val().is_unknown(), x);
+        x.write_enable.next = true;
+        x = ep.wait(1, x)?;
+        sim_assert_eq!(ep, x.read_data.val(), 0x12, x);
+        ep.done(x)
+    });
+    sim.set_four_state(true);
+    sim.run(Box::new(uut), 100).unwrap();
+}
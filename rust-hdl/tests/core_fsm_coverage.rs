@@ -0,0 +1,144 @@
+use rust_hdl::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Light {
+    Red,
+    Green,
+    Yellow,
+    Flashing,
+}
+
+#[derive(LogicBlock, Default)]
+struct TrafficLight {
+    pub clock: Signal<In, Clock>,
+    pub go: Signal<In, Bit>,
+    pub fault: Signal<In, Bit>,
+    state: DFF<Light>,
+}
+
+impl Logic for TrafficLight {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, state);
+        match self.state.q.val() {
+            Light::Red => {
+                if self.go.val() {
+                    self.state.d.next = Light::Green;
+                }
+            }
+            Light::Green => {
+                self.state.d.next = Light::Yellow;
+            }
+            Light::Yellow => {
+                self.state.d.next = Light::Red;
+            }
+            Light::Flashing => {
+                self.state.d.next = Light::Red;
+            }
+        }
+        if self.fault.val() {
+            self.state.d.next = Light::Flashing;
+        }
+    }
+}
+
+#[test]
+fn test_fsm_coverage() {
+    let mut sim = simple_sim!(TrafficLight, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.go.next = true;
+        wait_clock_cycles!(ep, clock, x, 5);
+        x.go.next = false;
+        wait_clock_cycles!(ep, clock, x, 5);
+        ep.done(x)
+    });
+    let coverage = sim.enable_fsm_coverage();
+    let mut uut = TrafficLight::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let coverage = coverage.borrow();
+    assert_eq!(coverage.reports().len(), 1);
+    let report = coverage.report("uut.state").unwrap();
+    assert_eq!(
+        report.states,
+        vec![
+            "Light::Red",
+            "Light::Green",
+            "Light::Yellow",
+            "Light::Flashing"
+        ]
+    );
+    assert_eq!(report.unvisited_states, vec!["Light::Flashing"]);
+    let pair = |a: &str, b: &str| (format!("Light::{}", a), format!("Light::{}", b));
+    assert_eq!(
+        report.taken_transitions,
+        vec![
+            pair("Red", "Green"),
+            pair("Green", "Yellow"),
+            pair("Yellow", "Red")
+        ]
+    );
+    // The fault can be raised in any state, and the light always recovers to red
+    assert_eq!(
+        report.untaken_transitions,
+        vec![
+            pair("Red", "Flashing"),
+            pair("Green", "Flashing"),
+            pair("Yellow", "Flashing"),
+            pair("Flashing", "Red")
+        ]
+    );
+    assert!(!coverage.is_complete());
+    let text = coverage.to_string();
+    assert!(
+        text.contains("states   3/4   transitions   3/7"),
+        "{}",
+        text
+    );
+    assert!(text.contains("unvisited state Light::Flashing"));
+    assert!(text.contains("untaken transition Light::Green -> Light::Flashing"));
+    // Transitions that were taken are not listed
+    assert!(!text.contains("Light::Yellow -> Light::Red"));
+}
+
+#[test]
+fn test_fsm_coverage_all_states_visited() {
+    let mut sim = simple_sim!(TrafficLight, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.go.next = true;
+        wait_clock_cycles!(ep, clock, x, 5);
+        x.fault.next = true;
+        wait_clock_cycle!(ep, clock, x);
+        x.fault.next = false;
+        wait_clock_cycles!(ep, clock, x, 5);
+        ep.done(x)
+    });
+    sim.enable_fsm_coverage();
+    let mut uut = TrafficLight::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    let report = sim.fsm_coverage().unwrap().report("uut.state").unwrap();
+    assert!(report.unvisited_states.is_empty());
+    assert!(report
+        .taken_transitions
+        .contains(&("Light::Flashing".to_string(), "Light::Red".to_string())));
+    // The fault was only raised in one of the other states
+    assert_eq!(report.untaken_transitions.len(), 2);
+    assert!(!report.is_complete());
+}
+
+#[test]
+fn test_fsm_coverage_is_opt_in() {
+    let mut sim = simple_sim!(TrafficLight, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 5);
+        ep.done(x)
+    });
+    let mut uut = TrafficLight::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    assert!(sim.fsm_coverage().is_none());
+}
@@ -0,0 +1,170 @@
+use rust_hdl::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Phase {
+    Idle,
+    Counting,
+    Done,
+}
+
+#[derive(LogicBlock, Default)]
+struct TraceMe {
+    pub clock: Signal<In, Clock>,
+    pub start: Signal<In, Bit>,
+    pub count: Signal<Out, Bits<16>>,
+    counter: DFF<Bits<16>>,
+    phase: DFF<Phase>,
+}
+
+impl Logic for TraceMe {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter, phase);
+        self.count.next = self.counter.q.val();
+        match self.phase.q.val() {
+            Phase::Idle => {
+                if self.start.val() {
+                    self.phase.d.next = Phase::Counting;
+                }
+            }
+            Phase::Counting => {
+                self.counter.d.next = self.counter.q.val() + 1;
+                if self.counter.q.val() == 1000 {
+                    self.phase.d.next = Phase::Done;
+                }
+            }
+            Phase::Done => {}
+        }
+    }
+}
+
+fn trace_me_sim() -> Simulation<TraceMe> {
+    simple_sim!(TraceMe, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.start.next = true;
+        wait_clock_cycles!(ep, clock, x, 1010);
+        sim_assert_eq!(ep, x.count.val(), 1001, x);
+        ep.done(x)
+    })
+}
+
+#[test]
+fn test_fst_trace_to_file() {
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    let path = vcd_path!("trace_me.fst");
+    trace_me_sim()
+        .run_to_file(Box::new(uut), 100 * sim_time::ONE_MICROSECOND, &path)
+        .unwrap();
+    let fst = std::fs::read(&path).unwrap();
+    // The first block of an FST file is always the header block
+    assert_eq!(fst[0], 0);
+}
+
+#[test]
+fn test_fst_is_smaller_than_vcd() {
+    let mut vcd = vec![];
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    trace_me_sim()
+        .run_traced_as(
+            Box::new(uut),
+            100 * sim_time::ONE_MICROSECOND,
+            &mut vcd,
+            TraceFormat::VCD,
+        )
+        .unwrap();
+    let mut fst = vec![];
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    trace_me_sim()
+        .run_traced_as(
+            Box::new(uut),
+            100 * sim_time::ONE_MICROSECOND,
+            &mut fst,
+            TraceFormat::FST,
+        )
+        .unwrap();
+    assert_eq!(fst[0], 0);
+    assert!(fst.len() < vcd.len());
+}
+
+// Read back the values written for each signal, keyed by the `.` separated path
+fn read_fst_values(path: &str) -> std::collections::HashMap<String, Vec<String>> {
+    let file = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+    let mut reader = fst_reader::FstReader::open(file).unwrap();
+    let mut scope = vec![];
+    let mut names = std::collections::HashMap::new();
+    reader
+        .read_hierarchy(|entry| match entry {
+            fst_reader::FstHierarchyEntry::Scope { name, .. } => scope.push(name),
+            fst_reader::FstHierarchyEntry::UpScope => {
+                scope.pop();
+            }
+            fst_reader::FstHierarchyEntry::Var { name, handle, .. } => {
+                names.insert(handle.get_index(), format!("{}.{}", scope.join("."), name));
+            }
+            _ => {}
+        })
+        .unwrap();
+    let mut values: std::collections::HashMap<String, Vec<String>> = Default::default();
+    reader
+        .read_signals(&fst_reader::FstFilter::all(), |_time, handle, value| {
+            if let fst_reader::FstSignalValue::String(x) = value {
+                values
+                    .entry(names[&handle.get_index()].clone())
+                    .or_default()
+                    .push(String::from_utf8_lossy(x).into());
+            }
+            Ok::<(), ()>(())
+        })
+        .unwrap();
+    values
+}
+
+#[test]
+fn test_fst_values_read_back() {
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    let path = vcd_path!("trace_me_values.fst");
+    trace_me_sim()
+        .run_to_file(Box::new(uut), 100 * sim_time::ONE_MICROSECOND, &path)
+        .unwrap();
+    let values = read_fst_values(&path);
+    let count = &values["uut.count"];
+    assert_eq!(count.last().unwrap(), &format!("{:016b}", 1001));
+    // The enum is written as the index of its label: Idle, Counting, then Done
+    let mut phases = values["uut.phase.q"].clone();
+    phases.dedup();
+    assert_eq!(phases, vec!["00", "01", "10"]);
+}
+
+#[test]
+fn test_fst_flushed_in_blocks_reads_back() {
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    let path = vcd_path!("trace_me_blocks.fst");
+    // A small threshold splits the trace into many value change blocks
+    let mut tracer = FSTTracer::new(&path).with_flush_threshold(1024);
+    trace_me_sim()
+        .run_with_tracer(Box::new(uut), 100 * sim_time::ONE_MICROSECOND, &mut tracer)
+        .unwrap();
+    let values = read_fst_values(&path);
+    let count = &values["uut.count"];
+    assert_eq!(count.last().unwrap(), &format!("{:016b}", 1001));
+    let mut counts = count.clone();
+    counts.dedup();
+    assert_eq!(counts.len(), 1002);
+}
+
+#[test]
+fn test_fst_write_error_is_returned() {
+    let mut uut = TraceMe::default();
+    uut.connect_all();
+    let result = trace_me_sim().run_to_file(
+        Box::new(uut),
+        100 * sim_time::ONE_MICROSECOND,
+        "no_such_directory/trace_me.fst",
+    );
+    assert!(matches!(result, Err(SimError::TraceFailed(_))));
+}
@@ -74,7 +74,7 @@ fn test_host_test_synthesizes() {
     let uut = make_host_test();
     let vlog = generate_verilog(&uut);
     yosys_validate("host", &vlog).unwrap();
-    check_timing(&make_host_test())
+    check_timing(&make_host_test());
 }
 
 #[test]
@@ -0,0 +1,200 @@
+use rand::Rng;
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock)]
+struct FIFOTestFixture {
+    feeder: LazyFIFOFeeder<Bits<8>, 10>,
+    fifo: SyncFIFO<Bits<8>, 4, 5, 1>,
+    reader: LazyFIFOReader<Bits<8>, 10>,
+    clock: Signal<In, Clock>,
+}
+
+impl Logic for FIFOTestFixture {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, feeder, fifo, reader);
+        FIFOWriteController::<Bits<8>>::join(&mut self.feeder.bus, &mut self.fifo.bus_write);
+        FIFOReadController::<Bits<8>>::join(&mut self.reader.bus, &mut self.fifo.bus_read);
+    }
+}
+
+impl FIFOTestFixture {
+    pub fn new(data: &[Bits<8>]) -> FIFOTestFixture {
+        FIFOTestFixture {
+            feeder: LazyFIFOFeeder::new(
+                data,
+                &(0..data.len()).map(|_| bursty_rand()).collect::<Vec<_>>(),
+            ),
+            fifo: SyncFIFO::default(),
+            reader: LazyFIFOReader::new(
+                data,
+                &(0..data.len()).map(|_| bursty_rand()).collect::<Vec<_>>(),
+            ),
+            clock: Default::default(),
+        }
+    }
+}
+
+fn fifo_sim() -> Simulation<FIFOTestFixture> {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<FIFOTestFixture>| {
+        x.clock.next = !x.clock.val()
+    });
+    sim.add_testbench(move |mut sim: Sim<FIFOTestFixture>| {
+        let mut x = sim.init()?;
+        wait_clock_true!(sim, clock, x);
+        x.feeder.start.next = true;
+        x.reader.start.next = true;
+        wait_clock_cycle!(sim, clock, x);
+        x = sim.watch(|x| x.feeder.done.val() & x.reader.done.val(), x)?;
+        wait_clock_cycle!(sim, clock, x);
+        sim.done(x)
+    });
+    sim
+}
+
+fn fifo_uut(data: &[Bits<8>]) -> Box<FIFOTestFixture> {
+    let mut uut = FIFOTestFixture::new(data);
+    uut.feeder.start.connect();
+    uut.reader.start.connect();
+    uut.connect_all();
+    Box::new(uut)
+}
+
+#[test]
+fn test_fifo_monitors_match_the_model() {
+    let data = (0..64)
+        .map(|_| rand::thread_rng().gen::<u8>().to_bits())
+        .collect::<Vec<Bits<8>>>();
+    let mut sim = fifo_sim();
+    let writes = sim.add_monitor(
+        FIFOWriteMonitor::new(
+            |x: &FIFOTestFixture| x.clock.val(),
+            |x: &FIFOTestFixture| &x.feeder.bus,
+        )
+        .expecting(data.clone()),
+    );
+    let reads = sim.add_monitor(
+        FIFOReadMonitor::new(
+            |x: &FIFOTestFixture| x.clock.val(),
+            |x: &FIFOTestFixture| &x.reader.bus,
+        )
+        .expecting(data.clone()),
+    );
+    sim.run(fifo_uut(&data), 100_000).unwrap();
+    let writes = writes.borrow();
+    let reads = reads.borrow();
+    assert!(writes.scoreboard().is_clean(), "{}", writes.scoreboard());
+    assert!(reads.scoreboard().is_clean(), "{}", reads.scoreboard());
+    assert_eq!(writes.scoreboard().matched(), data.len());
+    assert_eq!(reads.transactions().len(), data.len());
+    // Every item is read after it is written
+    for (write, read) in writes.transactions().iter().zip(reads.transactions()) {
+        assert_eq!(write.item, read.item);
+        assert!(write.time < read.time);
+    }
+}
+
+#[test]
+fn test_fifo_monitor_reports_mismatches() {
+    let data = (0..16).map(|x: u8| x.to_bits()).collect::<Vec<Bits<8>>>();
+    let mut model = data.clone();
+    model[5] = 0xFF.into();
+    model.push(0x10.into());
+    let mut sim = fifo_sim();
+    let reads = sim.add_monitor(
+        FIFOReadMonitor::new(
+            |x: &FIFOTestFixture| x.clock.val(),
+            |x: &FIFOTestFixture| &x.reader.bus,
+        )
+        .expecting(model),
+    );
+    sim.run(fifo_uut(&data), 100_000).unwrap();
+    let reads = reads.borrow();
+    let scoreboard = reads.scoreboard();
+    assert!(!scoreboard.is_clean());
+    assert_eq!(scoreboard.matched(), 15);
+    assert_eq!(scoreboard.missing().len(), 1);
+    match scoreboard.errors() {
+        [ScoreboardError::Mismatch {
+            time,
+            expected,
+            actual,
+        }] => {
+            assert_eq!(*time, reads.transactions()[5].time);
+            assert_eq!(*expected, 0xFF);
+            assert_eq!(*actual, 5);
+        }
+        x => panic!("Expected a single mismatch, got {:?}", x),
+    }
+    assert!(scoreboard.to_string().contains("Never saw"));
+}
+
+#[derive(LogicBlock)]
+struct MOSIPortTest {
+    bus: SoCBusController<16, 2>,
+    bridge: Bridge<16, 2, 2>,
+    port_a: MOSIPort<16>,
+    port_b: MOSIPort<16>,
+}
+
+impl Default for MOSIPortTest {
+    fn default() -> Self {
+        Self {
+            bus: Default::default(),
+            bridge: Bridge::new(["port_a", "port_b"]),
+            port_a: Default::default(),
+            port_b: Default::default(),
+        }
+    }
+}
+
+impl Logic for MOSIPortTest {
+    #[hdl_gen]
+    fn update(&mut self) {
+        SoCBusController::<16, 2>::join(&mut self.bus, &mut self.bridge.upstream);
+        SoCPortController::<16>::join(&mut self.bridge.nodes[0], &mut self.port_a.bus);
+        SoCPortController::<16>::join(&mut self.bridge.nodes[1], &mut self.port_b.bus);
+    }
+}
+
+#[test]
+fn test_soc_bus_monitor() {
+    let mut uut = MOSIPortTest::default();
+    uut.port_a.ready.connect();
+    uut.port_b.ready.connect();
+    uut.connect_all();
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<MOSIPortTest>| {
+        x.bus.clock.next = !x.bus.clock.val()
+    });
+    sim.add_testbench(move |mut sim: Sim<MOSIPortTest>| {
+        let mut x = sim.init()?;
+        x.port_a.ready.next = true;
+        x.port_b.ready.next = true;
+        wait_clock_cycles!(sim, bus.clock, x, 10);
+        bus_address_strobe!(sim, x, bus, 1);
+        bus_write_strobe!(sim, x, bus, 0xDEAD_u16);
+        bus_address_strobe!(sim, x, bus, 0);
+        bus_write_strobe!(sim, x, bus, 0xBEEF_u16);
+        wait_clock_cycle!(sim, bus.clock, x);
+        sim.done(x)
+    });
+    let monitor = sim.add_monitor(SoCBusMonitor::new(|x: &MOSIPortTest| &x.bus).expecting([
+        SoCBusTransaction::Address(1.into()),
+        SoCBusTransaction::Strobe {
+            address: 1.into(),
+            from_controller: 0xDEAD.into(),
+            to_controller: 0.into(),
+        },
+        SoCBusTransaction::Address(0.into()),
+        SoCBusTransaction::Strobe {
+            address: 0.into(),
+            from_controller: 0xBEEF.into(),
+            to_controller: 0.into(),
+        },
+    ]));
+    sim.run(Box::new(uut), 10_000).unwrap();
+    let monitor = monitor.borrow();
+    assert!(monitor.scoreboard().is_clean(), "{}", monitor.scoreboard());
+}
@@ -0,0 +1,88 @@
+use rust_hdl::core::check_error::{CheckError, PathedName};
+use rust_hdl::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Mode {
+    Off,
+    Slow,
+    Fast,
+}
+
+#[derive(LogicBlock, Default)]
+struct Leaky {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    pub speed: Signal<Out, Bits<4>>,
+    pub active: Signal<Out, Bit>,
+    pub led: Signal<Out, Bit>,
+    mode: DFF<Mode>,
+}
+
+impl Logic for Leaky {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, mode);
+        // Latch prevention
+        self.led.next = false;
+        if self.enable.val() {
+            self.active.next = true;
+            self.led.next = true;
+        }
+        match self.mode.q.val() {
+            Mode::Off => {
+                self.mode.d.next = Mode::Slow;
+            }
+            Mode::Slow => {
+                self.speed.next = 1.into();
+                self.mode.d.next = Mode::Fast;
+            }
+            Mode::Fast => {
+                self.speed.next = 2.into();
+                self.mode.d.next = Mode::Off;
+            }
+        }
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct LeakyTop {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    pub active: Signal<Out, Bit>,
+    leaky: Leaky,
+}
+
+impl Logic for LeakyTop {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, leaky);
+        self.leaky.enable.next = self.enable.val();
+        self.active.next = self.leaky.active.val() & self.leaky.led.val();
+    }
+}
+
+#[test]
+fn test_latches_are_reported() {
+    let mut uut = LeakyTop::default();
+    uut.connect_all();
+    assert_eq!(
+        check_latches(&uut),
+        Err(CheckError::InferredLatches(vec![
+            PathedName {
+                path: "uut$leaky".into(),
+                name: "active".into(),
+            },
+            PathedName {
+                path: "uut$leaky".into(),
+                name: "speed".into(),
+            },
+        ]))
+    );
+}
+
+#[test]
+fn test_async_fifo_has_no_latches() {
+    let mut uut: AsynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_latches(&uut).is_ok());
+}
@@ -0,0 +1,89 @@
+use rust_hdl::core::check_error::{CheckError, PathedName};
+use rust_hdl::prelude::*;
+
+#[derive(LogicInterface, Default)]
+struct CounterPort {
+    pub enable: Signal<In, Bit>,
+    pub count: Signal<Out, Bits<8>>,
+}
+
+#[derive(LogicBlock, Default)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub port: CounterPort,
+    pub overflow: Signal<Out, Bit>,
+    count: DFF<Bits<8>>,
+    scratch: Signal<Local, Bits<8>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, count);
+        self.scratch.next = self.count.q.val() + 1;
+        if self.port.enable.val() {
+            self.count.d.next = self.count.q.val() + 1;
+        }
+        self.port.count.next = self.count.q.val();
+        self.overflow.next = self.count.q.val().all();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Wrapper {
+    pub clock: Signal<In, Clock>,
+    pub port: CounterPort,
+    counter: Counter,
+}
+
+impl Logic for Wrapper {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, counter);
+        CounterPort::link(&mut self.port, &mut self.counter.port);
+        // Already driven by the link
+        self.port.count.next = 0.into();
+    }
+}
+
+#[test]
+fn test_multiple_drivers_are_reported() {
+    let mut uut = Wrapper::default();
+    uut.connect_all();
+    assert_eq!(
+        check_multiple_drivers(&uut),
+        Err(CheckError::MultipleDrivers(vec![PathedName {
+            path: "uut".into(),
+            name: "port$count".into(),
+        }]))
+    );
+}
+
+#[test]
+fn test_unused_signals_are_reported() {
+    let mut uut = Wrapper::default();
+    uut.connect_all();
+    assert_eq!(
+        check_unused_signals(&uut),
+        Err(CheckError::UnusedSignals(vec![
+            PathedName {
+                path: "uut$counter".into(),
+                name: "overflow".into(),
+            },
+            PathedName {
+                path: "uut$counter".into(),
+                name: "scratch".into(),
+            },
+        ]))
+    );
+}
+
+#[test]
+fn test_fifos_have_no_net_problems() {
+    let mut uut: SynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_multiple_drivers(&uut).is_ok());
+    let mut uut: AsynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_multiple_drivers(&uut).is_ok());
+}
@@ -0,0 +1,166 @@
+use rand::Rng;
+use rust_hdl::prelude::*;
+use std::sync::{Arc, Mutex};
+
+#[derive(LogicBlock, Default)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    counter: DFF<Bits<8>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        self.counter.d.next = self.counter.q.val() + 1;
+        self.count.next = self.counter.q.val();
+    }
+}
+
+// A testbench that waits a random number of cycles, and fails if that number is zero
+fn counter_regression(draws: Arc<Mutex<Vec<(u64, u8)>>>) -> Regression<Counter> {
+    Regression::new(1_000_000, move |seed| {
+        let draws = draws.clone();
+        let mut sim = Simulation::new();
+        sim.add_clock(5, |x: &mut Box<Counter>| x.clock.next = !x.clock.val());
+        sim.add_testbench(move |mut ep: Sim<Counter>| {
+            let mut x = ep.init()?;
+            let cycles = sim_rng().gen_range(0..4_u8);
+            draws.lock().unwrap().push((seed, cycles));
+            wait_clock_cycles!(ep, clock, x, cycles);
+            sim_assert!(ep, x.count.val() != 0, x);
+            ep.done(x)
+        });
+        let mut uut = Counter::default();
+        uut.connect_all();
+        (sim, Box::new(uut))
+    })
+}
+
+#[test]
+fn test_regression_is_reproducible() {
+    let draws = Arc::new(Mutex::new(vec![]));
+    let regression = counter_regression(draws.clone()).threads(4);
+    let first = regression.run_seeds(0..32);
+    let mut first_draws = std::mem::take(&mut *draws.lock().unwrap());
+    let second = regression.run_seeds(0..32);
+    let mut second_draws = std::mem::take(&mut *draws.lock().unwrap());
+    first_draws.sort();
+    second_draws.sort();
+    assert_eq!(first_draws, second_draws);
+    assert_eq!(first.failing_seeds(), second.failing_seeds());
+    // The results are reported in the order of the seeds
+    assert_eq!(
+        first.results.iter().map(|x| x.seed).collect::<Vec<_>>(),
+        (0..32).collect::<Vec<_>>()
+    );
+    // The seeds that fail are exactly those that waited for no cycles
+    let expected = first_draws
+        .iter()
+        .filter(|x| x.1 == 0)
+        .map(|x| x.0)
+        .collect::<Vec<_>>();
+    assert!(!expected.is_empty());
+    assert_eq!(first.failing_seeds(), expected);
+    assert!(!first.is_success());
+    assert!(first
+        .to_string()
+        .contains(&format!("{} of 32 seeds passed", 32 - expected.len())));
+}
+
+#[test]
+fn test_failing_seed_can_be_rerun_with_a_trace() {
+    let draws = Arc::new(Mutex::new(vec![]));
+    let regression = counter_regression(draws);
+    let report = regression.run_seeds(0..16);
+    let failure = report.failed().next().unwrap();
+    let path = vcd_path!("regression_rerun.vcd");
+    let rerun = regression.rerun_traced(failure.seed, &path);
+    assert_eq!(rerun, failure.result);
+    assert!(std::fs::metadata(&path).unwrap().len() > 0);
+}
+
+#[derive(LogicBlock)]
+struct FIFOTestFixture {
+    feeder: LazyFIFOFeeder<Bits<8>, 10>,
+    fifo: SyncFIFO<Bits<8>, 4, 5, 1>,
+    reader: LazyFIFOReader<Bits<8>, 10>,
+    clock: Signal<In, Clock>,
+}
+
+impl Logic for FIFOTestFixture {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, feeder, fifo, reader);
+        FIFOWriteController::<Bits<8>>::join(&mut self.feeder.bus, &mut self.fifo.bus_write);
+        FIFOReadController::<Bits<8>>::join(&mut self.reader.bus, &mut self.fifo.bus_read);
+    }
+}
+
+#[test]
+fn test_bursty_fifo_regression() {
+    let regression = Regression::new(1_000_000, |_seed| {
+        let data = (0..64)
+            .map(|_| sim_rng().gen::<u8>().to_bits())
+            .collect::<Vec<Bits<8>>>();
+        let mut uut = FIFOTestFixture {
+            feeder: LazyFIFOFeeder::new(&data, &bursty_vec(data.len())),
+            fifo: SyncFIFO::default(),
+            reader: LazyFIFOReader::new(&data, &bursty_vec(data.len())),
+            clock: Default::default(),
+        };
+        uut.feeder.start.connect();
+        uut.reader.start.connect();
+        uut.connect_all();
+        let mut sim = Simulation::new();
+        sim.add_clock(5, |x: &mut Box<FIFOTestFixture>| {
+            x.clock.next = !x.clock.val()
+        });
+        sim.add_testbench(move |mut sim: Sim<FIFOTestFixture>| {
+            let mut x = sim.init()?;
+            wait_clock_true!(sim, clock, x);
+            x.feeder.start.next = true;
+            x.reader.start.next = true;
+            wait_clock_cycle!(sim, clock, x);
+            x = sim.watch(|x| x.feeder.done.val() & x.reader.done.val(), x)?;
+            sim_assert!(sim, !x.reader.error.val(), x);
+            sim.done(x)
+        });
+        (sim, Box::new(uut))
+    });
+    let report = regression.run_seeds(0..4);
+    assert!(report.is_success(), "{}", report);
+}
+
+// Two testbenches that take turns drawing from the simulation generator
+fn interleaved_draws(executor: Executor) -> Vec<Vec<u32>> {
+    let draws = Arc::new(Mutex::new(vec![vec![]; 2]));
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<Counter>| x.clock.next = !x.clock.val());
+    for id in 0..2 {
+        let draws = draws.clone();
+        sim.add_testbench(move |mut ep: Sim<Counter>| {
+            let mut x = ep.init()?;
+            for _ in 0..8 {
+                draws.lock().unwrap()[id].push(sim_rng().gen::<u32>());
+                wait_clock_cycle!(ep, clock, x);
+            }
+            ep.done(x)
+        });
+    }
+    sim.set_seed(1234);
+    sim.set_executor(executor);
+    let mut uut = Counter::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 1_000_000).unwrap();
+    let draws = draws.lock().unwrap().clone();
+    draws
+}
+
+#[test]
+fn test_cooperative_testbenches_have_their_own_generators() {
+    let threaded = interleaved_draws(Executor::Threaded);
+    assert_ne!(threaded[0], threaded[1]);
+    assert_eq!(interleaved_draws(Executor::Cooperative), threaded);
+}
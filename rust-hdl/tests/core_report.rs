@@ -0,0 +1,97 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Sloppy {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    pub level: Signal<In, Bits<4>>,
+    pub active: Signal<Out, Bit>,
+    pub high: Signal<Out, Bit>,
+    pub count: Signal<Out, Bits<8>>,
+    scratch: Signal<Local, Bit>,
+}
+
+impl Logic for Sloppy {
+    #[hdl_gen]
+    fn update(&mut self) {
+        if self.enable.val() {
+            self.active.next = true;
+        }
+        self.high.next = self.level.val().get_bit(4);
+        self.scratch.next = self.enable.val();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct SloppyTop {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    pub active: Signal<Out, Bit>,
+    sloppy: Sloppy,
+}
+
+impl Logic for SloppyTop {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, sloppy);
+        self.sloppy.enable.next = self.enable.val();
+        self.active.next = self.sloppy.active.val() & self.sloppy.high.val();
+    }
+}
+
+#[test]
+fn test_report_collects_every_check() {
+    let mut uut = SloppyTop::default();
+    uut.connect_all();
+    let report = check_all_report(&uut);
+    let found = report
+        .violations
+        .iter()
+        .map(|x| (x.severity, x.check, x.signal.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        vec![
+            (CheckSeverity::Error, "open signal", "uut.sloppy.count"),
+            (CheckSeverity::Error, "open signal", "uut.sloppy.level"),
+            (CheckSeverity::Error, "inferred latch", "uut.sloppy.active"),
+            (CheckSeverity::Error, "width mismatch", "uut.sloppy.level"),
+            (CheckSeverity::Warning, "unused signal", "uut.sloppy.count"),
+            (
+                CheckSeverity::Warning,
+                "unused signal",
+                "uut.sloppy.scratch"
+            ),
+        ]
+    );
+    assert_eq!(report.errors(), 4);
+    assert_eq!(report.warnings(), 2);
+    assert!(!report.is_ok());
+}
+
+#[test]
+fn test_report_locates_blocks() {
+    let mut uut = SloppyTop::default();
+    uut.connect_all();
+    let report = check_all_report(&uut);
+    let latch = report
+        .violations
+        .iter()
+        .find(|x| x.check == "inferred latch")
+        .unwrap();
+    assert!(latch.block_type.ends_with("::Sloppy"));
+    let location = latch.location.unwrap();
+    assert!(location.file.ends_with("core_report.rs"));
+    assert_eq!(location.line, 16);
+    assert!(report
+        .to_string()
+        .contains("error: inferred latch `uut.sloppy.active`\n  --> "));
+}
+
+#[test]
+fn test_report_on_a_clean_design() {
+    let mut uut: SynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    let report = check_all_report(&uut);
+    assert!(report.is_ok());
+}
@@ -0,0 +1,83 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    store: DFF<Bits<8>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        self.store.d.next = self.store.q.val() + 1;
+        self.count.next = self.store.q.val();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Pair {
+    pub clock: Signal<In, Clock>,
+    pub first: Counter,
+    pub second: Counter,
+}
+
+impl Logic for Pair {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, first, second);
+    }
+}
+
+#[test]
+fn test_signal_paths_and_lookup() {
+    let uut = Pair::default();
+    let paths = signal_paths(&uut);
+    assert!(paths.contains(&"uut.first.store.q".to_string()));
+    assert!(paths.contains(&"uut.second.count".to_string()));
+    let handle = SignalHandle::find(&uut, "uut.second.store.d").unwrap();
+    assert_eq!(handle.bits(), 8);
+    assert_eq!(handle.type_name(), "Bits::<8>");
+    assert!(SignalHandle::find(&uut, "uut.third.count").is_none());
+    assert!(SignalHandle::find(&uut, "second.count").is_none());
+}
+
+fn force_and_release(scheduler: Scheduler) {
+    let mut sim = Simulation::new();
+    sim.set_scheduler(scheduler);
+    sim.add_clock(5, |x: &mut Box<Pair>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut ep: Sim<Pair>| {
+        let mut x = ep.init()?;
+        let q = SignalHandle::find(x.as_ref(), "uut.first.store.q").unwrap();
+        wait_clock_cycles!(ep, clock, x, 4);
+        sim_assert_eq!(ep, x.first.count.val(), 4, x);
+        // Stick the counter at 0x42, while the second one keeps counting
+        sim_assert!(ep, q.force(x.as_mut(), bits::<8>(0x42)), x);
+        wait_clock_cycles!(ep, clock, x, 3);
+        sim_assert_eq!(ep, x.first.count.val(), 0x42, x);
+        sim_assert_eq!(
+            ep,
+            q.read_as::<Bits<8>>(x.as_ref()),
+            Some(bits::<8>(0x42)),
+            x
+        );
+        sim_assert_eq!(ep, x.second.count.val(), 7, x);
+        // Once released, the counter picks up from the forced value
+        q.release(x.as_mut());
+        wait_clock_cycles!(ep, clock, x, 2);
+        sim_assert_eq!(ep, x.first.count.val(), 0x44, x);
+        ep.done(x)
+    });
+    sim.run(Box::new(Pair::default()), 1_000).unwrap();
+}
+
+#[test]
+fn test_force_and_release_internal_signal() {
+    force_and_release(Scheduler::Sweep);
+}
+
+#[test]
+fn test_force_and_release_internal_signal_event_driven() {
+    force_and_release(Scheduler::EventDriven);
+}
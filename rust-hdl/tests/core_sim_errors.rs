@@ -0,0 +1,127 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    counter: DFF<Bits<8>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        self.counter.d.next = self.counter.q.val() + 1;
+        self.count.next = self.counter.q.val();
+    }
+}
+
+fn run(sim: &mut Simulation<Counter>) -> Result<(), SimError> {
+    let mut uut = Counter::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 1_000_000)
+}
+
+#[test]
+fn test_failed_assertion_is_described() {
+    let mut sim = simple_sim!(Counter, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 3);
+        sim_assert_eq!(ep, x.count.val().index(), 5, x);
+        ep.done(x)
+    });
+    let failure = match run(&mut sim) {
+        Err(SimError::SimHalted(failure)) => failure,
+        x => panic!("Expected the simulation to halt, got {:?}", x),
+    };
+    assert_eq!(failure.message, "x.count.val().index() != 5");
+    assert_eq!(
+        failure.values,
+        vec![
+            ("x.count.val().index()".to_string(), "3".to_string()),
+            ("5".to_string(), "5".to_string())
+        ]
+    );
+    // The clock is worker 0, and the testbench is worker 1
+    assert_eq!(failure.testbench, Some(1));
+    assert_eq!(failure.time, 30_000);
+    assert!(failure
+        .location
+        .as_ref()
+        .unwrap()
+        .starts_with("rust-hdl/tests/core_sim_errors.rs:"));
+    let report = SimError::SimHalted(failure).to_string();
+    assert!(report.contains("x.count.val().index() != 5 at time 30000 ps in testbench 1"));
+    assert!(report.contains("x.count.val().index() = 3"));
+}
+
+#[test]
+fn test_failed_assertion_evaluates_once() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let mut sim = simple_sim!(Counter, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 3);
+        let next = || counter.fetch_add(1, Ordering::SeqCst);
+        sim_assert_eq!(ep, next(), 7, x);
+        ep.done(x)
+    });
+    let failure = match run(&mut sim) {
+        Err(SimError::SimHalted(failure)) => failure,
+        x => panic!("Expected the simulation to halt, got {:?}", x),
+    };
+    assert_eq!(failure.values[0], ("next()".to_string(), "0".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_testbench_panic_is_described() {
+    for executor in [Executor::Threaded, Executor::Cooperative] {
+        let mut sim = simple_sim!(Counter, clock, 100_000_000, ep, {
+            let mut x = ep.init()?;
+            wait_clock_cycles!(ep, clock, x, 2);
+            let count = x.count.val().index();
+            if count == 2 {
+                panic!("Count reached {}", count);
+            }
+            ep.done(x)
+        });
+        sim.set_executor(executor);
+        let failure = match run(&mut sim) {
+            Err(SimError::SimPanic(failure)) => failure,
+            x => panic!("Expected the simulation to panic, got {:?}", x),
+        };
+        assert_eq!(failure.message, "Count reached 2");
+        assert_eq!(failure.testbench, Some(1));
+        assert_eq!(failure.time, 20_000);
+        assert!(failure
+            .location
+            .unwrap()
+            .starts_with("rust-hdl/tests/core_sim_errors.rs:"));
+    }
+}
+
+#[test]
+fn test_clock_panic_is_described() {
+    let mut sim = Simulation::new();
+    sim.add_clock(5_000, |x: &mut Box<Counter>| {
+        if x.count.val() == 4 {
+            panic!("Clock stopped");
+        }
+        x.clock.next = !x.clock.val()
+    });
+    sim.add_testbench(|mut ep: Sim<Counter>| {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 10);
+        ep.done(x)
+    });
+    match run(&mut sim) {
+        Err(SimError::SimPanic(failure)) => {
+            assert_eq!(failure.message, "Clock stopped");
+            assert_eq!(failure.testbench, Some(0));
+        }
+        x => panic!("Expected the clock to panic, got {:?}", x),
+    }
+}
@@ -0,0 +1,106 @@
+use rust_hdl::prelude::*;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(LogicBlock, Default)]
+struct Clocked {
+    pub clock: Signal<In, Clock>,
+}
+
+impl Logic for Clocked {
+    #[hdl_gen]
+    fn update(&mut self) {}
+}
+
+// Record the times at which the clock rises and falls
+fn edges(sim: &mut Simulation<Clocked>, cycles: usize) -> Vec<(u64, bool)> {
+    let edges = Arc::new(Mutex::new(vec![]));
+    let record = edges.clone();
+    sim.add_testbench(move |mut ep: Sim<Clocked>| {
+        let mut x = ep.init()?;
+        for _ in 0..cycles {
+            wait_clock_true!(ep, clock, x);
+            record.lock().unwrap().push((ep.time(), true));
+            wait_clock_false!(ep, clock, x);
+            record.lock().unwrap().push((ep.time(), false));
+        }
+        ep.done(x)
+    });
+    sim.run(Box::new(Clocked::default()), 1_000_000).unwrap();
+    let ret = edges.lock().unwrap().clone();
+    ret
+}
+
+#[test]
+fn test_time_unit_conversions() {
+    assert_eq!(SimTime::from_us(3).ps(), 3_000_000);
+    assert_eq!(SimTime::from_ms(1), SimTime::from_us(1_000));
+    assert_eq!(
+        SimTime::from(Duration::from_nanos(25)),
+        SimTime::from_ps(25_000)
+    );
+    assert_eq!(
+        Duration::from(SimTime::from_ns(40)),
+        Duration::from_nanos(40)
+    );
+    assert_eq!(
+        SimTime::from_ns(3) * 2 + SimTime::from_ps(500),
+        SimTime::from_ps(6_500)
+    );
+    assert_eq!(SimTime::from_ps(6_500).ns(), 6.5);
+    assert_eq!(SimTime::from_ns(20).to_string(), "20 ns");
+    assert_eq!(SimTime::from_ps(1_500).to_string(), "1500 ps");
+    let clock = Frequency::from_mhz(48.0);
+    assert_eq!(clock.hz(), 48.0e6);
+    assert_eq!(clock.period().ps(), 20_833);
+    assert_eq!(clock.half_period().ps(), 10_417);
+    assert_eq!(clock.to_string(), "48 MHz");
+    assert_eq!(
+        Frequency::from_mhz(100.0).high_low(0.3),
+        (SimTime::from_ns(3), SimTime::from_ns(7))
+    );
+}
+
+#[test]
+fn test_frequency_clock_matches_half_period_clock() {
+    let mut sim = Simulation::new();
+    sim.add_clock_frequency(Frequency::from_mhz(50.0), |x: &mut Box<Clocked>| {
+        x.clock.next = !x.clock.val()
+    });
+    let by_frequency = edges(&mut sim, 3);
+    let mut sim = Simulation::new();
+    sim.add_clock(10_000, |x: &mut Box<Clocked>| x.clock.next = !x.clock.val());
+    assert_eq!(by_frequency, edges(&mut sim, 3));
+    assert_eq!(by_frequency[0], (10_000, true));
+}
+
+#[test]
+fn test_duty_cycle_clock() {
+    let mut sim = Simulation::new();
+    sim.add_clock_duty(Frequency::from_mhz(100.0), 0.25, |x: &mut Box<Clocked>| {
+        x.clock.next = !x.clock.val()
+    });
+    assert_eq!(
+        edges(&mut sim, 2),
+        vec![
+            (7_500, true),
+            (10_000, false),
+            (17_500, true),
+            (20_000, false)
+        ]
+    );
+}
+
+#[test]
+fn test_wait_for_duration() {
+    let mut sim = Simulation::new();
+    sim.add_testbench(|mut ep: Sim<Clocked>| {
+        let mut x = ep.init()?;
+        x = ep.wait_for(Duration::from_micros(2), x)?;
+        sim_assert_eq!(ep, ep.now(), SimTime::from_us(2), x);
+        x = ep.wait_for(SimTime::from_ns(15), x)?;
+        sim_assert_eq!(ep, ep.time(), 2_015_000, x);
+        ep.done(x)
+    });
+    sim.run(Box::new(Clocked::default()), 10_000_000).unwrap();
+}
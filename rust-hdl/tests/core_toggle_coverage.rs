@@ -0,0 +1,88 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Wrapper {
+    pub clock: Signal<In, Clock>,
+    pub enable: Signal<In, Bit>,
+    pub count: Signal<Out, Bits<4>>,
+    pub stuck: Signal<Out, Bit>,
+    counter: DFF<Bits<4>>,
+}
+
+impl Logic for Wrapper {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        if self.enable.val() {
+            self.counter.d.next = self.counter.q.val() + 1;
+        }
+        self.count.next = self.counter.q.val();
+        self.stuck.next = false;
+    }
+}
+
+fn run_coverage(cycles: usize) -> std::rc::Rc<std::cell::RefCell<ToggleCoverage>> {
+    let mut sim = simple_sim!(Wrapper, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        x.enable.next = true;
+        wait_clock_cycles!(ep, clock, x, cycles);
+        x.enable.next = false;
+        wait_clock_cycles!(ep, clock, x, 2);
+        ep.done(x)
+    });
+    let coverage = sim.add_observer(ToggleCoverage::default());
+    let mut uut = Wrapper::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 10 * sim_time::ONE_MICROSECOND)
+        .unwrap();
+    coverage
+}
+
+#[test]
+fn test_toggle_coverage_finds_untoggled_bits() {
+    // Counting to 3 and wrapping to 4 toggles bits 0 and 1 in both directions,
+    // while bit 2 only rises and bit 3 never moves.
+    let coverage = run_coverage(4);
+    let coverage = coverage.borrow();
+    let untoggled = coverage.untoggled();
+    let count = untoggled
+        .iter()
+        .filter(|x| x.signal == "uut.count")
+        .collect::<Vec<_>>();
+    assert_eq!(count.len(), 2);
+    assert_eq!(
+        *count[0],
+        UntoggledBit {
+            signal: "uut.count".into(),
+            bit: 2,
+            rose: true,
+            fell: false
+        }
+    );
+    assert_eq!(count[1].bit, 3);
+    assert!(!count[1].rose && !count[1].fell);
+    assert!(untoggled.iter().any(|x| x.signal == "uut.stuck"));
+    assert!(untoggled.iter().any(|x| x.signal == "uut.counter.q"));
+    assert!(!untoggled.iter().any(|x| x.signal == "uut.clock"));
+    assert!(!untoggled.iter().any(|x| x.signal == "uut.enable"));
+}
+
+#[test]
+fn test_toggle_coverage_summary() {
+    let coverage = run_coverage(16);
+    let coverage = coverage.borrow();
+    let summary = coverage.summary();
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].module, "uut");
+    // clock + enable + count (4) + stuck
+    assert_eq!(summary[0].bits, 7);
+    assert_eq!(summary[0].toggled, 6);
+    assert_eq!(summary[1].module, "uut.counter");
+    assert_eq!(summary[1].bits, 9);
+    assert_eq!(summary[1].toggled, 9);
+    let json = coverage.to_json();
+    assert!(json.contains("{\"signal\":\"uut.stuck\",\"bit\":0,\"rose\":false,\"fell\":false}"));
+    assert!(json.contains("{\"module\":\"uut.counter\",\"bits\":9,\"toggled\":9}"));
+    let report = coverage.to_string();
+    assert!(report.contains("uut.counter"));
+}
@@ -0,0 +1,141 @@
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Inner {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    counter: DFF<Bits<8>>,
+}
+
+impl Logic for Inner {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        self.counter.d.next = self.counter.q.val() + 1;
+        self.count.next = self.counter.q.val();
+    }
+}
+
+#[derive(LogicBlock, Default)]
+struct Outer {
+    pub clock: Signal<In, Clock>,
+    pub count: Signal<Out, Bits<8>>,
+    pub other: Signal<Out, Bits<8>>,
+    left: Inner,
+    right: Inner,
+}
+
+impl Logic for Outer {
+    #[hdl_gen]
+    fn update(&mut self) {
+        clock!(self, clock, left, right);
+        self.count.next = self.left.count.val();
+        self.other.next = self.right.count.val();
+    }
+}
+
+fn outer_sim() -> Simulation<Outer> {
+    simple_sim!(Outer, clock, 100_000_000, ep, {
+        let mut x = ep.init()?;
+        wait_clock_cycles!(ep, clock, x, 100);
+        ep.done(x)
+    })
+}
+
+fn run_with(sim: Simulation<Outer>) -> String {
+    let mut sim = sim;
+    let mut uut = Outer::default();
+    uut.connect_all();
+    let mut vcd = vec![];
+    sim.run_traced(Box::new(uut), 10 * sim_time::ONE_MICROSECOND, &mut vcd)
+        .unwrap();
+    String::from_utf8(vcd).unwrap()
+}
+
+fn var_names(vcd: &str) -> Vec<String> {
+    vcd.lines()
+        .filter(|x| x.starts_with("$var"))
+        .map(|x| x.split_whitespace().nth(4).unwrap().to_string())
+        .collect()
+}
+
+fn scope_names(vcd: &str) -> Vec<String> {
+    vcd.lines()
+        .filter(|x| x.starts_with("$scope"))
+        .map(|x| x.split_whitespace().nth(2).unwrap().to_string())
+        .collect()
+}
+
+fn timestamps(vcd: &str) -> Vec<u64> {
+    vcd.lines()
+        .filter_map(|x| x.strip_prefix('#'))
+        .map(|x| x.parse().unwrap())
+        .collect()
+}
+
+#[test]
+fn test_trace_filter_paths() {
+    let mut sim = outer_sim();
+    sim.set_trace_filter(TraceFilter {
+        paths: vec!["uut.left*".into()],
+        ..Default::default()
+    });
+    let vcd = run_with(sim);
+    let names = var_names(&vcd);
+    assert!(names.contains(&"count".to_string()));
+    assert!(names.contains(&"q".to_string()));
+    assert!(!names.contains(&"other".to_string()));
+    // Only the signals in `left` are kept: clock, count, and the DFF clock, d and q
+    assert_eq!(names.len(), 5);
+    // Modules with no signals left in them are not written at all
+    assert_eq!(scope_names(&vcd), vec!["uut", "left", "counter"]);
+}
+
+#[test]
+fn test_trace_filter_depth() {
+    let mut sim = outer_sim();
+    sim.set_trace_filter(TraceFilter {
+        max_depth: Some(0),
+        ..Default::default()
+    });
+    let vcd = run_with(sim);
+    assert_eq!(var_names(&vcd), vec!["clock", "count", "other"]);
+}
+
+#[test]
+fn test_trace_filter_window() {
+    let mut sim = outer_sim();
+    sim.set_trace_filter(TraceFilter {
+        start_time: 200_000,
+        stop_time: Some(500_000),
+        ..Default::default()
+    });
+    let vcd = run_with(sim);
+    let times = timestamps(&vcd);
+    assert_eq!(*times.first().unwrap(), 200_000);
+    assert_eq!(*times.last().unwrap(), 500_000);
+    // The first record in the window is a full dump
+    assert!(vcd.contains("$dumpvars"));
+}
+
+#[test]
+fn test_trace_trigger() {
+    let mut sim = outer_sim();
+    sim.set_trace_trigger(|x: &Outer| x.count.val() == 10);
+    let vcd = run_with(sim);
+    let times = timestamps(&vcd);
+    // The 10th rising edge of the clock is at 95ns
+    assert_eq!(times[0], 95_000);
+    assert!(vcd.contains("$dumpvars"));
+}
+
+#[test]
+fn test_unfiltered_trace_is_unchanged() {
+    let vcd = run_with(outer_sim());
+    let names = var_names(&vcd);
+    assert_eq!(names.len(), 13);
+    // The initial state is dumped without a timestamp, as before
+    let body = vcd.split("$enddefinitions $end").nth(1).unwrap();
+    assert!(body.trim_start().starts_with("$dumpvars"));
+    assert_eq!(timestamps(&vcd)[0], 5_000);
+}
@@ -0,0 +1,264 @@
+use rand::Rng;
+use rust_hdl::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Mode {
+    Hold,
+    Add,
+    Load,
+}
+
+#[derive(LogicBlock, Default)]
+struct Accumulator {
+    pub clock: Signal<In, Clock>,
+    pub mode: Signal<In, Mode>,
+    pub data: Signal<In, Bits<8>>,
+    pub total: Signal<Out, Bits<8>>,
+    store: DFF<Bits<8>>,
+}
+
+impl Logic for Accumulator {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        match self.mode.val() {
+            Mode::Hold => {}
+            Mode::Add => self.store.d.next = self.store.q.val() + self.data.val(),
+            Mode::Load => self.store.d.next = self.data.val(),
+            _ => {}
+        }
+        self.total.next = self.store.q.val();
+    }
+}
+
+fn accumulator() -> Box<Accumulator> {
+    let mut uut = Accumulator::default();
+    uut.clock.connect();
+    uut.mode.connect();
+    uut.data.connect();
+    Box::new(uut)
+}
+
+// Record the output of the accumulator at every time step
+#[derive(Default)]
+struct Totals(Vec<(u64, u8)>);
+
+impl Monitor<Accumulator> for Totals {
+    fn sample(&mut self, time: u64, x: &Accumulator) {
+        let total = x.total.val().index() as u8;
+        if self.0.last().map(|x| x.1) != Some(total) {
+            self.0.push((time, total));
+        }
+    }
+}
+
+#[test]
+fn test_replay_of_a_simulation_trace_matches_the_original() {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<Accumulator>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut ep: Sim<Accumulator>| {
+        let mut x = ep.init()?;
+        for _ in 0..50 {
+            x.mode.next = match sim_rng().gen_range(0..3) {
+                0 => Mode::Hold,
+                1 => Mode::Add,
+                _ => Mode::Load,
+            };
+            x.data.next = sim_rng().gen::<u8>().to_bits();
+            wait_clock_cycle!(ep, clock, x);
+        }
+        ep.done(x)
+    });
+    let original = sim.add_monitor(Totals::default());
+    let mut trace = vec![];
+    sim.run_traced(accumulator(), 100_000, &mut trace).unwrap();
+    let original = original.borrow().0.clone();
+    assert!(original.len() > 10);
+
+    let replay = VCDReplay::parse(trace.as_slice()).unwrap();
+    let mut sim = Simulation::new();
+    sim.add_vcd_replay(replay.clone());
+    let replayed = sim.add_monitor(Totals::default());
+    sim.run(accumulator(), 100_000).unwrap();
+    assert_eq!(replayed.borrow().0, original);
+    // Only the top level inputs are driven
+    let matches = replay.matches(accumulator().as_ref());
+    assert_eq!(
+        matches,
+        vec![
+            ("uut.clock".to_string(), "uut.clock".to_string()),
+            ("uut.mode".to_string(), "uut.mode".to_string()),
+            ("uut.data".to_string(), "uut.data".to_string()),
+        ]
+    );
+}
+
+const CAPTURE: &str = "$timescale 10 ns $end
+$scope module analyzer $end
+$scope module board $end
+$var wire 1 ! clk $end
+$var wire 8 \" bus [7:0] $end
+$var wire 1 # mode $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+#0
+0!
+b00000011 \"
+#1
+1!
+#2
+0!
+b0000x101 \"
+#3
+1!
+#4
+0!
+";
+
+#[test]
+fn test_replay_of_a_capture_with_renamed_signals() {
+    let replay = VCDReplay::parse(CAPTURE.as_bytes()).unwrap();
+    assert_eq!(replay.unknowns(), vec!["analyzer.board.bus"]);
+    assert_eq!(
+        replay.signals(),
+        vec![
+            "analyzer.board.clk",
+            "analyzer.board.bus",
+            "analyzer.board.mode"
+        ]
+    );
+    // The names in the capture do not match the circuit
+    assert!(replay.matches(accumulator().as_ref()).is_empty());
+    let replay = replay
+        .root("analyzer.board")
+        .rename("analyzer.board.clk", "uut.clock")
+        .rename("analyzer.board.bus", "uut.data");
+    // With the new root, the mode is matched by name (it never changes in the capture)
+    assert_eq!(
+        replay.matches(accumulator().as_ref()),
+        vec![
+            ("analyzer.board.clk".to_string(), "uut.clock".to_string()),
+            ("analyzer.board.bus".to_string(), "uut.data".to_string()),
+            ("analyzer.board.mode".to_string(), "uut.mode".to_string()),
+        ]
+    );
+    let mut sim = Simulation::new();
+    sim.add_vcd_replay(replay);
+    sim.add_testbench(|mut ep: Sim<Accumulator>| {
+        let mut x = ep.init()?;
+        x.mode.next = Mode::Add;
+        // The capture has a timescale of 10ns, so the clock rises at 10ns and 30ns
+        x = ep.wait(10_001, x)?;
+        sim_assert_eq!(ep, x.total.val(), 3, x);
+        x = ep.wait(20_000, x)?;
+        // The unknown bit is replayed as a zero
+        sim_assert_eq!(ep, x.total.val(), 8, x);
+        ep.done(x)
+    });
+    sim.run(accumulator(), 100_000).unwrap();
+}
+
+#[test]
+fn test_replay_with_no_matching_signals_fails() {
+    let mut sim = Simulation::new();
+    sim.add_vcd_replay(VCDReplay::parse(CAPTURE.as_bytes()).unwrap());
+    let result = sim.run(accumulator(), 100_000);
+    assert!(matches!(result, Err(SimError::SimHalted(_))));
+}
+
+#[test]
+fn test_replay_with_a_misspelled_signal_fails() {
+    let replay = VCDReplay::parse(CAPTURE.as_bytes())
+        .unwrap()
+        .root("analyzer.board")
+        .rename("analyzer.board.clk", "uut.clk")
+        .rename("analyzer.board.bus", "uut.data");
+    assert_eq!(
+        replay.unmatched(accumulator().as_ref()),
+        vec!["analyzer.board.clk"]
+    );
+    let mut sim = Simulation::new();
+    sim.add_vcd_replay(replay.clone());
+    let result = sim.run(accumulator(), 100_000);
+    assert!(matches!(result, Err(SimError::SimHalted(_))));
+    // Once the signal is left out, the rest of the capture drives the circuit
+    let replay = replay.ignore("analyzer.board.clk");
+    assert!(replay.unmatched(accumulator().as_ref()).is_empty());
+    assert_eq!(replay.matches(accumulator().as_ref()).len(), 2);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default, LogicStruct)]
+struct Command {
+    mode: Mode,
+    data: Bits<8>,
+}
+
+#[derive(LogicBlock, Default)]
+struct CommandAccumulator {
+    pub clock: Signal<In, Clock>,
+    pub cmd: Signal<In, Command>,
+    pub total: Signal<Out, Bits<8>>,
+    store: DFF<Bits<8>>,
+}
+
+impl Logic for CommandAccumulator {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, store);
+        match self.cmd.val().mode {
+            Mode::Hold => {}
+            Mode::Add => self.store.d.next = self.store.q.val() + self.cmd.val().data,
+            Mode::Load => self.store.d.next = self.cmd.val().data,
+            _ => {}
+        }
+        self.total.next = self.store.q.val();
+    }
+}
+
+fn command_accumulator() -> Box<CommandAccumulator> {
+    let mut uut = CommandAccumulator::default();
+    uut.clock.connect();
+    uut.cmd.connect();
+    Box::new(uut)
+}
+
+#[test]
+fn test_replay_drives_the_fields_of_a_struct_input() {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<CommandAccumulator>| {
+        x.clock.next = !x.clock.val()
+    });
+    sim.add_testbench(|mut ep: Sim<CommandAccumulator>| {
+        let mut x = ep.init()?;
+        for (mode, data) in [
+            (Mode::Load, 3),
+            (Mode::Add, 4),
+            (Mode::Hold, 9),
+            (Mode::Add, 5),
+        ] {
+            x.cmd.next.mode = mode;
+            x.cmd.next.data = data.into();
+            wait_clock_cycle!(ep, clock, x);
+        }
+        ep.done(x)
+    });
+    let mut trace = vec![];
+    sim.run_traced(command_accumulator(), 100_000, &mut trace)
+        .unwrap();
+
+    let replay = VCDReplay::parse(trace.as_slice()).unwrap();
+    let matches = replay.matches(command_accumulator().as_ref());
+    assert!(matches.contains(&("uut.cmd$mode".to_string(), "uut.cmd$mode".to_string())));
+    assert!(matches.contains(&("uut.cmd$data".to_string(), "uut.cmd$data".to_string())));
+    assert!(replay.unmatched(command_accumulator().as_ref()).is_empty());
+    let mut sim = Simulation::new();
+    sim.add_vcd_replay(replay);
+    sim.add_testbench(|mut ep: Sim<CommandAccumulator>| {
+        let mut x = ep.init()?;
+        x = ep.wait(50_000, x)?;
+        sim_assert_eq!(ep, x.total.val(), 12, x);
+        ep.done(x)
+    });
+    sim.run(command_accumulator(), 100_000).unwrap();
+}
@@ -0,0 +1,176 @@
+use rust_hdl::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, LogicState)]
+enum Mode {
+    Idle,
+    Load,
+    Run,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Default, LogicStruct)]
+struct Command {
+    mode: Mode,
+    count: Bits<12>,
+    offset: Bits<8>,
+}
+
+#[derive(LogicInterface, Default, Clone)]
+struct Bus {
+    pub data: Signal<In, Bits<8>>,
+    pub valid: Signal<In, Bit>,
+}
+
+#[derive(LogicBlock, Default, Clone)]
+struct Counter {
+    pub clock: Signal<In, Clock>,
+    pub bus: Bus,
+    pub total: Signal<Out, Bits<72>>,
+    counter: DFF<Bits<72>>,
+}
+
+impl Logic for Counter {
+    #[hdl_gen]
+    fn update(&mut self) {
+        dff_setup!(self, clock, counter);
+        if self.bus.valid.val() {
+            self.counter.d.next = self.counter.q.val() + bit_cast::<72, 8>(self.bus.data.val());
+        }
+        self.total.next = self.counter.q.val();
+    }
+}
+
+fn counter_sim() -> Simulation<Counter> {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<Counter>| x.clock.next = !x.clock.val());
+    sim.add_testbench(|mut ep: Sim<Counter>| {
+        let mut x = ep.init()?;
+        for value in 1..=10_u64 {
+            x.bus.data.next = value.into();
+            x.bus.valid.next = true;
+            wait_clock_cycle!(ep, clock, x);
+        }
+        x.bus.valid.next = false;
+        wait_clock_cycle!(ep, clock, x);
+        sim_assert_eq!(ep, x.total.val(), 55, x);
+        ep.done(x)
+    });
+    sim
+}
+
+#[test]
+fn test_from_bits_round_trip() {
+    let cmd = Command {
+        mode: Mode::Run,
+        count: 1234.into(),
+        offset: 0xA5.into(),
+    };
+    assert_eq!(Command::from_bits(&cmd.verilog().as_biguint()), Some(cmd));
+    assert_eq!(
+        Mode::from_bits(&Mode::Load.verilog().as_biguint()),
+        Some(Mode::Load)
+    );
+    // Mode has 3 variants in 2 bits, so the pattern 3 is not a valid Mode
+    assert_eq!(Mode::from_bits(&3_u32.into()), None);
+    let offset: Signed<8> = (-7).into();
+    assert_eq!(Signed::<8>::from_bits(&offset.verilog().as_biguint()), Some(offset));
+    // Values that do not fit are rejected
+    assert_eq!(Bits::<4>::from_bits(&16_u32.into()), None);
+}
+
+#[test]
+fn test_verilator_harness_maps_top_level_ports() {
+    let mut uut = Counter::default();
+    uut.connect_all();
+    let harness = verilator_harness(&uut);
+    assert!(harness.contains("case 0: top->clock = words[0];"));
+    assert!(harness.contains("case 1: top->bus__024data = words[0];"));
+    // Wide ports are exchanged a word at a time
+    assert!(harness.contains("case 3: for (int i = 0; i < 3; i++) words[i] = top->total[i];"));
+    // Internal signals are not ports
+    assert!(!harness.contains("counter"));
+}
+
+// A backend that ignores the Rust model, and implements an accumulator that
+// sums the data on every cycle, whether or not it is valid
+struct Accumulate {
+    sum: u64,
+}
+
+struct AccumulatePorts<'a> {
+    backend: &'a mut Accumulate,
+    clock_edge: bool,
+    changed: bool,
+}
+
+impl<'a> ProbeMut for AccumulatePorts<'a> {
+    fn visit_atom(&mut self, name: &str, signal: &mut dyn AtomMut) {
+        match name {
+            "clock" => {
+                let next = signal.next_bits();
+                self.clock_edge = signal.set_bits(&next) && next == 1_u32.into();
+                self.changed |= self.clock_edge;
+            }
+            "data" => {
+                let next = signal.next_bits();
+                signal.set_bits(&next);
+                if self.clock_edge {
+                    self.backend.sum += next.to_u64_digits().first().copied().unwrap_or(0);
+                }
+            }
+            "total" => {
+                self.changed |= signal.set_bits(&self.backend.sum.into());
+            }
+            _ => {}
+        }
+    }
+}
+
+impl SimBackend<Counter> for Accumulate {
+    fn evaluate(&mut self, x: &mut Counter) -> bool {
+        let mut ports = AccumulatePorts {
+            backend: self,
+            clock_edge: false,
+            changed: false,
+        };
+        x.accept_mut("top", &mut ports);
+        ports.changed
+    }
+}
+
+#[test]
+fn test_sim_backend_replaces_the_rust_model() {
+    let mut sim = Simulation::new();
+    sim.add_clock(5, |x: &mut Box<Counter>| x.clock.next = !x.clock.val());
+    sim.set_backend(Accumulate { sum: 0 });
+    sim.add_testbench(|mut ep: Sim<Counter>| {
+        let mut x = ep.init()?;
+        x.bus.data.next = 3.into();
+        x.bus.valid.next = false;
+        wait_clock_cycles!(ep, clock, x, 4);
+        // The Rust model would not have accumulated anything
+        sim_assert_eq!(ep, x.total.val(), 12, x);
+        sim_assert_eq!(ep, x.counter.q.val(), 0, x);
+        ep.done(x)
+    });
+    let mut uut = Counter::default();
+    uut.connect_all();
+    sim.run(Box::new(uut), 1_000).unwrap();
+}
+
+#[test]
+fn test_rust_model_baseline() {
+    let mut uut = Counter::default();
+    uut.connect_all();
+    counter_sim().run(Box::new(uut), 1_000).unwrap();
+}
+
+// Run with `cargo test -p rust-hdl --test core_verilator -- --ignored`
+#[test]
+#[ignore = "requires verilator"]
+fn test_verilator_cosim_matches_rust_model() {
+    let mut uut = Counter::default();
+    uut.connect_all();
+    let mut sim = counter_sim();
+    sim.set_backend(VerilatorModel::new("verilator_counter", &uut).unwrap());
+    sim.run(Box::new(uut), 1_000).unwrap();
+}
@@ -0,0 +1,102 @@
+use rust_hdl::core::check_error::{CheckError, PathedName};
+use rust_hdl::prelude::*;
+
+#[derive(LogicBlock, Default)]
+struct Mismatched {
+    pub wide: Signal<In, Bits<16>>,
+    pub narrow: Signal<Out, Bits<8>>,
+    pub top_bit: Signal<Out, Bit>,
+    pub big: Signal<Out, Bit>,
+    pub limit: Signal<Out, Bits<4>>,
+    pub widened: Signal<Out, Bits<16>>,
+}
+
+impl Logic for Mismatched {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.narrow.next = self.wide.val().get_bits::<8>(12);
+        self.top_bit.next = self.wide.val().get_bit(16);
+        self.big.next = self.narrow.val() == 300;
+        self.limit.next = 20.into();
+        self.widened.next = bit_cast::<16, 8>(self.narrow.val());
+    }
+}
+
+fn mismatch(name: &str, kind: WidthMismatchKind, expected: usize, actual: usize) -> WidthMismatch {
+    WidthMismatch {
+        signal: PathedName {
+            path: "uut".into(),
+            name: name.into(),
+        },
+        kind,
+        expected,
+        actual,
+    }
+}
+
+#[test]
+fn test_width_mismatches_are_reported() {
+    let mut uut = Mismatched::default();
+    uut.connect_all();
+    assert_eq!(
+        check_widths(&uut),
+        Err(CheckError::WidthMismatches(vec![
+            mismatch("wide", WidthMismatchKind::OutOfRange, 16, 20),
+            mismatch("wide", WidthMismatchKind::OutOfRange, 16, 17),
+            mismatch("narrow", WidthMismatchKind::Comparison, 8, 9),
+            mismatch("limit", WidthMismatchKind::Truncation, 4, 5),
+        ]))
+    );
+}
+
+#[test]
+fn test_width_mismatches_can_be_allowed() {
+    let mut uut = Mismatched::default();
+    uut.connect_all();
+    let check = WidthCheck::default().allow("uut.wide").allow("uut.narrow");
+    assert_eq!(
+        check.check(&uut),
+        Err(CheckError::WidthMismatches(vec![mismatch(
+            "limit",
+            WidthMismatchKind::Truncation,
+            4,
+            5
+        )]))
+    );
+    assert!(check.allow("uut.l*").check(&uut).is_ok());
+}
+
+#[test]
+fn test_async_fifo_widths_match() {
+    let mut uut: AsynchronousFIFO<Bits<8>, 4, 5, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_widths(&uut).is_ok());
+    let mut uut: SynchronousFIFO<Bits<16>, 6, 7, 1> = Default::default();
+    uut.connect_all();
+    assert!(check_widths(&uut).is_ok());
+}
+
+#[derive(LogicBlock, Default)]
+struct CastOperands {
+    pub wide: Signal<In, Bits<16>>,
+    pub sum: Signal<Out, Bits<8>>,
+    pub shifted: Signal<Out, Bits<8>>,
+}
+
+impl Logic for CastOperands {
+    #[hdl_gen]
+    fn update(&mut self) {
+        self.sum.next = bit_cast::<8, 16>(self.wide.val()) + 1;
+        self.shifted.next = bit_cast::<8, 16>(self.wide.val()) << 2;
+    }
+}
+
+#[test]
+fn test_cast_binds_tighter_than_the_operator_it_feeds() {
+    let mut uut = CastOperands::default();
+    uut.connect_all();
+    let vlog = generate_verilog(&uut);
+    // The mask has to be applied before the addition and the shift
+    assert!(vlog.contains("sum = ((wide) & 8'hff) + 32'h1;"));
+    assert!(vlog.contains("shifted = ((wide) & 8'hff) << 32'h2;"));
+}